@@ -10,7 +10,10 @@ extern crate log;
 use criterion::black_box;
 use criterion::Criterion;
 
-use librustbuster::fuzzbuster::{FuzzBuster, FuzzRequest};
+use librustbuster::fuzzbuster::{FuzzBuster, FuzzMode, FuzzRequest};
+use librustbuster::output::OutputFormat;
+use librustbuster::resume::ResumeState;
+use librustbuster::wordlist::WordlistOptions;
 
 fn fuzzrequest_body() -> FuzzRequest {
     FuzzRequest {
@@ -19,7 +22,7 @@ fn fuzzrequest_body() -> FuzzRequest {
         http_headers: vec![],
         http_body: "CSRFCSRF".to_owned(),
         user_agent: "ua".to_owned(),
-        payload: vec!["1".to_owned()],
+        payload: vec![("FUZZ".to_owned(), "1".to_owned())].into_iter().collect(),
         csrf_uri: Some("http://localhost/".parse::<hyper::Uri>().unwrap()),
         csrf_regex: Some("(\\w+)".to_owned()),
         csrf_headers: None,
@@ -33,7 +36,7 @@ fn fuzzrequest_header() -> FuzzRequest {
         http_headers: vec![("X-CSRF-Token".to_owned(), "CSRFCSRF".to_owned())],
         http_body: "body".to_owned(),
         user_agent: "ua".to_owned(),
-        payload: vec!["1".to_owned()],
+        payload: vec![("FUZZ".to_owned(), "1".to_owned())].into_iter().collect(),
         csrf_uri: Some("http://localhost/".parse::<hyper::Uri>().unwrap()),
         csrf_regex: Some("(\\w+)".to_owned()),
         csrf_headers: None,
@@ -47,7 +50,7 @@ fn fuzzrequest_url() -> FuzzRequest {
         http_headers: vec![],
         http_body: "body".to_owned(),
         user_agent: "ua".to_owned(),
-        payload: vec!["1".to_owned()],
+        payload: vec![("FUZZ".to_owned(), "1".to_owned())].into_iter().collect(),
         csrf_uri: Some("http://localhost/".parse::<hyper::Uri>().unwrap()),
         csrf_regex: Some("(\\w+)".to_owned()),
         csrf_headers: None,
@@ -63,17 +66,31 @@ fn fuzzbuster_url_single() -> FuzzBuster {
         user_agent: "ua".to_owned(),
         http_headers: vec![("Header".to_owned(), "Value".to_owned())],
         wordlist_paths: vec!["./examples/wordlist_short".to_owned()],
+        wordlist_keywords: vec![None],
+        wordlist_encoders: vec![],
+        wordlist_options: WordlistOptions::default(),
         url: "http://localhost/FUZZ".to_owned(),
-        include_status_codes: vec![],
-        ignore_status_codes: vec!["404".to_owned()],
-        include_body: vec![],
-        ignore_body: vec![],
+        targets: vec![],
+        host_threads: 0,
+        matcher: librustbuster::matcher::Matcher::default(),
         no_progress_bar: true,
         exit_on_connection_errors: false,
         output: "".to_owned(),
+        output_format: OutputFormat::Json,
         csrf_url: None,
         csrf_regex: None,
         csrf_headers: None,
+        auto_calibrate: false,
+        mode: FuzzMode::ClusterBomb,
+        default_value: "".to_owned(),
+        proxy: "".to_owned(),
+        replay_proxy: "".to_owned(),
+        requeue: false,
+        rate: 0,
+        delay: None,
+        timeout: 10000,
+        retries: 2,
+        resume: ResumeState::default(),
     }
 }
 
@@ -89,17 +106,31 @@ fn fuzzbuster_url_multiple() -> FuzzBuster {
             "./examples/wordlist_short".to_owned(),
             "./examples/wordlist_short".to_owned(),
         ],
+        wordlist_keywords: vec![None, None],
+        wordlist_encoders: vec![],
+        wordlist_options: WordlistOptions::default(),
         url: "http://localhost/FUZZ/FUZZ".to_owned(),
-        include_status_codes: vec![],
-        ignore_status_codes: vec!["404".to_owned()],
-        include_body: vec![],
-        ignore_body: vec![],
+        targets: vec![],
+        host_threads: 0,
+        matcher: librustbuster::matcher::Matcher::default(),
         no_progress_bar: true,
         exit_on_connection_errors: false,
         output: "".to_owned(),
+        output_format: OutputFormat::Json,
         csrf_url: None,
         csrf_regex: None,
         csrf_headers: None,
+        auto_calibrate: false,
+        mode: FuzzMode::ClusterBomb,
+        default_value: "".to_owned(),
+        proxy: "".to_owned(),
+        replay_proxy: "".to_owned(),
+        requeue: false,
+        rate: 0,
+        delay: None,
+        timeout: 10000,
+        retries: 2,
+        resume: ResumeState::default(),
     }
 }
 
@@ -112,17 +143,31 @@ fn fuzzbuster_header_single() -> FuzzBuster {
         user_agent: "ua".to_owned(),
         http_headers: vec![("Header".to_owned(), "FUZZ".to_owned())],
         wordlist_paths: vec!["./examples/wordlist_short".to_owned()],
+        wordlist_keywords: vec![None],
+        wordlist_encoders: vec![],
+        wordlist_options: WordlistOptions::default(),
         url: "http://localhost/".to_owned(),
-        include_status_codes: vec![],
-        ignore_status_codes: vec!["404".to_owned()],
-        include_body: vec![],
-        ignore_body: vec![],
+        targets: vec![],
+        host_threads: 0,
+        matcher: librustbuster::matcher::Matcher::default(),
         no_progress_bar: true,
         exit_on_connection_errors: false,
         output: "".to_owned(),
+        output_format: OutputFormat::Json,
         csrf_url: None,
         csrf_regex: None,
         csrf_headers: None,
+        auto_calibrate: false,
+        mode: FuzzMode::ClusterBomb,
+        default_value: "".to_owned(),
+        proxy: "".to_owned(),
+        replay_proxy: "".to_owned(),
+        requeue: false,
+        rate: 0,
+        delay: None,
+        timeout: 10000,
+        retries: 2,
+        resume: ResumeState::default(),
     }
 }
 
@@ -138,17 +183,31 @@ fn fuzzbuster_header_multiple() -> FuzzBuster {
             "./examples/wordlist_short".to_owned(),
             "./examples/wordlist_short".to_owned(),
         ],
+        wordlist_keywords: vec![None, None],
+        wordlist_encoders: vec![],
+        wordlist_options: WordlistOptions::default(),
         url: "http://localhost/".to_owned(),
-        include_status_codes: vec![],
-        ignore_status_codes: vec!["404".to_owned()],
-        include_body: vec![],
-        ignore_body: vec![],
+        targets: vec![],
+        host_threads: 0,
+        matcher: librustbuster::matcher::Matcher::default(),
         no_progress_bar: true,
         exit_on_connection_errors: false,
         output: "".to_owned(),
+        output_format: OutputFormat::Json,
         csrf_url: None,
         csrf_regex: None,
         csrf_headers: None,
+        auto_calibrate: false,
+        mode: FuzzMode::ClusterBomb,
+        default_value: "".to_owned(),
+        proxy: "".to_owned(),
+        replay_proxy: "".to_owned(),
+        requeue: false,
+        rate: 0,
+        delay: None,
+        timeout: 10000,
+        retries: 2,
+        resume: ResumeState::default(),
     }
 }
 
@@ -161,17 +220,31 @@ fn fuzzbuster_body_single() -> FuzzBuster {
         user_agent: "ua".to_owned(),
         http_headers: vec![("Header".to_owned(), "Value".to_owned())],
         wordlist_paths: vec!["./examples/wordlist_short".to_owned()],
+        wordlist_keywords: vec![None],
+        wordlist_encoders: vec![],
+        wordlist_options: WordlistOptions::default(),
         url: "http://localhost/".to_owned(),
-        include_status_codes: vec![],
-        ignore_status_codes: vec!["404".to_owned()],
-        include_body: vec![],
-        ignore_body: vec![],
+        targets: vec![],
+        host_threads: 0,
+        matcher: librustbuster::matcher::Matcher::default(),
         no_progress_bar: true,
         exit_on_connection_errors: false,
         output: "".to_owned(),
+        output_format: OutputFormat::Json,
         csrf_url: None,
         csrf_regex: None,
         csrf_headers: None,
+        auto_calibrate: false,
+        mode: FuzzMode::ClusterBomb,
+        default_value: "".to_owned(),
+        proxy: "".to_owned(),
+        replay_proxy: "".to_owned(),
+        requeue: false,
+        rate: 0,
+        delay: None,
+        timeout: 10000,
+        retries: 2,
+        resume: ResumeState::default(),
     }
 }
 
@@ -187,17 +260,31 @@ fn fuzzbuster_body_multiple() -> FuzzBuster {
             "./examples/wordlist_short".to_owned(),
             "./examples/wordlist_short".to_owned(),
         ],
+        wordlist_keywords: vec![None, None],
+        wordlist_encoders: vec![],
+        wordlist_options: WordlistOptions::default(),
         url: "http://localhost/".to_owned(),
-        include_status_codes: vec![],
-        ignore_status_codes: vec!["404".to_owned()],
-        include_body: vec![],
-        ignore_body: vec![],
+        targets: vec![],
+        host_threads: 0,
+        matcher: librustbuster::matcher::Matcher::default(),
         no_progress_bar: true,
         exit_on_connection_errors: false,
         output: "".to_owned(),
+        output_format: OutputFormat::Json,
         csrf_url: None,
         csrf_regex: None,
         csrf_headers: None,
+        auto_calibrate: false,
+        mode: FuzzMode::ClusterBomb,
+        default_value: "".to_owned(),
+        proxy: "".to_owned(),
+        replay_proxy: "".to_owned(),
+        requeue: false,
+        rate: 0,
+        delay: None,
+        timeout: 10000,
+        retries: 2,
+        resume: ResumeState::default(),
     }
 }
 
@@ -221,22 +308,22 @@ fn criterion_benchmark(c: &mut Criterion) {
         })
     });
     c.bench_function("build_requests_url_single", |b| {
-        b.iter(|| fuzzbuster_url_single().build_requests())
+        b.iter(|| fuzzbuster_url_single().build_requests().collect::<Vec<_>>())
     });
     c.bench_function("build_requests_url_multiple", |b| {
-        b.iter(|| fuzzbuster_url_multiple().build_requests())
+        b.iter(|| fuzzbuster_url_multiple().build_requests().collect::<Vec<_>>())
     });
     c.bench_function("build_requests_header_single", |b| {
-        b.iter(|| fuzzbuster_header_single().build_requests())
+        b.iter(|| fuzzbuster_header_single().build_requests().collect::<Vec<_>>())
     });
     c.bench_function("build_requests_header_multiple", |b| {
-        b.iter(|| fuzzbuster_header_multiple().build_requests())
+        b.iter(|| fuzzbuster_header_multiple().build_requests().collect::<Vec<_>>())
     });
     c.bench_function("build_requests_body_single", |b| {
-        b.iter(|| fuzzbuster_body_single().build_requests())
+        b.iter(|| fuzzbuster_body_single().build_requests().collect::<Vec<_>>())
     });
     c.bench_function("build_requests_body_multiple", |b| {
-        b.iter(|| fuzzbuster_body_multiple().build_requests())
+        b.iter(|| fuzzbuster_body_multiple().build_requests().collect::<Vec<_>>())
     });
 }
 
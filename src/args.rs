@@ -1,51 +1,104 @@
 use clap::{App, Arg};
+use std::net::SocketAddr;
 use terminal_size::{terminal_size, Height, Width};
 
+use crate::dnsbuster::takeover::Fingerprint;
+use crate::encoder::{split_encoders, Encoder};
+use crate::fuzzbuster::FuzzMode;
+use crate::generator::Generator;
+use crate::matcher::{Combinator, Matcher, NumericRange, Pattern, Rule};
+use crate::output::OutputFormat;
+use crate::wordlist::{WordCase, WordlistOptions, STDIN};
+
 pub struct CommonArgs {
     pub no_banner: bool,
     pub no_progress_bar: bool,
     pub exit_on_connection_errors: bool,
     pub n_threads: usize,
     pub output: String,
+    pub output_format: OutputFormat,
+    pub rate: u64,
+    pub delay: Option<NumericRange>,
 }
 
 pub struct WordlistArgs {
     pub wordlist_paths: Vec<String>,
+    pub wordlist_keywords: Vec<Option<String>>,
+    pub wordlist_encoders: Vec<Vec<Encoder>>,
+    pub wordlist_options: WordlistOptions,
+}
+
+pub struct TargetsArgs {
+    pub targets: Vec<String>,
+    pub host_threads: usize,
 }
 
 pub struct DNSArgs {
     pub domain: String,
 }
 
+pub struct ResolverArgs {
+    pub resolvers: Vec<SocketAddr>,
+    pub timeout: u64,
+    pub retries: usize,
+    pub record_types: Vec<u16>,
+    pub zone_transfer: bool,
+    pub takeover_fingerprints: Option<Vec<Fingerprint>>,
+}
+
 pub struct HTTPArgs {
     pub user_agent: String,
-    pub http_method: String,
-    pub http_body: String,
     pub url: String,
     pub ignore_certificate: bool,
     pub http_headers: Vec<(String, String)>,
-    pub include_status_codes: Vec<String>,
-    pub ignore_status_codes: Vec<String>,
+    pub proxy: String,
+    pub timeout: u64,
+    pub retries: usize,
+}
+
+pub struct RequestArgs {
+    pub http_method: String,
+    pub http_body: String,
+}
+
+pub struct ReplayArgs {
+    pub replay_proxy: String,
+}
+
+pub struct RequeueArgs {
+    pub requeue: bool,
 }
 
-pub struct BodyArgs {
-    pub include_strings: Vec<String>,
-    pub ignore_strings: Vec<String>,
+pub struct MatcherArgs {
+    pub matcher: Matcher,
 }
 
 pub struct DirArgs {
     pub append_slash: bool,
     pub extensions: Vec<String>,
+    pub recursion_depth: usize,
+}
+
+pub struct CalibrationArgs {
+    pub auto_calibrate: bool,
 }
 
 pub struct TildeArgs {
     pub extension: Option<String>,
 }
 
+pub struct CrawlArgs {
+    pub depth: usize,
+    pub min_length: usize,
+    pub min_frequency: usize,
+}
+
 pub struct FuzzArgs {
     pub csrf_url: Option<String>,
     pub csrf_regex: Option<String>,
     pub csrf_headers: Option<Vec<(String, String)>>,
+    pub mode: FuzzMode,
+    pub default_value: String,
 }
 
 pub fn set_common_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
@@ -70,6 +123,20 @@ pub fn set_common_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
             .default_value("10")
             .takes_value(true),
     )
+    .arg(
+        Arg::with_name("rate")
+            .long("rate")
+            .help("Limits the amount of requests per second (0 for unlimited)")
+            .default_value("0")
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("delay")
+            .long("delay")
+            .help("Waits a random delay in the specified range of ms before each request (e.g. 100-500)")
+            .default_value("")
+            .takes_value(true),
+    )
     .arg(
         Arg::with_name("exit-on-error")
             .long("exit-on-error")
@@ -93,22 +160,6 @@ pub fn set_common_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
 
 pub fn set_http_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
     app.arg(
-        Arg::with_name("include-status-codes")
-            .long("include-status-codes")
-            .help("Sets the list of status codes to include")
-            .short("s")
-            .default_value("")
-            .use_delimiter(true),
-    )
-    .arg(
-        Arg::with_name("ignore-status-codes")
-            .long("ignore-status-codes")
-            .help("Sets the list of status codes to ignore")
-            .short("S")
-            .default_value("404")
-            .use_delimiter(true),
-    )
-    .arg(
         Arg::with_name("user-agent")
             .long("user-agent")
             .help("Uses the specified User-Agent")
@@ -124,6 +175,47 @@ pub fn set_http_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
             .short("k"),
     )
     .arg(
+        Arg::with_name("http-header")
+            .long("http-header")
+            .help("Appends the specified HTTP header")
+            .short("H")
+            .multiple(true)
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("proxy")
+            .long("proxy")
+            .help("Sends the requests through the specified HTTP or SOCKS5 proxy (e.g. http://127.0.0.1:8080, socks5://127.0.0.1:1080, socks5h:// to resolve hostnames on the proxy)")
+            .default_value("")
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("timeout")
+            .long("timeout")
+            .help("Sets the timeout of each request in ms (0 for no timeout)")
+            .default_value("10000")
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("retries")
+            .long("retries")
+            .help("Sets the amount of retries with exponential backoff on transient errors")
+            .default_value("2")
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("url")
+            .long("url")
+            .alias("domain")
+            .help("Sets the target URL")
+            .short("u")
+            .takes_value(true)
+            .required_unless("targets-file"),
+    )
+}
+
+pub fn set_request_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
+    app.arg(
         Arg::with_name("http-method")
             .long("http-method")
             .help("Uses the specified HTTP method")
@@ -139,22 +231,50 @@ pub fn set_http_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
             .default_value("")
             .takes_value(true),
     )
-    .arg(
-        Arg::with_name("http-header")
-            .long("http-header")
-            .help("Appends the specified HTTP header")
-            .short("H")
-            .multiple(true)
+}
+
+pub fn set_output_format_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
+    app.arg(
+        Arg::with_name("output-format")
+            .long("output-format")
+            .help("Sets the format of the output file, jsonl appends each result as soon as it is found")
+            .possible_values(&["json", "jsonl"])
+            .default_value("json")
+            .takes_value(true),
+    )
+}
+
+pub fn set_targets_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
+    app.arg(
+        Arg::with_name("targets-file")
+            .long("targets-file")
+            .help("Scans every target listed in the specified file, one per line (- reads from stdin)")
             .takes_value(true),
     )
     .arg(
-        Arg::with_name("url")
-            .long("url")
-            .alias("domain")
-            .help("Sets the target URL")
-            .short("u")
-            .takes_value(true)
-            .required(true),
+        Arg::with_name("host-threads")
+            .long("host-threads")
+            .help("Limits the amount of concurrent requests to each target host (0 for unlimited)")
+            .default_value("0")
+            .takes_value(true),
+    )
+}
+
+pub fn set_requeue_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
+    app.arg(
+        Arg::with_name("requeue")
+            .long("requeue")
+            .help("Requeues the requests that got a 429 or 503 response while the target was throttling"),
+    )
+}
+
+pub fn set_replay_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
+    app.arg(
+        Arg::with_name("replay-proxy")
+            .long("replay-proxy")
+            .help("Re-sends the matching requests through the specified HTTP or SOCKS5 proxy")
+            .default_value("")
+            .takes_value(true),
     )
 }
 
@@ -178,6 +298,147 @@ pub fn set_body_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
     )
 }
 
+pub fn set_matcher_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
+    app.arg(
+        Arg::with_name("include-status-codes")
+            .long("include-status-codes")
+            .help("Sets the list of status codes, prefixes or ranges to include (404 by default in tilde mode)")
+            .short("s")
+            .use_delimiter(true),
+    )
+    .arg(
+        Arg::with_name("ignore-status-codes")
+            .long("ignore-status-codes")
+            .help("Sets the list of status codes, prefixes or ranges to ignore (404 by default, except in tilde mode)")
+            .short("S")
+            .use_delimiter(true),
+    )
+    .arg(
+        Arg::with_name("include-size")
+            .long("include-size")
+            .help("Includes results with the specified body sizes or ranges (e.g. 120,300-400)")
+            .multiple(true)
+            .use_delimiter(true)
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("ignore-size")
+            .long("ignore-size")
+            .help("Ignores results with the specified body sizes or ranges")
+            .multiple(true)
+            .use_delimiter(true)
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("include-words")
+            .long("include-words")
+            .help("Includes results with the specified word counts or ranges")
+            .multiple(true)
+            .use_delimiter(true)
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("ignore-words")
+            .long("ignore-words")
+            .help("Ignores results with the specified word counts or ranges")
+            .multiple(true)
+            .use_delimiter(true)
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("include-lines")
+            .long("include-lines")
+            .help("Includes results with the specified line counts or ranges")
+            .multiple(true)
+            .use_delimiter(true)
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("ignore-lines")
+            .long("ignore-lines")
+            .help("Ignores results with the specified line counts or ranges")
+            .multiple(true)
+            .use_delimiter(true)
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("include-response-time")
+            .long("include-response-time")
+            .help("Includes results with the specified response times or ranges in ms")
+            .multiple(true)
+            .use_delimiter(true)
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("ignore-response-time")
+            .long("ignore-response-time")
+            .help("Ignores results with the specified response times or ranges in ms")
+            .multiple(true)
+            .use_delimiter(true)
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("include-content-type")
+            .long("include-content-type")
+            .help("Includes results whose Content-Type contains the specified values")
+            .multiple(true)
+            .use_delimiter(true)
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("ignore-content-type")
+            .long("ignore-content-type")
+            .help("Ignores results whose Content-Type contains the specified values")
+            .multiple(true)
+            .use_delimiter(true)
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("include-regex")
+            .long("include-regex")
+            .help("Includes results whose HTTP body matches the specified RegEx")
+            .multiple(true)
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("ignore-regex")
+            .long("ignore-regex")
+            .help("Ignores results whose HTTP body matches the specified RegEx")
+            .multiple(true)
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("include-header-regex")
+            .long("include-header-regex")
+            .help("Includes results whose HTTP headers match the specified RegEx")
+            .multiple(true)
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("ignore-header-regex")
+            .long("ignore-header-regex")
+            .help("Ignores results whose HTTP headers match the specified RegEx")
+            .multiple(true)
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("include-mode")
+            .long("include-mode")
+            .help("Sets how the include conditions are combined")
+            .possible_values(&["and", "or"])
+            .default_value("and")
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("ignore-mode")
+            .long("ignore-mode")
+            .help("Sets how the ignore conditions are combined")
+            .possible_values(&["and", "or"])
+            .default_value("or")
+            .takes_value(true),
+    )
+}
+
 pub fn set_dir_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
     app.arg(
         Arg::with_name("extensions")
@@ -193,19 +454,94 @@ pub fn set_dir_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
             .help("Tries to also append / to the base request")
             .short("f"),
     )
+    .arg(
+        Arg::with_name("recursion-depth")
+            .long("recursion-depth")
+            .help("Sets the maximum depth to recurse into discovered directories")
+            .short("R")
+            .default_value("0")
+            .takes_value(true),
+    )
+}
+
+pub fn set_calibration_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
+    app.arg(
+        Arg::with_name("auto-calibrate")
+            .long("auto-calibrate")
+            .help("Filters out responses matching the ones for random non-existing resources (with -o the baselines are saved to <output>.calibration)"),
+    )
 }
 
 pub fn set_wordlist_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
     app.arg(
         Arg::with_name("wordlist")
             .long("wordlist")
-            .help("Sets the wordlist")
+            .help("Sets the wordlist (- reads from stdin, .gz files are decompressed) or a payload generator (range:1-100[/STEP], charset:a-z0-9/MIN-MAX, date:2020-01-01..2020-12-31[/%Y%m%d], list:a;b;c), optionally bound to a keyword and an encoder chain in fuzz mode (e.g. users.txt:USER@urlencode,base64)")
             .short("w")
             .takes_value(true)
             .multiple(true)
             .use_delimiter(true)
             .required(true),
     )
+    .arg(
+        Arg::with_name("wordlist-dedup")
+            .long("wordlist-dedup")
+            .help("Removes duplicated words from the wordlists"),
+    )
+    .arg(
+        Arg::with_name("wordlist-case")
+            .long("wordlist-case")
+            .help("Transforms the case of the words (all also keeps the original word)")
+            .possible_values(&["lower", "upper", "capitalize", "all"])
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("wordlist-prefix")
+            .long("wordlist-prefix")
+            .help("Adds the specified prefix to the words")
+            .default_value("")
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("wordlist-suffix")
+            .long("wordlist-suffix")
+            .help("Adds the specified suffix to the words")
+            .default_value("")
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("wordlist-min-length")
+            .long("wordlist-min-length")
+            .help("Skips the words shorter than the specified length")
+            .default_value("0")
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("wordlist-max-length")
+            .long("wordlist-max-length")
+            .help("Skips the words longer than the specified length")
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("wordlist-include-regex")
+            .long("wordlist-include-regex")
+            .help("Uses only the words matching the specified regex")
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("wordlist-exclude-regex")
+            .long("wordlist-exclude-regex")
+            .help("Skips the words matching the specified regex")
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("wordlist-rules")
+            .long("wordlist-rules")
+            .help("Mutates the words with the specified hashcat-style rule files")
+            .multiple(true)
+            .number_of_values(1)
+            .takes_value(true),
+    )
 }
 
 pub fn set_tilde_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
@@ -218,13 +554,88 @@ pub fn set_tilde_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
     )
 }
 
+pub fn set_crawl_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
+    app.arg(
+        Arg::with_name("depth")
+            .long("depth")
+            .help("Sets the maximum depth of links to follow")
+            .default_value("2")
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("min-length")
+            .long("min-length")
+            .help("Skips the words shorter than the specified length")
+            .default_value("3")
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("min-frequency")
+            .long("min-frequency")
+            .help("Skips the words found less than the specified number of times")
+            .default_value("1")
+            .takes_value(true),
+    )
+}
+
 pub fn set_dns_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
     app.arg(
         Arg::with_name("domain")
             .long("domain")
             .help("Uses the specified domain")
             .short("d")
-            .required(true)
+            .required_unless("targets-file")
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("resolvers")
+            .long("resolvers")
+            .help("Sends the queries to the specified resolvers in round-robin (defaults to the ones in /etc/resolv.conf)")
+            .short("r")
+            .use_delimiter(true)
+            .multiple(true)
+            .number_of_values(1)
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("timeout")
+            .long("timeout")
+            .help("Sets the timeout of each query in ms (0 for no timeout)")
+            .default_value("2000")
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("retries")
+            .long("retries")
+            .help("Sets the amount of retries on another resolver on timeouts and server failures")
+            .default_value("2")
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("record-types")
+            .long("record-types")
+            .help("Queries the specified record types for each name [possible values: A, AAAA, CNAME, MX, TXT, NS, SOA, SRV]")
+            .default_value("A,AAAA")
+            .use_delimiter(true)
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("no-zone-transfer")
+            .long("no-zone-transfer")
+            .help("Skips the AXFR attempt against the nameservers of the domain before bruteforcing it"),
+    )
+    .arg(
+        Arg::with_name("no-takeover-check")
+            .long("no-takeover-check")
+            .help("Skips the subdomain takeover check on CNAMEs pointing to third-party services")
+            .conflicts_with("takeover-fingerprints"),
+    )
+    .arg(
+        Arg::with_name("takeover-fingerprints")
+            .long("takeover-fingerprints")
+            .help("Adds the takeover fingerprints in the specified JSON file to the built-in ones")
+            .multiple(true)
+            .number_of_values(1)
             .takes_value(true),
     )
 }
@@ -238,15 +649,6 @@ pub fn set_vhost_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
             .required(true)
             .takes_value(true),
     )
-    .arg(
-        Arg::with_name("ignore-string")
-            .long("ignore-string")
-            .help("Ignores results with specified string in the HTTP body")
-            .short("x")
-            .required(true)
-            .multiple(true)
-            .takes_value(true),
-    )
 }
 
 pub fn set_fuzz_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
@@ -272,19 +674,57 @@ pub fn set_fuzz_args<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
             .multiple(true)
             .takes_value(true),
     )
+    .arg(
+        Arg::with_name("mode")
+            .long("mode")
+            .help("Sets how the wordlists are combined")
+            .possible_values(&["sniper", "battering-ram", "pitchfork", "cluster-bomb"])
+            .default_value("cluster-bomb")
+            .takes_value(true),
+    )
+    .arg(
+        Arg::with_name("default-value")
+            .long("default-value")
+            .help("Uses the specified value for the markers not being fuzzed in sniper mode")
+            .default_value("")
+            .takes_value(true),
+    )
 }
 
-pub fn extract_common_args<'a>(submatches: &clap::ArgMatches<'a>) -> CommonArgs {
+pub fn extract_common_args<'a>(submatches: &clap::ArgMatches<'a>) -> Result<CommonArgs, ()> {
     let mut no_banner = submatches.is_present("no-banner");
     let mut no_progress_bar = submatches.is_present("no-progress-bar");
     let exit_on_connection_errors = submatches.is_present("exit-on-error");
-    let n_threads = submatches
-        .value_of("threads")
-        .unwrap()
-        .parse::<usize>()
-        .expect("threads is a number");
+    let n_threads = match submatches.value_of("threads").unwrap().parse::<usize>() {
+        Ok(v) => v,
+        Err(_) => {
+            error!("threads must be a number");
+            return Err(());
+        }
+    };
+    let rate = match submatches.value_of("rate").unwrap().parse::<u64>() {
+        Ok(v) => v,
+        Err(_) => {
+            error!("rate must be a number of requests per second");
+            return Err(());
+        }
+    };
+    let delay = match submatches.value_of("delay").unwrap() {
+        "" => None,
+        v => match NumericRange::parse(v) {
+            Some(v) => Some(v),
+            None => {
+                error!("delay must be a number or a range of milliseconds, e.g. 100-500");
+                return Err(());
+            }
+        },
+    };
 
     let output = submatches.value_of("output").unwrap();
+    let output_format = match submatches.value_of("output-format") {
+        Some("jsonl") => OutputFormat::Jsonl,
+        _ => OutputFormat::Json,
+    };
 
     if let Some((Width(w), Height(h))) = terminal_size() {
         if w < 122 {
@@ -302,21 +742,37 @@ pub fn extract_common_args<'a>(submatches: &clap::ArgMatches<'a>) -> CommonArgs
         no_progress_bar = true;
     }
 
-    CommonArgs {
+    Ok(CommonArgs {
         no_banner,
         no_progress_bar,
         exit_on_connection_errors,
         n_threads,
         output: output.to_owned(),
-    }
+        output_format,
+        rate,
+        delay,
+    })
 }
 
-pub fn extract_http_args<'a>(submatches: &clap::ArgMatches<'a>) -> HTTPArgs {
+pub fn extract_http_args<'a>(submatches: &clap::ArgMatches<'a>) -> Result<HTTPArgs, ()> {
     let user_agent = submatches.value_of("user-agent").unwrap();
-    let http_method = submatches.value_of("http-method").unwrap();
-    let http_body = submatches.value_of("http-body").unwrap();
-    let url = submatches.value_of("url").unwrap();
+    let url = submatches.value_of("url").unwrap_or("");
     let ignore_certificate = submatches.is_present("ignore-certificate");
+    let proxy = submatches.value_of("proxy").unwrap();
+    let timeout = match submatches.value_of("timeout").unwrap().parse::<u64>() {
+        Ok(v) => v,
+        Err(_) => {
+            error!("timeout must be a number of milliseconds");
+            return Err(());
+        }
+    };
+    let retries = match submatches.value_of("retries").unwrap().parse::<usize>() {
+        Ok(v) => v,
+        Err(_) => {
+            error!("retries must be a number");
+            return Err(());
+        }
+    };
     let http_headers: Vec<(String, String)> = if submatches.is_present("http-header") {
         submatches
             .values_of("http-header")
@@ -326,39 +782,85 @@ pub fn extract_http_args<'a>(submatches: &clap::ArgMatches<'a>) -> HTTPArgs {
     } else {
         Vec::new()
     };
-    let include_status_codes = submatches
-        .values_of("include-status-codes")
-        .unwrap()
-        .filter(|s| {
-            if s.is_empty() {
-                return false;
-            }
-            s.parse::<hyper::StatusCode>().is_ok()
-        })
-        .map(|s| s.to_string())
-        .collect::<Vec<String>>();
-    let ignore_status_codes = submatches
-        .values_of("ignore-status-codes")
-        .unwrap()
-        .filter(|s| {
-            if s.is_empty() {
-                return false;
-            }
-            s.parse::<hyper::StatusCode>().is_ok()
-        })
-        .map(|s| s.to_string())
-        .collect::<Vec<String>>();
-
-    HTTPArgs {
+    Ok(HTTPArgs {
         user_agent: user_agent.to_owned(),
-        http_method: http_method.to_owned(),
-        http_body: http_body.to_owned(),
         url: url.to_owned(),
         ignore_certificate,
         http_headers,
-        include_status_codes,
-        ignore_status_codes,
+        proxy: proxy.to_owned(),
+        timeout,
+        retries,
+    })
+}
+
+pub fn extract_request_args<'a>(submatches: &clap::ArgMatches<'a>) -> RequestArgs {
+    let http_method = submatches.value_of("http-method").unwrap();
+    let http_body = submatches.value_of("http-body").unwrap();
+
+    RequestArgs {
+        http_method: http_method.to_owned(),
+        http_body: http_body.to_owned(),
+    }
+}
+
+pub fn extract_replay_args<'a>(submatches: &clap::ArgMatches<'a>) -> ReplayArgs {
+    let replay_proxy = submatches.value_of("replay-proxy").unwrap();
+
+    ReplayArgs {
+        replay_proxy: replay_proxy.to_owned(),
+    }
+}
+
+pub fn extract_requeue_args<'a>(submatches: &clap::ArgMatches<'a>) -> RequeueArgs {
+    let requeue = submatches.is_present("requeue");
+
+    RequeueArgs { requeue }
+}
+
+pub fn extract_targets_args<'a>(
+    submatches: &clap::ArgMatches<'a>,
+    target: &str,
+) -> Result<TargetsArgs, ()> {
+    let host_threads = match submatches.value_of("host-threads").unwrap().parse::<usize>() {
+        Ok(v) => v,
+        Err(_) => {
+            error!("host-threads must be a number");
+            return Err(());
+        }
+    };
+    let mut targets = Vec::new();
+    if !target.is_empty() {
+        targets.push(target.to_owned());
+    }
+
+    if let Some(targets_path) = submatches.value_of("targets-file") {
+        let stdin_wordlist = match submatches.values_of("wordlist") {
+            Some(mut wordlists) => wordlists.any(|w| w == STDIN || w.starts_with("-:") || w.starts_with("-@")),
+            None => false,
+        };
+        if targets_path == STDIN && stdin_wordlist {
+            error!("Stdin can be used either for the wordlist or for the targets, not both");
+            return Err(());
+        }
+
+        match crate::targets::load(targets_path) {
+            Ok(v) => targets.extend(v.into_iter().filter(|v| v != target)),
+            Err(e) => {
+                error!("Unable to read the targets: {}", e);
+                return Err(());
+            }
+        }
     }
+
+    if targets.is_empty() {
+        error!("No targets to scan");
+        return Err(());
+    }
+
+    Ok(TargetsArgs {
+        targets,
+        host_threads,
+    })
 }
 
 pub fn extract_dns_args<'a>(submatches: &clap::ArgMatches<'a>) -> DNSArgs {
@@ -369,33 +871,84 @@ pub fn extract_dns_args<'a>(submatches: &clap::ArgMatches<'a>) -> DNSArgs {
     }
 }
 
-pub fn extract_body_args<'a>(submatches: &clap::ArgMatches<'a>) -> BodyArgs {
-    let ignore_strings: Vec<String> = if submatches.is_present("ignore-string") {
-        submatches
-            .values_of("ignore-string")
-            .unwrap()
-            .map(|h| h.to_owned())
-            .collect()
-    } else {
-        Vec::new()
+pub fn extract_resolver_args<'a>(submatches: &clap::ArgMatches<'a>) -> Result<ResolverArgs, ()> {
+    let timeout = match submatches.value_of("timeout").unwrap().parse::<u64>() {
+        Ok(v) => v,
+        Err(_) => {
+            error!("timeout must be a number of milliseconds");
+            return Err(());
+        }
     };
-    let include_strings: Vec<String> = if submatches.is_present("include-string") {
-        submatches
-            .values_of("include-string")
-            .unwrap()
-            .map(|h| h.to_owned())
-            .collect()
-    } else {
-        Vec::new()
+    let retries = match submatches.value_of("retries").unwrap().parse::<usize>() {
+        Ok(v) => v,
+        Err(_) => {
+            error!("retries must be a number");
+            return Err(());
+        }
+    };
+    let resolvers = match submatches.values_of("resolvers") {
+        Some(resolvers) => {
+            let mut parsed = Vec::new();
+            for resolver in resolvers.filter(|r| !r.is_empty()) {
+                match crate::resolver::parse_nameserver(resolver) {
+                    Ok(v) => parsed.push(v),
+                    Err(e) => {
+                        error!("{}", e);
+                        return Err(());
+                    }
+                }
+            }
+
+            parsed
+        }
+        None => crate::resolver::system_nameservers(),
     };
 
-    BodyArgs {
-        include_strings,
-        ignore_strings,
+    if resolvers.is_empty() {
+        error!("No resolvers found, please specify them with --resolvers");
+        return Err(());
+    }
+
+    let mut record_types = Vec::new();
+    for record_type in submatches.values_of("record-types").unwrap() {
+        match crate::resolver::message::type_code(record_type) {
+            Some(v) if !record_types.contains(&v) => record_types.push(v),
+            Some(_) => (),
+            None => {
+                error!("{} is not a supported record type", record_type);
+                return Err(());
+            }
+        }
     }
+
+    let takeover_fingerprints = if submatches.is_present("no-takeover-check") {
+        None
+    } else {
+        let mut fingerprints = crate::dnsbuster::takeover::builtin_fingerprints();
+        for fingerprints_path in submatches.values_of("takeover-fingerprints").unwrap_or_default() {
+            match crate::dnsbuster::takeover::load_fingerprints(fingerprints_path) {
+                Ok(v) => fingerprints.extend(v),
+                Err(e) => {
+                    error!("Invalid fingerprint file {}", e);
+                    return Err(());
+                }
+            }
+        }
+
+        Some(fingerprints)
+    };
+
+    Ok(ResolverArgs {
+        resolvers,
+        timeout,
+        retries,
+        record_types,
+        zone_transfer: !submatches.is_present("no-zone-transfer"),
+        takeover_fingerprints,
+    })
 }
 
-pub fn extract_dir_args<'a>(submatches: &clap::ArgMatches<'a>) -> DirArgs {
+pub fn extract_dir_args<'a>(submatches: &clap::ArgMatches<'a>) -> Result<DirArgs, ()> {
     let append_slash = submatches.is_present("append-slash");
     let extensions = submatches
         .values_of("extensions")
@@ -403,13 +956,168 @@ pub fn extract_dir_args<'a>(submatches: &clap::ArgMatches<'a>) -> DirArgs {
         .filter(|e| !e.is_empty())
         .map(|s| s.to_owned())
         .collect::<Vec<String>>();
-    DirArgs {
+    let recursion_depth = match submatches.value_of("recursion-depth").unwrap().parse::<usize>() {
+        Ok(v) => v,
+        Err(_) => {
+            error!("recursion-depth must be a number");
+            return Err(());
+        }
+    };
+    Ok(DirArgs {
         append_slash,
         extensions,
+        recursion_depth,
+    })
+}
+
+pub fn extract_matcher_args<'a>(
+    submatches: &clap::ArgMatches<'a>,
+    default_include_status: &str,
+    default_ignore_status: &str,
+) -> Result<MatcherArgs, ()> {
+    let explicit_status = submatches.is_present("include-status-codes")
+        || submatches.is_present("ignore-status-codes");
+    let (include_status, ignore_status) = if explicit_status {
+        (
+            extract_status_ranges(submatches, "include-status-codes"),
+            extract_status_ranges(submatches, "ignore-status-codes"),
+        )
+    } else {
+        (
+            NumericRange::parse_status(default_include_status).into_iter().collect(),
+            NumericRange::parse_status(default_ignore_status).into_iter().collect(),
+        )
+    };
+
+    let mut include = Vec::new();
+    let mut ignore = Vec::new();
+    let mut valid = true;
+    let push_ranges = |rules: &mut Vec<Rule>, ranges: Vec<NumericRange>, rule: fn(Vec<NumericRange>) -> Rule| {
+        if !ranges.is_empty() {
+            rules.push(rule(ranges));
+        }
+    };
+
+    push_ranges(&mut include, include_status, Rule::Status);
+    push_ranges(&mut ignore, ignore_status, Rule::Status);
+    push_ranges(&mut include, extract_ranges(submatches, "include-size"), Rule::Size);
+    push_ranges(&mut ignore, extract_ranges(submatches, "ignore-size"), Rule::Size);
+    push_ranges(&mut include, extract_ranges(submatches, "include-words"), Rule::Words);
+    push_ranges(&mut ignore, extract_ranges(submatches, "ignore-words"), Rule::Words);
+    push_ranges(&mut include, extract_ranges(submatches, "include-lines"), Rule::Lines);
+    push_ranges(&mut ignore, extract_ranges(submatches, "ignore-lines"), Rule::Lines);
+    push_ranges(
+        &mut include,
+        extract_ranges(submatches, "include-response-time"),
+        Rule::ResponseTime,
+    );
+    push_ranges(
+        &mut ignore,
+        extract_ranges(submatches, "ignore-response-time"),
+        Rule::ResponseTime,
+    );
+
+    let mut push_patterns = |rules: &mut Vec<Rule>, name: &str, to_regex: fn(&str) -> String, rule: fn(Vec<Pattern>) -> Rule| {
+        let values = match submatches.values_of(name) {
+            Some(values) => values,
+            None => return,
+        };
+
+        let mut patterns = Vec::new();
+        for value in values.filter(|v| !v.is_empty()) {
+            match Pattern::new(&to_regex(value)) {
+                Ok(pattern) => patterns.push(pattern),
+                Err(e) => {
+                    error!("Invalid regex for {}: {}", name, e);
+                    valid = false;
+                }
+            }
+        }
+
+        if !patterns.is_empty() {
+            rules.push(rule(patterns));
+        }
+    };
+
+    push_patterns(&mut include, "include-string", regex::escape, Rule::Body);
+    push_patterns(&mut ignore, "ignore-string", regex::escape, Rule::Body);
+    push_patterns(&mut include, "include-regex", |v| v.to_owned(), Rule::Body);
+    push_patterns(&mut ignore, "ignore-regex", |v| v.to_owned(), Rule::Body);
+    push_patterns(&mut include, "include-header-regex", |v| v.to_owned(), Rule::Headers);
+    push_patterns(&mut ignore, "ignore-header-regex", |v| v.to_owned(), Rule::Headers);
+    push_patterns(&mut include, "include-content-type", content_type_regex, Rule::Headers);
+    push_patterns(&mut ignore, "ignore-content-type", content_type_regex, Rule::Headers);
+
+    if !valid {
+        return Err(());
     }
+
+    let include_mode = extract_combinator(submatches, "include-mode");
+    let ignore_mode = extract_combinator(submatches, "ignore-mode");
+
+    Ok(MatcherArgs {
+        matcher: Matcher {
+            include,
+            include_mode,
+            ignore,
+            ignore_mode,
+        },
+    })
 }
 
-pub fn extract_fuzz_args<'a>(submatches: &clap::ArgMatches<'a>) -> FuzzArgs {
+fn content_type_regex(value: &str) -> String {
+    format!("(?im)^content-type:.*{}", regex::escape(value))
+}
+
+fn extract_combinator<'a>(submatches: &clap::ArgMatches<'a>, name: &str) -> Combinator {
+    match submatches.value_of(name) {
+        Some("or") => Combinator::Or,
+        _ => Combinator::And,
+    }
+}
+
+fn extract_status_ranges<'a>(submatches: &clap::ArgMatches<'a>, name: &str) -> Vec<NumericRange> {
+    match submatches.values_of(name) {
+        Some(values) => values
+            .filter(|v| !v.is_empty())
+            .filter_map(|v| match NumericRange::parse_status(v) {
+                Some(range) => Some(range),
+                None => {
+                    warn!("Ignoring invalid status code for {}: {}", name, v);
+                    None
+                }
+            })
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+fn extract_ranges<'a>(submatches: &clap::ArgMatches<'a>, name: &str) -> Vec<NumericRange> {
+    match submatches.values_of(name) {
+        Some(values) => values
+            .filter(|v| !v.is_empty())
+            .filter_map(|v| match NumericRange::parse(v) {
+                Some(range) => Some(range),
+                None => {
+                    warn!("Ignoring invalid range for {}: {}", name, v);
+                    None
+                }
+            })
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+pub fn extract_calibration_args<'a>(submatches: &clap::ArgMatches<'a>) -> CalibrationArgs {
+    let auto_calibrate = submatches.is_present("auto-calibrate");
+
+    CalibrationArgs { auto_calibrate }
+}
+
+pub fn extract_fuzz_args<'a>(
+    submatches: &clap::ArgMatches<'a>,
+    wordlist_args: &WordlistArgs,
+) -> Result<FuzzArgs, ()> {
     let csrf_url = match submatches.value_of("csrf-url") {
         Some(v) => Some(v.to_owned()),
         None => None,
@@ -429,10 +1137,32 @@ pub fn extract_fuzz_args<'a>(submatches: &clap::ArgMatches<'a>) -> FuzzArgs {
     } else {
         None
     };
-    FuzzArgs {
+    let mode = match submatches.value_of("mode").unwrap() {
+        "sniper" => FuzzMode::Sniper,
+        "battering-ram" => FuzzMode::BatteringRam,
+        "pitchfork" => FuzzMode::Pitchfork,
+        _ => FuzzMode::ClusterBomb,
+    };
+    if mode == FuzzMode::Sniper {
+        if let Err(e) = crate::fuzzbuster::utils::check_sniper_wordlists(&wordlist_args.wordlist_keywords) {
+            error!("{}", e);
+            return Err(());
+        }
+    }
+
+    let default_value = submatches.value_of("default-value").unwrap().to_owned();
+    Ok(FuzzArgs {
         csrf_url,
         csrf_regex,
         csrf_headers,
+        mode,
+        default_value,
+    })
+}
+
+pub fn warn_unsupported_encoders(wordlist_args: &WordlistArgs) {
+    if wordlist_args.wordlist_encoders.iter().any(|encoders| !encoders.is_empty()) {
+        warn!("Encoder chains are only supported in fuzz mode and will be ignored");
     }
 }
 
@@ -462,6 +1192,20 @@ pub fn url_is_valid(url: &str) -> bool {
     }
 }
 
+pub fn proxy_is_valid(proxy: &str) -> bool {
+    if proxy.is_empty() {
+        return true;
+    }
+
+    match crate::proxy::Proxy::parse(proxy) {
+        Ok(_) => true,
+        Err(e) => {
+            error!("Invalid proxy: {}", e);
+            false
+        }
+    }
+}
+
 pub fn extract_tilde_args<'a>(submatches: &clap::ArgMatches<'a>) -> TildeArgs {
     let extension = match submatches.value_of("extension") {
         Some(v) => Some(v.to_owned()),
@@ -471,22 +1215,93 @@ pub fn extract_tilde_args<'a>(submatches: &clap::ArgMatches<'a>) -> TildeArgs {
     TildeArgs { extension }
 }
 
+pub fn extract_crawl_args<'a>(submatches: &clap::ArgMatches<'a>) -> Result<CrawlArgs, ()> {
+    let depth = match submatches.value_of("depth").unwrap().parse::<usize>() {
+        Ok(v) => v,
+        Err(_) => {
+            error!("depth must be a number");
+            return Err(());
+        }
+    };
+    let min_length = match submatches.value_of("min-length").unwrap().parse::<usize>() {
+        Ok(v) => v,
+        Err(_) => {
+            error!("min-length must be a number");
+            return Err(());
+        }
+    };
+    let min_frequency = match submatches.value_of("min-frequency").unwrap().parse::<usize>() {
+        Ok(v) => v,
+        Err(_) => {
+            error!("min-frequency must be a number");
+            return Err(());
+        }
+    };
+
+    Ok(CrawlArgs {
+        depth,
+        min_length,
+        min_frequency,
+    })
+}
+
 pub fn extract_wordlist_args<'a>(submatches: &clap::ArgMatches<'a>) -> Result<WordlistArgs, ()> {
-    let wordlist_paths = submatches
-        .values_of("wordlist")
-        .unwrap()
-        .map(|w| w.to_owned())
-        .collect::<Vec<String>>();
+    let mut values: Vec<String> = Vec::new();
+    for w in submatches.values_of("wordlist").unwrap() {
+        match values.last_mut() {
+            Some(previous)
+                if !split_encoders(previous).1.is_empty()
+                    && Encoder::parse(w).is_some()
+                    && std::fs::metadata(w).is_err() =>
+            {
+                previous.push(',');
+                previous.push_str(w);
+            }
+            _ => values.push(w.to_owned()),
+        }
+    }
+
+    let mut wordlist_paths = Vec::new();
+    let mut wordlist_keywords = Vec::new();
+    let mut wordlist_encoders = Vec::new();
+    for w in values {
+        if w == crate::wordlist::STDIN || std::fs::metadata(&w).is_ok() {
+            wordlist_paths.push(w);
+            wordlist_keywords.push(None);
+            wordlist_encoders.push(Vec::new());
+            continue;
+        }
+
+        let (w, encoders) = split_encoders(&w);
+        let (wordlist_path, wordlist_keyword) = crate::fuzzbuster::utils::split_wordlist_keyword(&w);
+        if Generator::is_generator(&w) && !Generator::is_generator(&wordlist_path) {
+            wordlist_paths.push(w);
+            wordlist_keywords.push(None);
+        } else {
+            wordlist_paths.push(wordlist_path);
+            wordlist_keywords.push(wordlist_keyword);
+        }
+        wordlist_encoders.push(encoders);
+    }
 
     let all_wordlists_exist = wordlist_paths
         .iter()
         .map(|wordlist_path| {
-            if std::fs::metadata(wordlist_path).is_err() {
-                error!("Specified wordlist does not exist: {}", wordlist_path);
-                return false;
-            } else {
+            if wordlist_path == crate::wordlist::STDIN || std::fs::metadata(wordlist_path).is_ok() {
                 return true;
             }
+
+            match Generator::parse(wordlist_path) {
+                Ok(Some(_)) => true,
+                Ok(None) => {
+                    error!("Specified wordlist does not exist: {}", wordlist_path);
+                    false
+                }
+                Err(e) => {
+                    error!("{}", e);
+                    false
+                }
+            }
         })
         .fold(true, |acc, e| acc && e);
 
@@ -494,5 +1309,84 @@ pub fn extract_wordlist_args<'a>(submatches: &clap::ArgMatches<'a>) -> Result<Wo
         return Err(());
     }
 
-    Ok(WordlistArgs { wordlist_paths })
+    let wordlist_options = extract_wordlist_options(submatches)?;
+    for wordlist_path in wordlist_paths.iter() {
+        if let Err(e) = crate::wordlist::check_generator(wordlist_path, &wordlist_options) {
+            error!("{}", e);
+            return Err(());
+        }
+    }
+
+    Ok(WordlistArgs {
+        wordlist_paths,
+        wordlist_keywords,
+        wordlist_encoders,
+        wordlist_options,
+    })
+}
+
+fn extract_wordlist_options<'a>(submatches: &clap::ArgMatches<'a>) -> Result<WordlistOptions, ()> {
+    let dedup = submatches.is_present("wordlist-dedup");
+    let case = match submatches.value_of("wordlist-case") {
+        Some("lower") => Some(WordCase::Lower),
+        Some("upper") => Some(WordCase::Upper),
+        Some("capitalize") => Some(WordCase::Capitalize),
+        Some(_) => Some(WordCase::All),
+        None => None,
+    };
+    let prefix = submatches.value_of("wordlist-prefix").unwrap().to_owned();
+    let suffix = submatches.value_of("wordlist-suffix").unwrap().to_owned();
+    let min_length = match submatches.value_of("wordlist-min-length").unwrap().parse::<usize>() {
+        Ok(v) => v,
+        Err(_) => {
+            error!("wordlist-min-length must be a number");
+            return Err(());
+        }
+    };
+    let max_length = match submatches.value_of("wordlist-max-length") {
+        Some(v) => match v.parse::<usize>() {
+            Ok(v) => Some(v),
+            Err(_) => {
+                error!("wordlist-max-length must be a number");
+                return Err(());
+            }
+        },
+        None => None,
+    };
+
+    let extract_pattern = |name: &str| match submatches.value_of(name) {
+        Some(v) => match Pattern::new(v) {
+            Ok(pattern) => Ok(Some(pattern)),
+            Err(e) => {
+                error!("Invalid regex for {}: {}", name, e);
+                Err(())
+            }
+        },
+        None => Ok(None),
+    };
+    let include = extract_pattern("wordlist-include-regex")?;
+    let exclude = extract_pattern("wordlist-exclude-regex")?;
+
+    let mut rules = Vec::new();
+    for rules_path in submatches.values_of("wordlist-rules").unwrap_or_default() {
+        match crate::rules::load(rules_path) {
+            Ok(v) => rules.extend(v),
+            Err(e) => {
+                error!("Invalid rule file {}", e);
+                return Err(());
+            }
+        }
+    }
+
+    Ok(WordlistOptions {
+        dedup,
+        case,
+        prefix,
+        suffix,
+        min_length,
+        max_length,
+        include,
+        exclude,
+        rules,
+    })
 }
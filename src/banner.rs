@@ -1,4 +1,8 @@
 use chrono::Local;
+use std::net::{IpAddr, SocketAddr};
+
+use crate::calibration::Calibration;
+use crate::wordlist::{self, WordlistOptions};
 
 const VERSION: &'static str = env!("CARGO_PKG_VERSION");
 
@@ -36,6 +40,69 @@ pub fn configuration(mode: &str, url: &str, threads: &str, wordlist: &str) -> St
     )
 }
 
+pub fn calibration(calibration: &Calibration) -> String {
+    if calibration.baselines.is_empty() {
+        return "[+] Calibration\t: no baseline collected".to_owned();
+    }
+
+    let multiple_targets = calibration
+        .baselines
+        .iter()
+        .any(|baseline| baseline.target != calibration.baselines[0].target);
+
+    calibration
+        .baselines
+        .iter()
+        .map(|baseline| {
+            let redirect = match &baseline.redirect {
+                Some(v) => format!(" => {}", v),
+                None => "".to_owned(),
+            };
+            let target = if multiple_targets {
+                format!("{} ", baseline.target)
+            } else {
+                "".to_owned()
+            };
+
+            format!(
+                "[+] Calibration\t: {}{}, {} bytes, {} words, {} lines{}",
+                target, baseline.status, baseline.content_length, baseline.words, baseline.lines, redirect
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub fn rules(wordlist_paths: &[String], options: &WordlistOptions) -> String {
+    wordlist_paths
+        .iter()
+        .map(|wordlist_path| {
+            format!(
+                "[+] Rules\t: {} rules, {} words from {}",
+                options.rules.len(),
+                wordlist::count(wordlist_path, options),
+                wordlist_path
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub fn resolvers(resolvers: &[SocketAddr]) -> String {
+    format!(
+        "[+] Resolvers\t: {}",
+        resolvers
+            .iter()
+            .map(|resolver| resolver.to_string())
+            .collect::<Vec<String>>()
+            .join(", ")
+    )
+}
+
+pub fn crawled(pages: usize, words: usize) -> String {
+    format!("\n[+] Crawled\t: {} pages, {} words\n", pages, words)
+}
+
 pub fn starting_time() -> String {
     format!(
         "[?] Started at\t: {}\n",
@@ -49,3 +116,44 @@ pub fn ending_time() -> String {
         Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
     )
 }
+
+pub fn wildcard(parent: &str, addrs: &[IpAddr]) -> String {
+    format!(
+        "[!] Wildcard\t: *.{} => {}",
+        parent,
+        addrs
+            .iter()
+            .map(|addr| addr.to_string())
+            .collect::<Vec<String>>()
+            .join(", ")
+    )
+}
+
+pub fn zone_transfer(domain: &str, zone: Option<(&str, usize)>) -> String {
+    match zone {
+        Some((nameserver, records)) => format!(
+            "[+] Zone transfer\t: {} from {}, {} records",
+            domain, nameserver, records
+        ),
+        None => format!("[+] Zone transfer\t: not allowed for {}", domain),
+    }
+}
+
+pub fn failed_requests(failed: &[String]) -> String {
+    format!(
+        "\n[!] Failed requests: {}\n{}",
+        failed.len(),
+        failed
+            .iter()
+            .map(|v| format!("\t{}", v))
+            .collect::<Vec<String>>()
+            .join("\n")
+    )
+}
+
+pub fn interrupted(path: &str) -> String {
+    format!(
+        "\n[!] Scan interrupted, resume it with: rustbuster --resume {}",
+        path
+    )
+}
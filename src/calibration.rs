@@ -0,0 +1,111 @@
+use rand::{distributions::Alphanumeric, thread_rng, Rng};
+use serde::{Deserialize, Serialize};
+use std::{fs::File, io::Write, path::Path};
+
+pub const CALIBRATION_REQUESTS: usize = 3;
+const RANDOM_TOKEN_LENGTH: usize = 16;
+const INJECTED_PLACEHOLDER: &str = "{RANDOM}";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ResponseFingerprint {
+    #[serde(default)]
+    pub target: String,
+    pub status: String,
+    pub content_length: usize,
+    pub words: usize,
+    pub lines: usize,
+    pub redirect: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Calibration {
+    pub baselines: Vec<ResponseFingerprint>,
+}
+
+impl ResponseFingerprint {
+    pub fn new(
+        target: &str,
+        status: &str,
+        location: Option<&str>,
+        body: &str,
+        injected: &[String],
+    ) -> Self {
+        ResponseFingerprint {
+            target: target.to_owned(),
+            status: status.to_owned(),
+            content_length: body.len(),
+            words: body.split_whitespace().count(),
+            lines: body.lines().count(),
+            redirect: redirect_pattern(location, injected),
+        }
+    }
+
+    pub fn matches(&self, other: &ResponseFingerprint) -> bool {
+        if self.target != other.target
+            || self.status != other.status
+            || self.redirect != other.redirect
+        {
+            return false;
+        }
+
+        self.content_length == other.content_length
+            || (self.words == other.words && self.lines == other.lines)
+    }
+}
+
+impl Calibration {
+    pub fn new() -> Self {
+        Calibration {
+            baselines: Vec::new(),
+        }
+    }
+
+    pub fn add_baseline(&mut self, fingerprint: ResponseFingerprint) {
+        if !self.baselines.contains(&fingerprint) {
+            self.baselines.push(fingerprint);
+        }
+    }
+
+    pub fn matches(&self, fingerprint: &ResponseFingerprint) -> bool {
+        self.baselines
+            .iter()
+            .any(|baseline| baseline.matches(fingerprint))
+    }
+}
+
+pub fn redirect_pattern(location: Option<&str>, injected: &[String]) -> Option<String> {
+    location.map(|location| {
+        injected
+            .iter()
+            .filter(|word| !word.is_empty())
+            .fold(location.to_owned(), |acc, word| {
+                acc.replace(word.as_str(), INJECTED_PLACEHOLDER)
+            })
+    })
+}
+
+pub fn save_calibration(output: &str, calibration: &Calibration) {
+    let path = format!("{}.calibration", output);
+    let json_string = serde_json::to_string(calibration).unwrap();
+
+    let mut file = match File::create(Path::new(&path)) {
+        Ok(f) => f,
+        Err(e) => {
+            error!("Error while creating file: {}\n{}", path, e);
+            return;
+        }
+    };
+
+    match file.write_all(json_string.as_bytes()) {
+        Ok(_) => debug!("Calibration saved to: {}", path),
+        Err(e) => error!("Error while writing calibration to file: {}\n{}", path, e),
+    };
+}
+
+pub fn random_token() -> String {
+    thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(RANDOM_TOKEN_LENGTH)
+        .collect::<String>()
+        .to_lowercase()
+}
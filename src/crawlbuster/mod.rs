@@ -0,0 +1,233 @@
+use futures::{sync::mpsc, Stream};
+use hyper::{
+    rt::{self, Future},
+    Body, Client, Request, Uri,
+};
+use hyper_tls::{self, HttpsConnector};
+use indicatif::{ProgressBar, ProgressStyle};
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::channel;
+use std::sync::mpsc::Sender;
+
+mod spec;
+pub mod utils;
+
+use crate::matcher::NumericRange;
+use crate::proxy::{build_client, ProxyConnector};
+use crate::resume::{is_interrupted, receive, InFlight};
+use crate::retry::{send_request, RetryPolicy};
+use crate::throttle::Throttle;
+use utils::{build_wordlist, extract_links, extract_words, resolve, save_wordlist, should_crawl};
+
+#[derive(Debug, Clone)]
+pub struct CrawlBuster {
+    pub n_threads: usize,
+    pub ignore_certificate: bool,
+    pub user_agent: String,
+    pub http_headers: Vec<(String, String)>,
+    pub url: String,
+    pub depth: usize,
+    pub min_length: usize,
+    pub min_frequency: usize,
+    pub no_progress_bar: bool,
+    pub exit_on_connection_errors: bool,
+    pub output: String,
+    pub proxy: String,
+    pub rate: u64,
+    pub delay: Option<NumericRange>,
+    pub timeout: u64,
+    pub retries: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct CrawledPage {
+    pub url: String,
+    pub depth: usize,
+    pub status: String,
+    pub content_type: String,
+    pub location: Option<String>,
+    pub body: String,
+    pub error: Option<String>,
+}
+
+impl CrawlBuster {
+    pub fn run(self) {
+        let start = self.url.parse::<Uri>().unwrap();
+        let depth = self.depth;
+        let min_length = self.min_length;
+        let min_frequency = self.min_frequency;
+        let no_progress_bar = self.no_progress_bar;
+        let exit_on_connection_errors = self.exit_on_connection_errors;
+        let output = self.output.clone();
+        let n_threads = self.n_threads;
+        let (tx, rx) = channel::<CrawledPage>();
+        let (tx_requests, rx_requests) = mpsc::unbounded::<(Uri, usize)>();
+        let client = build_client(self.n_threads, self.ignore_certificate, &self.proxy);
+        let throttle = Throttle::new(self.rate, self.delay.clone());
+        let in_flight = InFlight::default();
+        let stream_in_flight = in_flight.clone();
+        let bar = if self.no_progress_bar {
+            ProgressBar::hidden()
+        } else {
+            ProgressBar::new(1)
+        };
+        bar.set_draw_delta(1);
+        bar.set_style(ProgressStyle::default_bar()
+            .template("{spinner} [{elapsed_precise}] {bar:40.red/white} {pos:>7}/{len:7} pages")
+            .progress_chars("#>-"));
+
+        let stream = rx_requests
+            .map(move |(uri, depth)| {
+                let request_future = self.make_request_future(tx.clone(), client.clone(), uri, depth);
+                stream_in_flight.track(throttle.run(request_future))
+            })
+            .buffer_unordered(n_threads)
+            .for_each(Ok);
+
+        let _ = std::thread::spawn(move || rt::run(stream));
+
+        let mut visited = HashSet::new();
+        visited.insert(start.to_string());
+        tx_requests.unbounded_send((start.clone(), 0)).unwrap();
+
+        let mut pending = 1;
+        let mut total_pages = 1;
+        let mut crawled_pages = 0;
+        let mut frequencies = HashMap::new();
+        let mut failed_requests = Vec::new();
+        while pending > 0 {
+            let msg = match receive(&rx, &in_flight) {
+                Some(msg) => msg,
+                None => break,
+            };
+            pending -= 1;
+            bar.inc(1);
+
+            if let Some(e) = &msg.error {
+                error!("{} - {}", msg.url, e);
+                failed_requests.push(msg.url.clone());
+                if crawled_pages == 0 || exit_on_connection_errors {
+                    warn!("Check connectivity to the target");
+                    break;
+                }
+
+                continue;
+            }
+
+            crawled_pages += 1;
+            let is_error_page = msg.status.starts_with('4') || msg.status.starts_with('5');
+            let words = if is_error_page {
+                Vec::new()
+            } else {
+                extract_words(&msg.body, &msg.content_type, min_length)
+            };
+            let line = format!("{}\t{}\t{} words", msg.status, msg.url, words.len());
+            if no_progress_bar {
+                println!("{}", line);
+            } else {
+                bar.println(line);
+            }
+
+            for word in words {
+                *frequencies.entry(word).or_insert(0) += 1;
+            }
+
+            if is_error_page || msg.depth >= depth || is_interrupted() {
+                continue;
+            }
+
+            let uri = msg.url.parse::<Uri>().unwrap();
+            let mut links = extract_links(&uri, &msg.body, &msg.content_type);
+            links.extend(msg.location.as_ref().and_then(|location| resolve(&uri, location)));
+            for link in links {
+                if should_crawl(&start, &link) && visited.insert(link.to_string()) {
+                    pending += 1;
+                    total_pages += 1;
+                    bar.set_length(total_pages);
+                    tx_requests.unbounded_send((link, msg.depth + 1)).unwrap();
+                }
+            }
+        }
+
+        bar.finish();
+        let words = build_wordlist(&frequencies, min_frequency);
+        println!("{}", crate::banner::crawled(crawled_pages, words.len()));
+        if output.is_empty() {
+            for word in &words {
+                println!("{}", word);
+            }
+        } else {
+            save_wordlist(&output, &words);
+        }
+
+        if !failed_requests.is_empty() {
+            println!("{}", crate::banner::failed_requests(&failed_requests));
+        }
+        println!("{}", crate::banner::ending_time());
+    }
+
+    fn make_request_future(
+        &self,
+        tx: Sender<CrawledPage>,
+        client: Client<HttpsConnector<ProxyConnector>>,
+        uri: Uri,
+        depth: usize,
+    ) -> impl Future<Item = (), Error = ()> {
+        let tx_err = tx.clone();
+        let mut target = CrawledPage {
+            url: uri.to_string(),
+            depth,
+            status: String::new(),
+            content_type: String::new(),
+            location: None,
+            body: String::new(),
+            error: None,
+        };
+        let mut target_err = target.clone();
+        let buster = self.clone();
+        let policy = RetryPolicy::new(self.timeout, self.retries);
+
+        send_request(client, policy, move || buster.build_request(&uri))
+            .and_then(move |(parts, body, _)| {
+                target.status = parts.status.to_string();
+                target.content_type = parts
+                    .headers
+                    .get("Content-Type")
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or("")
+                    .to_lowercase();
+                if parts.status.is_redirection() {
+                    target.location = parts
+                        .headers
+                        .get("Location")
+                        .and_then(|v| v.to_str().ok())
+                        .map(|v| v.to_owned());
+                }
+                target.body = String::from_utf8_lossy(&body).into_owned();
+
+                let _ = tx.send(target);
+                Ok(())
+            })
+            .or_else(move |e| {
+                target_err.error = Some(e.to_string());
+                let _ = tx_err.send(target_err);
+                Ok(())
+            })
+    }
+
+    fn build_request(&self, url: &Uri) -> Request<Body> {
+        let mut request_builder = Request::builder();
+
+        for header_tuple in &self.http_headers {
+            request_builder.header(header_tuple.0.as_str(), header_tuple.1.as_str());
+        }
+
+        request_builder
+            .header("User-Agent", &self.user_agent[..])
+            .method("GET")
+            .uri(url)
+            .header("Host", url.host().unwrap())
+            .body(Body::empty())
+            .expect("Request builder")
+    }
+}
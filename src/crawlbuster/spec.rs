@@ -0,0 +1,38 @@
+use galvanic_test::test_suite;
+
+test_suite! {
+    name crawlbuster;
+
+    test extract_words_from_html() {
+        let body = "<html><!-- legacy backup --><head><style>.hidden { display: none }</style><script>var apiToken = fetch('/api/internal/users');</script></head>\
+            <body><a href=\"/admin/panel.php\" title=\"Control Panel\">Welcome &amp; login</a><img src=\"logo.png\" alt=\"Acme\"></body></html>";
+        let words = crate::crawlbuster::utils::extract_words(body, "text/html", 3);
+        for word in &["legacy", "backup", "var", "apiToken", "fetch", "api", "internal", "users", "admin", "panel", "php", "Control", "Panel", "Welcome", "login", "logo", "png", "Acme"] {
+            assert!(words.contains(&word.to_string()), "missing {}", word);
+        }
+        for word in &["html", "hidden", "display", "none", "href", "title", "amp"] {
+            assert!(!words.contains(&word.to_string()), "unexpected {}", word);
+        }
+    }
+
+    test extract_links_in_scope() {
+        let base = "http://localhost/blog/post.html".parse::<hyper::Uri>().unwrap();
+        let body = "<a href='../about/'>a</a><a href=\"next.html#top\">b</a><a href=\"mailto:a@b.c\">c</a>\
+            <a href=\"//example.com/\">d</a><script>load(\"/static/app.js?v=1\")</script>";
+        let links = crate::crawlbuster::utils::extract_links(&base, body, "text/html")
+            .iter()
+            .filter(|link| crate::crawlbuster::utils::should_crawl(&base, link))
+            .map(|link| link.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["http://localhost/about/", "http://localhost/blog/next.html", "http://localhost/static/app.js?v=1"], links);
+    }
+
+    test build_wordlist_by_frequency() {
+        let mut frequencies = std::collections::HashMap::new();
+        frequencies.insert("admin".to_owned(), 3);
+        frequencies.insert("login".to_owned(), 1);
+        frequencies.insert("backup".to_owned(), 3);
+        frequencies.insert("users".to_owned(), 2);
+        assert_eq!(vec!["admin", "backup", "users"], crate::crawlbuster::utils::build_wordlist(&frequencies, 2));
+    }
+}
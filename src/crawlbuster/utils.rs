@@ -0,0 +1,235 @@
+use hyper::Uri;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::OnceLock;
+
+static COMMENTS: OnceLock<Regex> = OnceLock::new();
+static SCRIPTS: OnceLock<Regex> = OnceLock::new();
+static STYLES: OnceLock<Regex> = OnceLock::new();
+static ATTRIBUTES: OnceLock<Regex> = OnceLock::new();
+static TAGS: OnceLock<Regex> = OnceLock::new();
+static ENTITIES: OnceLock<Regex> = OnceLock::new();
+static JS_PATHS: OnceLock<Regex> = OnceLock::new();
+
+fn regex(cell: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).unwrap())
+}
+
+fn comments() -> &'static Regex {
+    regex(&COMMENTS, r"(?s)<!--(.*?)-->")
+}
+
+fn scripts() -> &'static Regex {
+    regex(&SCRIPTS, r"(?is)(<script[^>]*>)(.*?)</script>")
+}
+
+fn styles() -> &'static Regex {
+    regex(&STYLES, r"(?is)<style[^>]*>.*?</style>")
+}
+
+fn attributes() -> &'static Regex {
+    regex(&ATTRIBUTES, r#"(?i)([a-z][a-z0-9_:-]*)\s*=\s*(?:"([^"]*)"|'([^']*)')"#)
+}
+
+fn tags() -> &'static Regex {
+    regex(&TAGS, r"(?s)<[^>]*>")
+}
+
+fn entities() -> &'static Regex {
+    regex(&ENTITIES, r"&#?[a-zA-Z0-9]+;")
+}
+
+fn js_paths() -> &'static Regex {
+    regex(&JS_PATHS, r#"["'`](\.{0,2}/[^"'`\s<>]*)["'`]"#)
+}
+
+const SKIPPED_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "ico", "bmp", "webp", "woff", "woff2", "ttf", "eot", "otf", "pdf",
+    "zip", "gz", "tar", "mp3", "mp4", "avi", "mov", "webm", "exe", "dmg", "iso",
+];
+
+pub fn is_html(content_type: &str) -> bool {
+    content_type.is_empty() || content_type.contains("html")
+}
+
+pub fn is_text(content_type: &str) -> bool {
+    content_type.is_empty()
+        || content_type.starts_with("text/")
+        || content_type.contains("javascript")
+        || content_type.contains("json")
+        || content_type.contains("xml")
+}
+
+pub fn tokenize(text: &str, min_length: usize) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric() && c != '_' && c != '-')
+        .map(|token| token.trim_matches('-'))
+        .filter(move |token| {
+            token.chars().count() >= min_length && token.chars().any(char::is_alphabetic)
+        })
+        .map(|token| token.to_owned())
+}
+
+pub fn extract_words(body: &str, content_type: &str, min_length: usize) -> Vec<String> {
+    if !is_text(content_type) {
+        return Vec::new();
+    }
+
+    if !is_html(content_type) {
+        return tokenize(body, min_length).collect();
+    }
+
+    let mut words = Vec::new();
+    for capture in comments().captures_iter(body) {
+        words.extend(tokenize(&capture[1], min_length));
+    }
+
+    for capture in scripts().captures_iter(body) {
+        words.extend(tokenize(&capture[2], min_length));
+    }
+
+    let markup = comments().replace_all(body, " ");
+    let markup = scripts().replace_all(&markup, "$1 ");
+    let markup = styles().replace_all(&markup, " ");
+    for tag in tags().find_iter(&markup) {
+        for capture in attributes().captures_iter(tag.as_str()) {
+            let value = capture.get(2).or_else(|| capture.get(3)).unwrap();
+            words.extend(tokenize(value.as_str(), min_length));
+        }
+    }
+
+    let text = tags().replace_all(&markup, " ");
+    let text = entities().replace_all(&text, " ");
+    words.extend(tokenize(&text, min_length));
+
+    words
+}
+
+pub fn extract_links(base: &Uri, body: &str, content_type: &str) -> Vec<Uri> {
+    if !is_text(content_type) {
+        return Vec::new();
+    }
+
+    let mut links = Vec::new();
+    let mut sources = Vec::new();
+    if is_html(content_type) {
+        let markup = comments().replace_all(body, " ");
+        for tag in tags().find_iter(&scripts().replace_all(&markup, "$1 ")) {
+            for capture in attributes().captures_iter(tag.as_str()) {
+                let name = capture[1].to_lowercase();
+                if name == "href" || name == "src" || name == "action" {
+                    let value = capture.get(2).or_else(|| capture.get(3)).unwrap();
+                    links.extend(resolve(base, value.as_str()));
+                }
+            }
+        }
+
+        sources.extend(scripts().captures_iter(body).map(|capture| capture[2].to_owned()));
+    } else {
+        sources.push(body.to_owned());
+    }
+
+    for source in sources {
+        for capture in js_paths().captures_iter(&source) {
+            links.extend(resolve(base, &capture[1]));
+        }
+    }
+
+    links
+}
+
+pub fn resolve(base: &Uri, link: &str) -> Option<Uri> {
+    let link = link.trim().replace("&amp;", "&");
+    let link = link.split('#').next().unwrap();
+    if link.is_empty() {
+        return None;
+    }
+
+    let scheme = base.scheme_part()?.as_str();
+    let authority = base.authority_part()?.as_str();
+    let lowercase = link.to_lowercase();
+    let absolute = if lowercase.starts_with("http://") || lowercase.starts_with("https://") {
+        link.to_owned()
+    } else if link.starts_with("//") {
+        format!("{}:{}", scheme, link)
+    } else if link.split(['/', '?']).next().unwrap().contains(':') {
+        return None;
+    } else if link.starts_with('/') {
+        format!("{}://{}{}", scheme, authority, normalize(link))
+    } else {
+        let path = base.path();
+        let directory = &path[..path.rfind('/').map_or(0, |index| index + 1)];
+        format!("{}://{}{}", scheme, authority, normalize(&format!("{}{}", directory, link)))
+    };
+
+    absolute.parse::<Uri>().ok()
+}
+
+fn normalize(path: &str) -> String {
+    let (path, query) = match path.find('?') {
+        Some(index) => path.split_at(index),
+        None => (path, ""),
+    };
+
+    let mut segments: Vec<&str> = Vec::new();
+    let trailing = path.ends_with('/') || path.ends_with("/.") || path.ends_with("/..");
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => (),
+            ".." => {
+                segments.pop();
+            }
+            _ => segments.push(segment),
+        }
+    }
+
+    let mut normalized = format!("/{}", segments.join("/"));
+    if trailing && !segments.is_empty() {
+        normalized.push('/');
+    }
+
+    format!("{}{}", normalized, query)
+}
+
+pub fn should_crawl(start: &Uri, uri: &Uri) -> bool {
+    let extension = uri
+        .path()
+        .rsplit('/')
+        .next()
+        .and_then(|name| name.rsplit_once('.'))
+        .map(|(_, extension)| extension.to_lowercase());
+
+    start.authority_part() == uri.authority_part()
+        && start.scheme_part() == uri.scheme_part()
+        && !matches!(extension, Some(extension) if SKIPPED_EXTENSIONS.contains(&extension.as_str()))
+}
+
+pub fn build_wordlist(frequencies: &HashMap<String, usize>, min_frequency: usize) -> Vec<String> {
+    let mut words = frequencies
+        .iter()
+        .filter(|(_, frequency)| **frequency >= min_frequency)
+        .collect::<Vec<_>>();
+    words.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    words.into_iter().map(|(word, _)| word.clone()).collect()
+}
+
+pub fn save_wordlist(path: &str, words: &[String]) {
+    let mut file = match File::create(Path::new(path)) {
+        Ok(f) => f,
+        Err(e) => {
+            error!("Error while creating file: {}\n{}", path, e);
+            return;
+        }
+    };
+
+    let content = words
+        .iter()
+        .map(|word| format!("{}\n", word))
+        .collect::<String>();
+    match file.write_all(content.as_bytes()) {
+        Ok(_) => debug!("Wordlist saved to: {}", path),
+        Err(e) => error!("Error while writing results to file: {}\n{}", path, e),
+    };
+}
@@ -1,22 +1,31 @@
-use futures::Stream;
+use futures::{sync::mpsc, Stream};
 use hyper::{
-    client::HttpConnector,
     rt::{self, Future},
     Body, Client, Method, Request, StatusCode, Uri,
 };
 use hyper_tls::{self, HttpsConnector};
-use native_tls;
+use std::collections::{HashMap, HashSet};
 use std::sync::mpsc::channel;
 use std::sync::mpsc::Sender;
-use std::{time::SystemTime};
+use std::time::SystemTime;
 use indicatif::{ProgressBar, ProgressStyle};
 use console::style;
 
+mod spec;
 pub mod result_processor;
 pub mod utils;
 
-use result_processor::{SingleDirScanResult, ResultProcessorConfig, ScanResult};
-use utils::{save_dir_results, build_urls};
+use result_processor::{ResultProcessorConfig, ScanResult, SingleDirScanResult};
+use crate::calibration::{save_calibration, Calibration};
+use crate::matcher::{format_headers, Matcher, NumericRange};
+use crate::output::{JsonlWriter, OutputFormat};
+use crate::resume::{is_interrupted, receive, Cursor, InFlight, ResumeState};
+use crate::proxy::{build_client, ProxyConnector, Replayer};
+use crate::retry::{send_request, RetryPolicy};
+use crate::targets::{self, interleave, target_of};
+use crate::throttle::{is_throttled, parse_retry_after, HostLimiter, Throttle, MAX_REQUEUES};
+use crate::wordlist::WordlistOptions;
+use utils::{build_calibration_urls, build_urls, count_urls, directory_url, save_dir_results};
 
 #[derive(Debug, Clone)]
 pub struct DirBuster {
@@ -27,14 +36,35 @@ pub struct DirBuster {
     pub user_agent: String,
     pub http_headers: Vec<(String, String)>,
     pub url: String,
-    pub wordlist_path: String,
+    pub targets: Vec<String>,
+    pub host_threads: usize,
+    pub wordlist_paths: Vec<String>,
+    pub wordlist_options: WordlistOptions,
     pub extensions: Vec<String>,
     pub append_slash: bool,
-    pub include_status_codes: Vec<String>,
-    pub ignore_status_codes: Vec<String>,
+    pub recursion_depth: usize,
+    pub auto_calibrate: bool,
+    pub matcher: Matcher,
     pub no_progress_bar: bool,
     pub exit_on_connection_errors: bool,
     pub output: String,
+    pub output_format: OutputFormat,
+    pub proxy: String,
+    pub replay_proxy: String,
+    pub requeue: bool,
+    pub rate: u64,
+    pub delay: Option<NumericRange>,
+    pub timeout: u64,
+    pub retries: usize,
+    pub resume: ResumeState,
+}
+
+#[derive(Debug, Clone)]
+pub struct DirRequest {
+    pub uri: Uri,
+    pub target: String,
+    pub depth: usize,
+    pub parent: Option<String>,
 }
 
 fn color_by_status_code(code: &str) -> String {
@@ -57,55 +87,229 @@ fn color_by_status_code(code: &str) -> String {
 }
 
 impl DirBuster {
+    fn targets(&self) -> Vec<String> {
+        if self.targets.is_empty() {
+            vec![self.url.clone()]
+        } else {
+            self.targets.clone()
+        }
+    }
+
     pub fn run(self) {
-        let mut current_numbers_of_request = 0;
+        let resume = self.resume.clone();
+        let mut current_numbers_of_request = resume.offset + resume.recursion_offset;
         let start_time = SystemTime::now();
         let output = self.output.clone();
+        let output_format = self.output_format;
+        let mut jsonl_writer = JsonlWriter::new(&self.output, self.output_format);
         let no_progress_bar = self.no_progress_bar;
         let exit_on_connection_errors = self.exit_on_connection_errors;
         let n_threads = self.n_threads;
-        let urls = build_urls(
-            &self.wordlist_path,
-            &self.url,
-            &self.extensions,
-            self.append_slash,
-        );
-        let total_numbers_of_request = urls.len();
+        let recursion_depth = self.recursion_depth;
+        let wordlist_paths = self.wordlist_paths.clone();
+        let wordlist_options = self.wordlist_options.clone();
+        let extensions = self.extensions.clone();
+        let append_slash = self.append_slash;
+        let targets = self.targets();
+        let cursor = Cursor::default();
+        let recursion_cursor = Cursor::default();
+        let urls = cursor
+            .track(interleave(
+                targets
+                    .iter()
+                    .map(|target| {
+                        let target = target.clone();
+                        let urls = build_urls(
+                            &self.wordlist_paths,
+                            &self.wordlist_options,
+                            &target,
+                            &self.extensions,
+                            self.append_slash,
+                        );
+                        Box::new(urls.map(move |uri| (target.clone(), uri)))
+                            as Box<dyn Iterator<Item = (String, Uri)> + Send>
+                    })
+                    .collect(),
+            ))
+            .skip(resume.offset);
+        let in_flight = InFlight::default();
+        let stream_in_flight = in_flight.clone();
+        let mut total_numbers_of_request = targets
+            .iter()
+            .map(|target| {
+                count_urls(
+                    &self.wordlist_paths,
+                    &self.wordlist_options,
+                    target,
+                    &self.extensions,
+                    self.append_slash,
+                )
+            })
+            .sum::<usize>();
+        let mut queued_directories = HashSet::new();
+        for target in targets.iter() {
+            if target.ends_with("/") {
+                queued_directories.insert(target.clone());
+            } else {
+                queued_directories.insert(format!("{}/", target));
+            }
+        }
         let (tx, rx) = channel::<SingleDirScanResult>();
-        let mut tls_connector_builder = native_tls::TlsConnector::builder();
-        tls_connector_builder.danger_accept_invalid_certs(self.ignore_certificate);
-        let tls_connector = tls_connector_builder
-            .build()
-            .expect("TLS initialization failed");
-        let mut http_connector = HttpConnector::new(self.n_threads);
-        http_connector.enforce_http(false);
-        let https_connector = HttpsConnector::from((http_connector, tls_connector));
-        let client = Client::builder().build(https_connector);
-        let rp_config = ResultProcessorConfig {
-            include: self.include_status_codes.clone(),
-            ignore: self.ignore_status_codes.clone(),
+        let client = build_client(self.n_threads, self.ignore_certificate, &self.proxy);
+        let replayer = Replayer::new(self.n_threads, self.ignore_certificate, &self.replay_proxy);
+        let replay_buster = self.clone();
+        let throttle = Throttle::new(self.rate, self.delay.clone());
+        let calibration_throttle = throttle.clone();
+        let limiter = HostLimiter::new(self.host_threads);
+        let calibration_limiter = limiter.clone();
+        let controller = throttle.clone();
+        let requeue = self.requeue;
+        let matcher = self.matcher.clone();
+        let auto_calibrate = self.auto_calibrate;
+        let calibration_urls = if self.auto_calibrate {
+            targets
+                .iter()
+                .flat_map(|target| {
+                    build_calibration_urls(target, &self.extensions, self.append_slash)
+                        .into_iter()
+                        .map(move |uri| (target.clone(), uri))
+                })
+                .collect()
+        } else {
+            Vec::new()
         };
-        let mut result_processor = ScanResult::new(rp_config);
+        let total_calibration_requests = calibration_urls.len();
+        let (tx_calibration, rx_calibration) = channel::<SingleDirScanResult>();
         let bar = if self.no_progress_bar {
             ProgressBar::hidden()
         } else {
             ProgressBar::new(total_numbers_of_request as u64)
         };
+        bar.set_position(current_numbers_of_request as u64);
         bar.set_draw_delta(100);
         bar.set_style(ProgressStyle::default_bar()
             .template("{spinner} [{elapsed_precise}] {bar:40.red/white} {pos:>7}/{len:7} ETA: {eta_precise} req/s: {msg}")
             .progress_chars("#>-"));
 
-        let stream = futures::stream::iter_ok(urls)
+        let (tx_directories, rx_directories) = mpsc::unbounded::<(String, usize, String)>();
+        let mut directories = Vec::new();
+        for (directory, depth) in resume.directories.iter() {
+            queued_directories.insert(directory.clone());
+            total_numbers_of_request += count_urls(&wordlist_paths, &wordlist_options, directory, &extensions, append_slash);
+            directories.push((directory.clone(), *depth));
+            tx_directories
+                .unbounded_send((directory.clone(), *depth, target_of(&targets, directory)))
+                .unwrap();
+        }
+        let (tx_requeue, rx_requeue) = mpsc::unbounded::<DirRequest>();
+        let recursion_wordlist_paths = wordlist_paths.clone();
+        let recursion_wordlist_options = wordlist_options.clone();
+        let recursion_extensions = extensions.clone();
+        let stream_recursion_cursor = recursion_cursor.clone();
+        let recursive_requests = rx_directories
+            .map(move |(directory, depth, target)| {
+                let parent = directory.clone();
+                let urls = stream_recursion_cursor.track(build_urls(
+                    &recursion_wordlist_paths,
+                    &recursion_wordlist_options,
+                    &directory,
+                    &recursion_extensions,
+                    append_slash,
+                ));
+
+                futures::stream::iter_ok(urls.map(move |uri| DirRequest {
+                    uri,
+                    target: target.clone(),
+                    depth,
+                    parent: Some(parent.clone()),
+                }))
+            })
+            .flatten()
+            .skip(resume.recursion_offset as u64);
+        let initial_requests = urls.into_iter().map(|(target, uri)| DirRequest {
+            uri,
+            target,
+            depth: 0,
+            parent: None,
+        });
+
+        let calibration_buster = self.clone();
+        let calibration_client = client.clone();
+        let calibration_requests = calibration_urls.into_iter().map(|(target, uri)| DirRequest {
+            uri,
+            target,
+            depth: 0,
+            parent: None,
+        });
+        let calibration_stream = futures::stream::iter_ok::<_, ()>(calibration_requests)
             .map(move |request| {
-                self.make_request_future(tx.clone(), client.clone(), request)
+                let host = targets::host(&request.target);
+                let request_future = calibration_buster.make_request_future(
+                    tx_calibration.clone(),
+                    calibration_client.clone(),
+                    request,
+                );
+
+                calibration_throttle.run(calibration_limiter.run(&host, request_future))
+            })
+            .buffer_unordered(n_threads)
+            .for_each(Ok);
+
+        let stream = futures::stream::iter_ok(initial_requests)
+            .select(recursive_requests)
+            .select(rx_requeue)
+            .map(move |request| {
+                let host = targets::host(&request.target);
+                let request_future = self.make_request_future(tx.clone(), client.clone(), request);
+                stream_in_flight.track(throttle.run(limiter.run(&host, request_future)))
             })
             .buffer_unordered(n_threads)
             .for_each(Ok)
             .map_err(|err| eprintln!("Err {:?}", err));
 
-        let _ = std::thread::spawn(move || rt::run(stream));
+        let _ = std::thread::spawn(move || rt::run(calibration_stream.then(|_| stream)));
 
+        let mut calibration = Calibration::new();
+        for _ in 0..total_calibration_requests {
+            let msg = match rx_calibration.recv() {
+                Ok(msg) => msg,
+                Err(_err) => {
+                    error!("{:?}", _err);
+                    break;
+                }
+            };
+
+            match &msg.error {
+                Some(e) => error!("{} - {:?}", msg.url, e),
+                None => calibration.add_baseline(msg.fingerprint()),
+            }
+        }
+
+        if auto_calibrate {
+            println!("{}\n", crate::banner::calibration(&calibration));
+            if !output.is_empty() {
+                save_calibration(&output, &calibration);
+            }
+        }
+
+        let calibration = if auto_calibrate {
+            Some(calibration)
+        } else {
+            None
+        };
+        let rp_config = ResultProcessorConfig {
+            matcher,
+            calibration: calibration.clone(),
+        };
+        let mut result_processor = ScanResult::new(rp_config);
+        for result in resume.results::<SingleDirScanResult>() {
+            jsonl_writer.write(&result);
+            result_processor.results.push(result);
+        }
+        let mut failed_requests = Vec::new();
+        let mut requeued = HashMap::new();
+
+        bar.set_length(total_numbers_of_request as u64);
         while current_numbers_of_request != total_numbers_of_request {
             current_numbers_of_request = current_numbers_of_request + 1;
             bar.inc(1);
@@ -119,17 +323,15 @@ impl DirBuster {
                 bar.set_message("warming up...")
             }
 
-            let msg = match rx.recv() {
-                Ok(msg) => msg,
-                Err(_err) => {
-                    error!("{:?}", _err);
-                    break;
-                }
+            let msg = match receive(&rx, &in_flight) {
+                Some(msg) => msg,
+                None => break,
             };
 
             match &msg.error {
                 Some(e) => {
                     error!("{} - {:?}", msg.url, e);
+                    failed_requests.push(msg.url.clone());
                     if current_numbers_of_request == 1 || exit_on_connection_errors
                     {
                         warn!("Check connectivity to the target");
@@ -141,8 +343,60 @@ impl DirBuster {
                 None => (),
             }
 
+            let throttled = is_throttled(&msg.status);
+            controller.observe(throttled, parse_retry_after(&msg.headers));
+            if throttled && requeue {
+                let requeues = requeued.entry(msg.url.clone()).or_insert(0);
+                if *requeues < MAX_REQUEUES && !is_interrupted() {
+                    if let Ok(uri) = msg.url.parse::<Uri>() {
+                        *requeues += 1;
+                        debug!("requeueing {}", msg.url);
+                        total_numbers_of_request += 1;
+                        bar.set_length(total_numbers_of_request as u64);
+                        tx_requeue
+                            .unbounded_send(DirRequest {
+                                uri,
+                                target: msg.target.clone(),
+                                depth: msg.depth,
+                                parent: msg.parent.clone(),
+                            })
+                            .unwrap();
+                        continue;
+                    }
+                }
+
+                failed_requests.push(msg.url.clone());
+                continue;
+            }
+
             let was_added = result_processor.maybe_add_result(msg.clone());
             if was_added {
+                jsonl_writer.write(&msg);
+                if let Ok(uri) = msg.url.parse::<Uri>() {
+                    replayer.replay(replay_buster.build_request(&uri));
+                }
+
+                if msg.depth < recursion_depth {
+                    if let Some(directory) = directory_url(&msg) {
+                        if queued_directories.insert(directory.clone()) {
+                            let requests = count_urls(
+                                &wordlist_paths,
+                                &wordlist_options,
+                                &directory,
+                                &extensions,
+                                append_slash,
+                            );
+                            debug!("recursing into {} ({} requests)", directory, requests);
+                            total_numbers_of_request += requests;
+                            bar.set_length(total_numbers_of_request as u64);
+                            directories.push((directory.clone(), msg.depth + 1));
+                            tx_directories
+                                .unbounded_send((directory, msg.depth + 1, msg.target.clone()))
+                                .unwrap();
+                        }
+                    }
+                }
+
                 let mut extra = msg.extra.unwrap_or("".to_owned());
 
                 if !extra.is_empty() {
@@ -157,22 +411,33 @@ impl DirBuster {
                     _ => 0,
                 };
 
+                let stats = format!(
+                    " [Size: {}, Words: {}, Lines: {}, Type: {}, Time: {}ms]",
+                    msg.content_length,
+                    msg.words,
+                    msg.lines,
+                    msg.content_type.as_deref().unwrap_or("-"),
+                    msg.response_time,
+                );
+
                 if no_progress_bar {
                     println!(
-                        "{}\t{}{}{}{}",
+                        "{}\t{}{}{}{}{}",
                         msg.method,
                         msg.status,
                         "\t".repeat(n_tabs),
                         msg.url,
+                        stats,
                         extra
                     );
                 } else {
                     bar.println(format!(
-                        "{}\t{}{}{}{}",
+                        "{}\t{}{}{}{}{}",
                         msg.method,
                         color_by_status_code(&msg.status),
                         "\t".repeat(n_tabs),
                         msg.url,
+                        style(stats).dim(),
                         extra
                     ));
                 }
@@ -180,50 +445,71 @@ impl DirBuster {
         }
         
         bar.finish();
+        replayer.finish();
+        if !failed_requests.is_empty() {
+            println!("{}", crate::banner::failed_requests(&failed_requests));
+        }
         println!("{}", crate::banner::ending_time());
         
-        if !output.is_empty() {
+        if !output.is_empty() && output_format == OutputFormat::Json {
             save_dir_results(&output, &result_processor.results);
         }
+
+        if is_interrupted() {
+            let state = ResumeState {
+                offset: cursor.position(),
+                recursion_offset: recursion_cursor.position(),
+                directories,
+                results: serde_json::to_value(&result_processor.results).unwrap(),
+                ..resume
+            };
+            state.save();
+            println!("{}", crate::banner::interrupted(&state.path));
+        }
     }
 
     fn make_request_future(
         &self,
         tx: Sender<SingleDirScanResult>,
-        client: Client<HttpsConnector<HttpConnector>>,
-        url: Uri,
+        client: Client<HttpsConnector<ProxyConnector>>,
+        request: DirRequest,
     ) -> impl Future<Item = (), Error = ()> {
         let tx_err = tx.clone();
+        let url = request.uri;
         let mut target = SingleDirScanResult {
             url: url.to_string(),
+            target: request.target,
             method: Method::GET.to_string(),
             status: StatusCode::default().to_string(),
             error: None,
             extra: None,
+            depth: request.depth,
+            parent: request.parent,
+            content_length: 0,
+            words: 0,
+            lines: 0,
+            content_type: None,
+            response_time: 0,
+            headers: String::new(),
+            body: String::new(),
         };
         let mut target_err = target.clone();
-        let mut request_builder = Request::builder();
-        
-        for header_tuple in &self.http_headers {
-            request_builder.header(header_tuple.0.as_str(), header_tuple.1.as_str());
-        }
-        
-        let request = request_builder
-        .header("User-Agent", &self.user_agent[..])
-        .method(&self.http_method[..])
-        .uri(&url)
-        .header("Host", url.host().unwrap())
-        .body(Body::from(self.http_body.clone()))
-        .expect("Request builder");
-        
-        client
-        .request(request)
-        .and_then(move |res| {
-            let status = res.status();
+        let buster = self.clone();
+        let policy = RetryPolicy::new(self.timeout, self.retries);
+
+        send_request(client, policy, move || buster.build_request(&url))
+        .and_then(move |(parts, body, elapsed)| {
+            let status = parts.status;
             target.status = status.to_string();
+            target.content_type = parts
+                .headers
+                .get("Content-Type")
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_owned());
+            target.headers = format_headers(&parts.headers);
             if status.is_redirection() {
                 target.extra = Some(
-                    res.headers()
+                    parts.headers
                     .get("Location")
                     .unwrap()
                     .to_str()
@@ -231,7 +517,14 @@ impl DirBuster {
                     .to_owned(),
                 );
             }
-            
+
+            target.content_length = body.len();
+            let body = String::from_utf8_lossy(&body);
+            target.words = body.split_whitespace().count();
+            target.lines = body.lines().count();
+            target.response_time = elapsed.as_millis() as u64;
+            target.body = body.into_owned();
+
             tx.send(target).unwrap();
             Ok(())
         })
@@ -240,5 +533,21 @@ impl DirBuster {
             tx_err.send(target_err).unwrap_or_else(|_| ());
             Ok(())
         })
-    }    
+    }
+
+    fn build_request(&self, url: &Uri) -> Request<Body> {
+        let mut request_builder = Request::builder();
+
+        for header_tuple in &self.http_headers {
+            request_builder.header(header_tuple.0.as_str(), header_tuple.1.as_str());
+        }
+
+        request_builder
+            .header("User-Agent", &self.user_agent[..])
+            .method(&self.http_method[..])
+            .uri(url)
+            .header("Host", url.host().unwrap())
+            .body(Body::from(self.http_body.clone()))
+            .expect("Request builder")
+    }
 }
@@ -1,18 +1,69 @@
 use serde::{Deserialize, Serialize};
 
+use crate::calibration::{redirect_pattern, Calibration, ResponseFingerprint};
+use crate::matcher::{MatchTarget, Matcher};
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SingleDirScanResult {
     pub url: String,
+    pub target: String,
     pub method: String,
     pub status: String,
     pub error: Option<String>,
     pub extra: Option<String>,
+    pub depth: usize,
+    pub parent: Option<String>,
+    pub content_length: usize,
+    pub words: usize,
+    pub lines: usize,
+    pub content_type: Option<String>,
+    pub response_time: u64,
+    #[serde(skip)]
+    pub headers: String,
+    #[serde(skip)]
+    pub body: String,
+}
+
+impl SingleDirScanResult {
+    pub fn fingerprint(&self) -> ResponseFingerprint {
+        let injected = match self.url.parse::<hyper::Uri>() {
+            Ok(uri) => uri
+                .path()
+                .trim_end_matches('/')
+                .rsplit('/')
+                .next()
+                .unwrap_or("")
+                .to_owned(),
+            Err(_) => "".to_owned(),
+        };
+
+        ResponseFingerprint {
+            target: self.target.clone(),
+            status: self.status.clone(),
+            content_length: self.content_length,
+            words: self.words,
+            lines: self.lines,
+            redirect: redirect_pattern(self.extra.as_deref(), &[injected]),
+        }
+    }
+
+    pub fn match_target(&self) -> MatchTarget<'_> {
+        MatchTarget {
+            status: &self.status,
+            content_length: self.content_length,
+            words: self.words,
+            lines: self.lines,
+            response_time: self.response_time,
+            headers: &self.headers,
+            body: &self.body,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ResultProcessorConfig {
-    pub include: Vec<String>,
-    pub ignore: Vec<String>,
+    pub matcher: Matcher,
+    pub calibration: Option<Calibration>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -31,23 +82,13 @@ impl ScanResult {
 
     pub fn maybe_add_result(&mut self, res: SingleDirScanResult) -> bool {
         trace!("{:?}", res);
-        let mut ignore = false;
-        let mut include = false;
-        for code in self.config.ignore.iter() {
-            if res.status.starts_with(code) {
-                ignore = true;
-                break;
-            }
-        }
-
-        for code in self.config.include.iter() {
-            if res.status.starts_with(code) {
-                include = true;
-                break;
+        if let Some(calibration) = &self.config.calibration {
+            if calibration.matches(&res.fingerprint()) {
+                return false;
             }
         }
 
-        if !ignore && (self.config.include.is_empty() || include) {
+        if self.config.matcher.is_match(&res.match_target()) {
             self.results.push(res);
             return true;
         }
@@ -0,0 +1,175 @@
+use galvanic_test::test_suite;
+
+test_suite! {
+    name dirbuster;
+
+    fn scan_result(
+        url: &str,
+        status: &str,
+        extra: Option<&str>,
+        depth: usize,
+        parent: Option<&str>,
+    ) -> crate::dirbuster::result_processor::SingleDirScanResult {
+        crate::dirbuster::result_processor::SingleDirScanResult {
+            url: url.to_owned(),
+            target: "http://localhost/".to_owned(),
+            method: "GET".to_owned(),
+            status: status.to_owned(),
+            error: None,
+            extra: extra.map(|v| v.to_owned()),
+            depth,
+            parent: parent.map(|v| v.to_owned()),
+            content_length: 0,
+            words: 0,
+            lines: 0,
+            content_type: None,
+            response_time: 0,
+            headers: String::new(),
+            body: String::new(),
+        }
+    }
+
+    fn existing_file() -> crate::dirbuster::result_processor::SingleDirScanResult {
+        scan_result("http://localhost/index.php", "200 OK", None, 0, None)
+    }
+
+    fn serve() -> (String, std::sync::Arc<std::sync::Mutex<Vec<String>>>) {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let paths = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let requested = paths.clone();
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut request = [0u8; 1024];
+                let length = stream.read(&mut request).unwrap();
+                let request = String::from_utf8_lossy(&request[..length]).into_owned();
+                requested.lock().unwrap().push(request.split(' ').nth(1).unwrap_or("").to_owned());
+                stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok").unwrap();
+            }
+        });
+
+        (url, paths)
+    }
+
+    test directory_url_redirect_to_directory() {
+        let result = scan_result("http://localhost/admin", "301 Moved Permanently", Some("/admin/"), 0, None);
+        let actual = crate::dirbuster::utils::directory_url(&result);
+        assert_eq!(Some("http://localhost/admin/".to_owned()), actual);
+    }
+
+    test directory_url_redirect_elsewhere() {
+        let result = scan_result("http://localhost/admin", "302 Found", Some("/login"), 0, None);
+        let actual = crate::dirbuster::utils::directory_url(&result);
+        assert_eq!(None, actual);
+    }
+
+    test directory_url_forbidden_directory() {
+        let result = scan_result("http://localhost/admin/", "403 Forbidden", None, 1, Some("http://localhost/"));
+        let actual = crate::dirbuster::utils::directory_url(&result);
+        assert_eq!(Some("http://localhost/admin/".to_owned()), actual);
+    }
+
+    test directory_url_existing_file() {
+        let actual = crate::dirbuster::utils::directory_url(&existing_file());
+        assert_eq!(None, actual);
+    }
+
+    test save_results_document() {
+        let redirect = scan_result("http://localhost/admin", "301 Moved Permanently", Some("/admin/"), 0, None);
+        let path = std::env::temp_dir().join(format!("rustbuster-{}-dir-results.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        let results = vec![redirect.clone(), existing_file()];
+        crate::dirbuster::utils::save_dir_results(path, &results);
+
+        let document: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        let saved = document.as_array().unwrap();
+        assert_eq!(2, saved.len());
+        assert_eq!("http://localhost/admin", saved[0]["url"]);
+        assert!(!std::path::Path::new(&format!("{}.calibration", path)).exists());
+
+        let calibration = crate::calibration::Calibration {
+            baselines: vec![redirect.fingerprint()],
+        };
+        crate::calibration::save_calibration(path, &calibration);
+        let calibration_path = format!("{}.calibration", path);
+        let saved: crate::calibration::Calibration = serde_json::from_str(&std::fs::read_to_string(&calibration_path).unwrap()).unwrap();
+        assert_eq!(vec![redirect.fingerprint()], saved.baselines);
+        std::fs::remove_file(path).unwrap();
+        std::fs::remove_file(&calibration_path).unwrap();
+    }
+
+    test build_urls_merges_wordlists() {
+        let wordlist_paths = vec!["./examples/wordlist_short".to_owned(), "./examples/wordlist_short".to_owned()];
+        let urls = crate::dirbuster::utils::build_urls(&wordlist_paths, &crate::wordlist::WordlistOptions::default(), "http://localhost/", &[], false)
+            .map(|uri| uri.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["http://localhost/1", "http://localhost/2", "http://localhost/1", "http://localhost/2"], urls);
+
+        let options = crate::wordlist::WordlistOptions {
+            dedup: true,
+            ..crate::wordlist::WordlistOptions::default()
+        };
+        let urls = crate::dirbuster::utils::build_urls(&wordlist_paths, &options, "http://localhost/", &[], false)
+            .map(|uri| uri.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["http://localhost/1", "http://localhost/2"], urls);
+    }
+
+    test resume_interrupted_scan() {
+        let (url, paths) = serve();
+        let output = std::env::temp_dir().join(format!("rustbuster-{}-resumed.json", std::process::id()));
+        let output = output.to_str().unwrap().to_owned();
+        let buster = crate::dirbuster::DirBuster {
+            n_threads: 1,
+            ignore_certificate: false,
+            http_method: "GET".to_owned(),
+            http_body: "".to_owned(),
+            user_agent: "ua".to_owned(),
+            http_headers: vec![],
+            url: url.clone(),
+            targets: vec![],
+            host_threads: 0,
+            wordlist_paths: vec!["./examples/wordlist_short".to_owned()],
+            wordlist_options: crate::wordlist::WordlistOptions::default(),
+            extensions: vec![],
+            append_slash: false,
+            recursion_depth: 1,
+            auto_calibrate: false,
+            matcher: crate::matcher::Matcher::default(),
+            no_progress_bar: true,
+            exit_on_connection_errors: false,
+            output: output.clone(),
+            output_format: crate::output::OutputFormat::Json,
+            proxy: "".to_owned(),
+            replay_proxy: "".to_owned(),
+            requeue: false,
+            rate: 0,
+            delay: None,
+            timeout: 10000,
+            retries: 0,
+            resume: crate::resume::ResumeState {
+                offset: 1,
+                recursion_offset: 1,
+                directories: vec![(format!("{}d/", url), 1)],
+                results: serde_json::to_value(vec![existing_file()]).unwrap(),
+                ..crate::resume::ResumeState::default()
+            },
+        };
+        buster.run();
+
+        let mut requested = paths.lock().unwrap().clone();
+        requested.sort();
+        assert_eq!(vec!["/2", "/d/2"], requested);
+
+        let results: Vec<crate::dirbuster::result_processor::SingleDirScanResult> =
+            serde_json::from_str(&std::fs::read_to_string(&output).unwrap()).unwrap();
+        std::fs::remove_file(&output).unwrap();
+        let mut urls = results.iter().map(|result| result.url.clone()).collect::<Vec<_>>();
+        urls.sort();
+        assert_eq!(vec![format!("{}2", url), format!("{}d/2", url), existing_file().url], urls);
+    }
+}
@@ -1,75 +1,108 @@
-use std::{fs, fs::File, io::Write, path::Path, str};
+use std::{fs::File, io::Write, path::Path, str};
 
 use super::result_processor::SingleDirScanResult;
+use crate::calibration::{random_token, CALIBRATION_REQUESTS};
+use crate::wordlist::{self, WordlistOptions};
 
 pub fn build_urls(
-    wordlist_path: &str,
+    wordlist_paths: &[String],
+    wordlist_options: &WordlistOptions,
     url: &str,
-    extensions: &Vec<String>,
+    extensions: &[String],
     append_slash: bool,
-) -> Vec<hyper::Uri> {
+) -> impl Iterator<Item = hyper::Uri> {
     debug!("building urls");
-    let mut urls: Vec<hyper::Uri> = Vec::new();
-    let wordlist =
-        fs::read_to_string(wordlist_path).expect("Something went wrong reading the wordlist file");
-    let urls_iter = wordlist
-        .lines()
-        .filter(|word| !word.starts_with('#') && !word.starts_with(' '))
-        .map(|word| {
-            if url.ends_with("/") {
-                format!("{}{}", url, word)
-            } else {
-                format!("{}/{}", url, word)
-            }
-        });
-
-    for url in urls_iter {
-        if append_slash {
-            if !url.ends_with("/") {
-                match format!("{}/", url).parse::<hyper::Uri>() {
-                    Ok(v) => {
-                        urls.push(v);
-                    }
-                    Err(e) => {
-                        trace!("URI: {}", e);
-                    }
-                }
-            }
-        }
+    let words = wordlist::merge(wordlist_paths, wordlist_options);
 
-        match url.parse::<hyper::Uri>() {
-            Ok(v) => {
-                urls.push(v);
-            }
-            Err(e) => {
-                trace!("URI: {}", e);
-            }
+    build_word_urls(words, url, extensions, append_slash)
+}
+
+pub fn count_urls(
+    wordlist_paths: &[String],
+    wordlist_options: &WordlistOptions,
+    url: &str,
+    extensions: &[String],
+    append_slash: bool,
+) -> usize {
+    build_urls(wordlist_paths, wordlist_options, url, extensions, append_slash).count()
+}
+
+pub fn build_calibration_urls(
+    url: &str,
+    extensions: &[String],
+    append_slash: bool,
+) -> Vec<hyper::Uri> {
+    debug!("building calibration urls");
+    let tokens = (0..CALIBRATION_REQUESTS).map(|_| random_token());
+
+    build_word_urls(tokens, url, extensions, append_slash).collect()
+}
+
+fn build_word_urls(
+    words: impl Iterator<Item = String>,
+    url: &str,
+    extensions: &[String],
+    append_slash: bool,
+) -> impl Iterator<Item = hyper::Uri> {
+    let url = url.to_owned();
+    let extensions = extensions.to_vec();
+
+    words.flat_map(move |word| {
+        let url = if url.ends_with("/") {
+            format!("{}{}", url, word)
+        } else {
+            format!("{}/{}", url, word)
+        };
+        let mut candidates = Vec::new();
+
+        if append_slash && !url.ends_with("/") {
+            candidates.push(format!("{}/", url));
         }
 
+        candidates.push(url.clone());
+
         for extension in extensions.iter() {
             if append_slash {
-                match format!("{}.{}/", url, extension).parse::<hyper::Uri>() {
-                    Ok(v) => {
-                        urls.push(v);
-                    }
-                    Err(e) => {
-                        trace!("URI: {}", e);
-                    }
-                }
+                candidates.push(format!("{}.{}/", url, extension));
             }
 
-            match format!("{}.{}", url, extension).parse::<hyper::Uri>() {
-                Ok(v) => {
-                    urls.push(v);
-                }
+            candidates.push(format!("{}.{}", url, extension));
+        }
+
+        candidates
+            .into_iter()
+            .filter_map(|candidate| match candidate.parse::<hyper::Uri>() {
+                Ok(v) => Some(v),
                 Err(e) => {
                     trace!("URI: {}", e);
+                    None
                 }
+            })
+    })
+}
+
+pub fn directory_url(result: &SingleDirScanResult) -> Option<String> {
+    let uri = match result.url.parse::<hyper::Uri>() {
+        Ok(v) => v,
+        Err(_) => return None,
+    };
+
+    match result.status.get(..3) {
+        Some("301") | Some("302") => match &result.extra {
+            Some(location) if location.ends_with(&format!("{}/", uri.path())) => {
+                Some(format!("{}/", result.url))
+            }
+            _ => None,
+        },
+        Some(code) if code.starts_with('2') || code == "403" => {
+            if uri.path().ends_with('/') {
+                Some(result.url.to_owned())
+            } else {
+                None
             }
         }
+        _ => None,
     }
-
-    urls
 }
 
 pub fn save_dir_results(path: &str, results: &Vec<SingleDirScanResult>) {
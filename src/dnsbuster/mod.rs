@@ -1,90 +1,230 @@
-use futures::{future, Future, Stream};
+use futures::{
+    future::{self, Either},
+    Future, Stream,
+};
 use hyper::rt;
-use std::{net::ToSocketAddrs, sync::mpsc::Sender};
+use std::{collections::HashSet, net::{IpAddr, SocketAddr}, sync::mpsc::Sender};
 use std::sync::mpsc::channel;
 use std::{time::SystemTime};
 use indicatif::{ProgressBar, ProgressStyle};
 
 pub mod result_processor;
+pub mod takeover;
 pub mod utils;
-use result_processor::{SingleDnsScanResult, DnsScanResult};
+pub mod wildcard;
+pub mod zone;
+mod spec;
+use result_processor::{DnsRecord, SingleDnsScanResult, DnsScanResult};
 use utils::{build_domains, save_dns_results};
+use takeover::{Fingerprint, TakeoverChecker};
+use wildcard::{is_wildcard, parent_domain, WildcardDetector};
+use zone::{transfer_zones, zone_results};
+use crate::matcher::NumericRange;
+use crate::output::{JsonlWriter, OutputFormat};
+use crate::proxy::build_client;
+use crate::resolver::Resolver;
+use crate::resume::{is_interrupted, receive, Cursor, InFlight, ResumeState};
+use crate::retry::RetryPolicy;
+use crate::targets::interleave;
+use crate::throttle::{HostLimiter, Throttle};
+use crate::wordlist::{self, WordlistOptions};
 
 #[derive(Debug, Clone)]
 pub struct DnsBuster {
     pub n_threads: usize,
-    pub wordlist_path: String,
+    pub wordlist_paths: Vec<String>,
+    pub wordlist_options: WordlistOptions,
     pub domain: String,
+    pub targets: Vec<String>,
+    pub host_threads: usize,
     pub no_progress_bar: bool,
+    pub exit_on_connection_errors: bool,
     pub output: String,
+    pub output_format: OutputFormat,
+    pub resolvers: Vec<SocketAddr>,
+    pub record_types: Vec<u16>,
+    pub zone_transfer: bool,
+    pub takeover_fingerprints: Option<Vec<Fingerprint>>,
+    pub rate: u64,
+    pub delay: Option<NumericRange>,
+    pub timeout: u64,
+    pub retries: usize,
+    pub resume: ResumeState,
 }
 
 impl DnsBuster {
+    fn targets(&self) -> Vec<String> {
+        if self.targets.is_empty() {
+            vec![self.domain.clone()]
+        } else {
+            self.targets.clone()
+        }
+    }
 
     fn make_request_future(
         &self,
         tx: Sender<SingleDnsScanResult>,
+        resolver: &Resolver,
+        detector: &WildcardDetector,
+        checker: &Option<TakeoverChecker>,
+        target: String,
         domain: String,
     ) -> impl Future<Item = (), Error = ()> {
-        future::lazy(move || {
-            match domain.to_socket_addrs() {
-                Ok(v) => {
-                    debug!("{:?}", v);
-                    let mut addrs: Vec<std::net::SocketAddr> = Vec::new();
-                    for addr in v {
-                        addrs.push(addr);
-                    }
-    
-                    let result = SingleDnsScanResult {
-                        domain,
-                        status: true,
-                        extra: Some(addrs),
-                    };
-                    tx.send(result).unwrap();
+        let lookup = resolver.lookup(&domain, &self.record_types).then(Ok::<_, ()>);
+        let wildcard = detector.detect(parent_domain(&domain));
+        let checker = checker.clone();
+
+        lookup.join(wildcard).and_then(move |(lookup, wildcard)| {
+            let mut result = SingleDnsScanResult {
+                domain,
+                target,
+                status: false,
+                extra: None,
+                error: None,
+                wildcard,
+                takeover: None,
+            };
+
+            let records = match lookup {
+                Err(e) => {
+                    result.error = Some(e.to_string());
+                    Vec::new()
                 }
-                Err(_e) => {
-                    let result = SingleDnsScanResult {
-                        domain,
-                        status: false,
-                        extra: None,
-                    };
-    
-                    tx.send(result).unwrap();
+                Ok(records) => {
+                    debug!("{:?}", records);
+                    let addrs = records
+                        .iter()
+                        .filter_map(|record| record.data.ip())
+                        .collect::<Vec<IpAddr>>();
+
+                    if !records.is_empty() && !is_wildcard(&result.wildcard, &addrs) {
+                        result.status = true;
+                        result.extra = Some(records.iter().map(DnsRecord::from).collect());
+                    }
+
+                    records
                 }
             };
-    
-            Ok(())
+
+            let takeover = match checker {
+                Some(ref checker) if result.status => Either::A(checker.check(&result.domain, &records)),
+                _ => Either::B(future::ok(None)),
+            };
+
+            takeover.map(move |takeover| {
+                result.takeover = takeover;
+                let _ = tx.send(result);
+            })
         })
     }
-    
+
     pub fn run(self) {
-        let mut current_numbers_of_request = 0;
+        let resume = self.resume.clone();
+        let mut current_numbers_of_request = resume.offset;
         let start_time = SystemTime::now();
         let output = self.output.clone();
+        let output_format = self.output_format;
+        let mut jsonl_writer = JsonlWriter::new(&self.output, self.output_format);
         let no_progress_bar = self.no_progress_bar;
+        let exit_on_connection_errors = self.exit_on_connection_errors;
         let n_threads = self.n_threads;
-        let domains = build_domains(&self.wordlist_path, &self.domain);
-        let total_numbers_of_request = domains.len();
+        let resolver = Resolver::new(&self.resolvers, RetryPolicy::new(self.timeout, self.retries));
+        let detector = WildcardDetector::new(resolver.clone());
+        let checker = self.takeover_fingerprints.clone().map(|fingerprints| {
+            TakeoverChecker::new(
+                fingerprints,
+                resolver.clone(),
+                build_client(self.n_threads, true, ""),
+                RetryPolicy::new(self.timeout, 0),
+            )
+        });
+        let targets = self.targets();
+        let zones = if self.zone_transfer {
+            transfer_zones(&resolver, &targets)
+        } else {
+            vec![None; targets.len()]
+        };
+        let mut transferred = Vec::new();
+        for (target, zone) in targets.iter().zip(zones.iter()) {
+            if self.zone_transfer {
+                println!(
+                    "{}",
+                    crate::banner::zone_transfer(
+                        target,
+                        zone.as_ref().map(|zone| (zone.nameserver.as_str(), zone.records.len()))
+                    )
+                );
+            }
+            if let Some(zone) = zone {
+                transferred.extend(zone_results(target, zone));
+            }
+        }
+        let targets = targets
+            .into_iter()
+            .zip(zones)
+            .filter(|(_, zone)| zone.is_none())
+            .map(|(target, _)| target)
+            .collect::<Vec<String>>();
+        let cursor = Cursor::default();
+        let domains = cursor
+            .track(interleave(
+                targets
+                    .iter()
+                    .map(|target| {
+                        let target = target.clone();
+                        let domains = build_domains(&self.wordlist_paths, &self.wordlist_options, &target);
+                        Box::new(domains.map(move |domain| (target.clone(), domain)))
+                            as Box<dyn Iterator<Item = (String, String)> + Send>
+                    })
+                    .collect(),
+            ))
+            .skip(resume.offset);
+        let in_flight = InFlight::default();
+        let stream_in_flight = in_flight.clone();
+        let total_numbers_of_request =
+            wordlist::merge(&self.wordlist_paths, &self.wordlist_options).count() * targets.len();
         let (tx, rx) = channel::<SingleDnsScanResult>();
         let mut result_processor = DnsScanResult::new();
+        for result in resume.results::<SingleDnsScanResult>() {
+            jsonl_writer.write(&result);
+            result_processor.results.push(result);
+        }
+        for result in transferred {
+            for line in result_lines(&result) {
+                println!("{}", line);
+            }
+            if resume.offset == 0 {
+                jsonl_writer.write(&result);
+                result_processor.maybe_add_result(result);
+            }
+        }
+        let mut failed_requests = Vec::new();
+        let mut wildcards = HashSet::new();
 
         let bar = if self.no_progress_bar {
             ProgressBar::hidden()
         } else {
             ProgressBar::new(total_numbers_of_request as u64)
         };
+        bar.set_position(current_numbers_of_request as u64);
         bar.set_draw_delta(25);
         bar.set_style(ProgressStyle::default_bar()
             .template("{spinner} [{elapsed_precise}] {bar:40.red/white} {pos:>7}/{len:7} ETA: {eta_precise} req/s: {msg}")
             .progress_chars("#>-"));
 
+        let throttle = Throttle::new(self.rate, self.delay.clone());
+        let limiter = HostLimiter::new(self.host_threads);
         let stream = futures::stream::iter_ok(domains)
-            .map(move |url| self.make_request_future(tx.clone(), url))
+            .map(move |(target, url)| {
+                let request_future = self.make_request_future(tx.clone(), &resolver, &detector, &checker, target.clone(), url);
+                let request_future = limiter.run(&target, request_future);
+                stream_in_flight.track(throttle.wait().and_then(move |_| request_future))
+            })
             .buffer_unordered(n_threads)
             .for_each(Ok)
             .map_err(|err| eprintln!("Err {:?}", err));
     
-        rt::run(stream);
+        let _ = std::thread::spawn(move || rt::run(stream));
 
         while current_numbers_of_request != total_numbers_of_request {
             current_numbers_of_request = current_numbers_of_request + 1;
@@ -100,57 +240,84 @@ impl DnsBuster {
                 bar.set_message("warming up...")
             }
 
-            let msg = match rx.recv() {
-                Ok(msg) => msg,
-                Err(_err) => {
-                    error!("{:?}", _err);
+            let msg = match receive(&rx, &in_flight) {
+                Some(msg) => msg,
+                None => break,
+            };
+
+            if let Some(e) = &msg.error {
+                error!("{} - {}", msg.domain, e);
+                failed_requests.push(msg.domain.clone());
+                if current_numbers_of_request == 1 || exit_on_connection_errors {
+                    warn!("Check connectivity to the resolvers");
                     break;
                 }
-            };
 
-            result_processor.maybe_add_result(msg.clone());
-            match msg.status {
-                true => {
+                continue;
+            }
+
+            if let Some(addrs) = &msg.wildcard {
+                let parent = parent_domain(&msg.domain);
+                if wildcards.insert(parent.to_owned()) {
+                    let wildcard = crate::banner::wildcard(parent, addrs);
                     if no_progress_bar {
-                        println!("OK\t{}", &msg.domain[..msg.domain.len() - 3]);
+                        println!("{}", wildcard);
                     } else {
-                        bar.println(format!("OK\t{}", &msg.domain[..msg.domain.len() - 3]));
+                        bar.println(wildcard);
                     }
+                }
+            }
 
-                    match msg.extra {
-                        Some(v) => {
-                            for addr in v {
-                                let string_repr = addr.ip().to_string();
-                                match addr.is_ipv4() {
-                                    true => {
-                                        if no_progress_bar {
-                                            println!("\t\tIPv4: {}", string_repr);
-                                        } else {
-                                            bar.println(format!("\t\tIPv4: {}", string_repr));
-                                        }
-                                    }
-                                    false => {
-                                        if no_progress_bar {
-                                            println!("\t\tIPv6: {}", string_repr);
-                                        } else {
-                                            bar.println(format!("\t\tIPv6: {}", string_repr));
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        None => (),
-                    }
+            jsonl_writer.write(&msg);
+            result_processor.maybe_add_result(msg.clone());
+            for line in result_lines(&msg) {
+                if no_progress_bar {
+                    println!("{}", line);
+                } else {
+                    bar.println(line);
                 }
-                false => (),
             }
         }
 
         bar.finish();
+        if !failed_requests.is_empty() {
+            println!("{}", crate::banner::failed_requests(&failed_requests));
+        }
         println!("{}", crate::banner::ending_time());
 
-        if !output.is_empty() {
+        if !output.is_empty() && output_format == OutputFormat::Json {
             save_dns_results(&output, &result_processor.results);
         }
+
+        if is_interrupted() {
+            let state = ResumeState {
+                offset: cursor.position(),
+                results: serde_json::to_value(&result_processor.results).unwrap(),
+                ..resume
+            };
+            state.save();
+            println!("{}", crate::banner::interrupted(&state.path));
+        }
+    }
+}
+
+fn result_lines(result: &SingleDnsScanResult) -> Vec<String> {
+    if !result.status {
+        return Vec::new();
+    }
+
+    let mut lines = vec![format!("OK\t{}", result.domain)];
+    for record in result.extra.iter().flatten() {
+        let label = match record.record_type.as_str() {
+            "A" => "IPv4",
+            "AAAA" => "IPv6",
+            record_type => record_type,
+        };
+        lines.push(format!("\t\t{}: {}", label, record.data));
+    }
+    if let Some(takeover) = &result.takeover {
+        lines.push(format!("\t\t[!] Takeover: {}", takeover));
     }
+
+    lines
 }
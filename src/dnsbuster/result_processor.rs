@@ -1,10 +1,38 @@
 use serde::{Deserialize, Serialize};
 
+use crate::resolver::message::{type_name, Record};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DnsRecord {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub record_type: String,
+    pub ttl: u32,
+    pub data: String,
+}
+
+impl From<&Record> for DnsRecord {
+    fn from(record: &Record) -> Self {
+        DnsRecord {
+            name: record.name.clone(),
+            record_type: type_name(record.rtype),
+            ttl: record.ttl,
+            data: record.data.to_string(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SingleDnsScanResult {
     pub domain: String,
+    pub target: String,
     pub status: bool,
-    pub extra: Option<Vec<std::net::SocketAddr>>,
+    pub extra: Option<Vec<DnsRecord>>,
+    pub error: Option<String>,
+    #[serde(default)]
+    pub wildcard: Option<Vec<std::net::IpAddr>>,
+    #[serde(default)]
+    pub takeover: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -0,0 +1,56 @@
+use galvanic_test::test_suite;
+
+test_suite! {
+    name dnsbuster;
+
+    use crate::dnsbuster::takeover::{builtin_fingerprints, load_fingerprints, Fingerprint};
+    use crate::dnsbuster::wildcard::{is_wildcard, parent_domain};
+    use std::net::IpAddr;
+
+    test parent_domains() {
+        assert_eq!("example.com", parent_domain("www.example.com"));
+        assert_eq!("api.example.com", parent_domain("dev.api.example.com"));
+        assert_eq!("localhost", parent_domain("localhost"));
+    }
+
+    test wildcard_matches() {
+        let wildcard: Option<Vec<IpAddr>> = Some(vec!["10.0.0.1".parse().unwrap(), "::1".parse().unwrap()]);
+        assert!(is_wildcard(&wildcard, &["10.0.0.1".parse().unwrap()]));
+        assert!(is_wildcard(&wildcard, &["10.0.0.1".parse().unwrap(), "::1".parse().unwrap()]));
+        assert!(!is_wildcard(&wildcard, &["10.0.0.1".parse().unwrap(), "10.0.0.2".parse().unwrap()]));
+        assert!(!is_wildcard(&wildcard, &[]));
+        assert!(!is_wildcard(&None, &["10.0.0.1".parse().unwrap()]));
+    }
+
+    test takeover_fingerprints() {
+        let fingerprints = builtin_fingerprints();
+        let pages = fingerprints.iter().find(|fingerprint| fingerprint.matches_cname("foo.GitHub.io.")).unwrap();
+        assert_eq!("GitHub Pages", pages.name);
+        assert_eq!(Some("GitHub Pages".to_owned()), pages.takeover(false, Some("<p>There isn't a GitHub Pages site here.</p>")));
+        assert_eq!(None, pages.takeover(false, Some("<p>Hello</p>")));
+        assert_eq!(None, pages.takeover(true, None));
+
+        let azure = fingerprints.iter().find(|fingerprint| fingerprint.matches_cname("gone.azurewebsites.net")).unwrap();
+        assert_eq!(Some("Azure".to_owned()), azure.takeover(true, None));
+        assert_eq!(None, azure.takeover(false, Some("")));
+        assert!(fingerprints.iter().all(|fingerprint| !fingerprint.matches_cname("www.example.com")));
+    }
+
+    test load_takeover_fingerprints() {
+        let path = std::env::temp_dir().join(format!("rustbuster-fingerprints-{}.json", std::process::id()));
+        std::fs::write(&path, r#"[{"name": "Example", "cnames": ["example.net"], "body": "unclaimed"}]"#).unwrap();
+        let fingerprints = load_fingerprints(path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            vec![Fingerprint {
+                name: "Example".to_owned(),
+                cnames: vec!["example.net".to_owned()],
+                nxdomain: false,
+                body: Some("unclaimed".to_owned()),
+            }],
+            fingerprints
+        );
+        assert!(load_fingerprints("/nonexistent/fingerprints.json").is_err());
+    }
+}
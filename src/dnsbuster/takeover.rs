@@ -0,0 +1,186 @@
+use futures::{
+    future::{self, Either},
+    Future,
+};
+use hyper::{client::Client, Body, Request};
+use hyper_tls::HttpsConnector;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use crate::proxy::ProxyConnector;
+use crate::resolver::message::{Record, RecordData, RCODE_NXDOMAIN, TYPE_A};
+use crate::resolver::Resolver;
+use crate::retry::{send_request, RetryPolicy};
+
+const HTTP_PORT: u16 = 80;
+
+const FINGERPRINTS: &[(&str, &[&str], bool, &str)] = &[
+    ("AWS/S3", &["s3.amazonaws.com", "s3-website"], false, "NoSuchBucket"),
+    ("AWS/Elastic Beanstalk", &["elasticbeanstalk.com"], true, ""),
+    (
+        "Azure",
+        &[
+            "azurewebsites.net",
+            "cloudapp.net",
+            "cloudapp.azure.com",
+            "trafficmanager.net",
+            "blob.core.windows.net",
+            "azureedge.net",
+        ],
+        true,
+        "",
+    ),
+    ("Bitbucket", &["bitbucket.io"], false, "Repository not found"),
+    ("Fastly", &["fastly.net"], false, "Fastly error: unknown domain"),
+    ("Ghost", &["ghost.io"], false, "The thing you were looking for is no longer here, or never was"),
+    ("GitHub Pages", &["github.io"], false, "There isn't a GitHub Pages site here."),
+    ("Heroku", &["herokuapp.com", "herokudns.com"], false, "No such app"),
+    ("Help Scout", &["helpscoutdocs.com"], false, "No settings were found for this company:"),
+    ("Pantheon", &["pantheonsite.io"], false, "The gods are wise, but do not know of the site which you seek."),
+    ("Readme.io", &["readme.io"], false, "Project doesnt exist... yet!"),
+    ("Shopify", &["myshopify.com"], false, "Sorry, this shop is currently unavailable."),
+    ("Surge.sh", &["surge.sh"], false, "project not found"),
+    ("Tumblr", &["domains.tumblr.com"], false, "Whatever you were looking for doesn't currently exist at this address."),
+    ("Unbounce", &["unbouncepages.com"], false, "The requested URL was not found on this server."),
+    ("Zendesk", &["zendesk.com"], false, "Help Center Closed"),
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Fingerprint {
+    pub name: String,
+    pub cnames: Vec<String>,
+    #[serde(default)]
+    pub nxdomain: bool,
+    #[serde(default)]
+    pub body: Option<String>,
+}
+
+impl Fingerprint {
+    pub fn matches_cname(&self, cname: &str) -> bool {
+        let cname = cname.trim_end_matches('.').to_lowercase();
+        self.cnames.iter().any(|pattern| cname.contains(&pattern.to_lowercase()))
+    }
+
+    pub fn takeover(&self, nxdomain: bool, body: Option<&str>) -> Option<String> {
+        let takeover = match (&self.body, body) {
+            _ if nxdomain => self.nxdomain,
+            (Some(pattern), Some(body)) => !pattern.is_empty() && body.contains(pattern.as_str()),
+            _ => false,
+        };
+
+        if takeover {
+            Some(self.name.clone())
+        } else {
+            None
+        }
+    }
+}
+
+pub fn builtin_fingerprints() -> Vec<Fingerprint> {
+    FINGERPRINTS
+        .iter()
+        .map(|(name, cnames, nxdomain, body)| Fingerprint {
+            name: (*name).to_owned(),
+            cnames: cnames.iter().map(|cname| (*cname).to_owned()).collect(),
+            nxdomain: *nxdomain,
+            body: if body.is_empty() {
+                None
+            } else {
+                Some((*body).to_owned())
+            },
+        })
+        .collect()
+}
+
+pub fn load_fingerprints(path: &str) -> Result<Vec<Fingerprint>, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    serde_json::from_str(&content).map_err(|e| format!("{}: {}", path, e))
+}
+
+#[derive(Clone)]
+pub struct TakeoverChecker {
+    fingerprints: Arc<Vec<Fingerprint>>,
+    resolver: Resolver,
+    client: Client<HttpsConnector<ProxyConnector>>,
+    policy: RetryPolicy,
+}
+
+impl TakeoverChecker {
+    pub fn new(
+        fingerprints: Vec<Fingerprint>,
+        resolver: Resolver,
+        client: Client<HttpsConnector<ProxyConnector>>,
+        policy: RetryPolicy,
+    ) -> Self {
+        TakeoverChecker {
+            fingerprints: Arc::new(fingerprints),
+            resolver,
+            client,
+            policy,
+        }
+    }
+
+    pub fn check(&self, domain: &str, records: &[Record]) -> impl Future<Item = Option<String>, Error = ()> + Send {
+        let candidate = records.iter().find_map(|record| match &record.data {
+            RecordData::Cname(cname) => self
+                .fingerprints
+                .iter()
+                .find(|fingerprint| fingerprint.matches_cname(cname))
+                .map(|fingerprint| (cname.clone(), fingerprint.clone())),
+            _ => None,
+        });
+        let (cname, fingerprint) = match candidate {
+            Some(v) => v,
+            None => return Either::A(future::ok(None)),
+        };
+        let addr = records
+            .iter()
+            .find_map(|record| record.data.ip())
+            .map(|ip| SocketAddr::new(ip, HTTP_PORT));
+        let client = self.client.clone();
+        let policy = self.policy;
+        let domain = domain.to_owned();
+
+        Either::B(
+            self.resolver
+                .query(&cname, TYPE_A)
+                .then(move |result| {
+                    let nxdomain = match result {
+                        Ok(message) => message.rcode == RCODE_NXDOMAIN,
+                        Err(e) => {
+                            debug!("unable to resolve {}: {}", cname, e);
+                            false
+                        }
+                    };
+
+                    match addr {
+                        Some(addr) if !nxdomain && fingerprint.body.is_some() => Either::A(
+                            send_request(client, policy, move || build_request(&domain, addr)).then(move |result| {
+                                let body = match result {
+                                    Ok((_, body, _)) => Some(String::from_utf8_lossy(&body).into_owned()),
+                                    Err(e) => {
+                                        debug!("unable to fetch {}: {}", addr, e);
+                                        None
+                                    }
+                                };
+
+                                Ok(fingerprint.takeover(false, body.as_deref()))
+                            }),
+                        ),
+                        _ => Either::B(future::ok(fingerprint.takeover(nxdomain, None))),
+                    }
+                }),
+        )
+    }
+}
+
+fn build_request(domain: &str, addr: SocketAddr) -> Request<Body> {
+    Request::builder()
+        .method("GET")
+        .uri(format!("http://{}/", addr))
+        .header("Host", domain)
+        .body(Body::empty())
+        .expect("Request builder")
+}
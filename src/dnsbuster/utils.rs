@@ -1,15 +1,17 @@
 use std::{fs, io::Write, path};
 
 use super::result_processor::SingleDnsScanResult;
+use crate::wordlist::{self, WordlistOptions};
 
-pub fn build_domains(wordlist_path: &str, url: &str) -> Vec<String> {
+pub fn build_domains(
+    wordlist_paths: &[String],
+    wordlist_options: &WordlistOptions,
+    url: &str,
+) -> impl Iterator<Item = String> {
     debug!("building urls");
-    fs::read_to_string(wordlist_path)
-        .expect("Something went wrong reading the wordlist file")
-        .lines()
-        .filter(|word| !word.starts_with('#') && !word.starts_with(' '))
-        .map(|word| format!("{}.{}:80", word, url))
-        .collect()
+    let url = url.to_owned();
+
+    wordlist::merge(wordlist_paths, wordlist_options).map(move |word| format!("{}.{}", word, url))
 }
 
 pub fn save_dns_results(path: &str, results: &Vec<SingleDnsScanResult>) {
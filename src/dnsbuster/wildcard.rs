@@ -0,0 +1,81 @@
+use futures::{
+    future::{self, Shared},
+    Future,
+};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+
+use crate::calibration::random_token;
+use crate::resolver::Resolver;
+
+const WILDCARD_PROBES: usize = 3;
+
+type Detection = Shared<Box<dyn Future<Item = Option<Vec<IpAddr>>, Error = ()> + Send>>;
+
+#[derive(Clone)]
+pub struct WildcardDetector {
+    resolver: Resolver,
+    detections: Arc<Mutex<HashMap<String, Detection>>>,
+}
+
+impl WildcardDetector {
+    pub fn new(resolver: Resolver) -> Self {
+        WildcardDetector {
+            resolver,
+            detections: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn detect(&self, parent: &str) -> impl Future<Item = Option<Vec<IpAddr>>, Error = ()> + Send {
+        let mut detections = self.detections.lock().unwrap();
+        let detection = detections
+            .entry(parent.to_owned())
+            .or_insert_with(|| probe(&self.resolver, parent).shared())
+            .clone();
+
+        detection.map(|addrs| (*addrs).clone()).map_err(|_| ())
+    }
+}
+
+fn probe(resolver: &Resolver, parent: &str) -> Box<dyn Future<Item = Option<Vec<IpAddr>>, Error = ()> + Send> {
+    let parent = parent.to_owned();
+    let probes = (0..WILDCARD_PROBES)
+        .map(|_| {
+            let name = format!("{}.{}", random_token(), parent);
+            resolver.lookup_ip(&name).then(move |result| {
+                if let Err(e) = &result {
+                    debug!("wildcard probe {} failed: {}", name, e);
+                }
+                Ok::<_, ()>(result.unwrap_or_default())
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Box::new(future::join_all(probes).map(move |answers| {
+        let mut addrs = answers.into_iter().flatten().collect::<Vec<IpAddr>>();
+        addrs.sort();
+        addrs.dedup();
+        debug!("wildcard addresses for {}: {:?}", parent, addrs);
+
+        if addrs.is_empty() {
+            None
+        } else {
+            Some(addrs)
+        }
+    }))
+}
+
+pub fn parent_domain(domain: &str) -> &str {
+    match domain.find('.') {
+        Some(index) => &domain[index + 1..],
+        None => domain,
+    }
+}
+
+pub fn is_wildcard(wildcard: &Option<Vec<IpAddr>>, addrs: &[IpAddr]) -> bool {
+    match wildcard {
+        Some(wildcard) => !addrs.is_empty() && addrs.iter().all(|addr| wildcard.contains(addr)),
+        None => false,
+    }
+}
@@ -0,0 +1,118 @@
+use futures::{
+    future::{self, Loop},
+    Future,
+};
+use hyper::rt;
+use std::net::SocketAddr;
+use std::sync::mpsc::channel;
+
+use super::result_processor::{DnsRecord, SingleDnsScanResult};
+use crate::resolver::message::{Record, RecordData, TYPE_NS};
+use crate::resolver::{Resolver, DNS_PORT};
+
+#[derive(Debug, Clone)]
+pub struct Zone {
+    pub nameserver: String,
+    pub records: Vec<Record>,
+}
+
+pub fn transfer_zones(resolver: &Resolver, targets: &[String]) -> Vec<Option<Zone>> {
+    let (tx, rx) = channel();
+    let transfers = future::join_all(targets.iter().map(|target| transfer_zone(resolver, target)).collect::<Vec<_>>())
+        .map(move |zones| {
+            let _ = tx.send(zones);
+        });
+
+    let _ = std::thread::spawn(move || rt::run(transfers));
+    rx.recv().unwrap_or_else(|_| vec![None; targets.len()])
+}
+
+fn transfer_zone(resolver: &Resolver, domain: &str) -> impl Future<Item = Option<Zone>, Error = ()> + Send {
+    let domain = domain.to_owned();
+    let lookup_resolver = resolver.clone();
+    let transfer_resolver = resolver.clone();
+
+    resolver
+        .query(&domain, TYPE_NS)
+        .map(|message| {
+            message
+                .answers
+                .into_iter()
+                .filter_map(|record| match record.data {
+                    RecordData::Ns(nameserver) => Some(nameserver),
+                    _ => None,
+                })
+                .collect::<Vec<String>>()
+        })
+        .or_else({
+            let domain = domain.clone();
+            move |e| {
+                debug!("unable to lookup the nameservers of {}: {}", domain, e);
+                Ok(Vec::new())
+            }
+        })
+        .and_then(move |nameservers| {
+            future::join_all(
+                nameservers
+                    .into_iter()
+                    .map(|nameserver| {
+                        lookup_resolver.lookup_ip(&nameserver).then(move |result| {
+                            let addrs = result.unwrap_or_default();
+                            Ok::<_, ()>(
+                                addrs
+                                    .into_iter()
+                                    .map(|ip| (nameserver.clone(), SocketAddr::new(ip, DNS_PORT)))
+                                    .collect::<Vec<(String, SocketAddr)>>(),
+                            )
+                        })
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .and_then(move |candidates| {
+            let candidates = candidates.into_iter().flatten().collect::<Vec<(String, SocketAddr)>>();
+
+            future::loop_fn(0, move |index| {
+                let (nameserver, addr) = match candidates.get(index) {
+                    Some(v) => v.clone(),
+                    None => return future::Either::A(future::ok(Loop::Break(None))),
+                };
+                let domain = domain.clone();
+
+                future::Either::B(transfer_resolver.zone_transfer(addr, &domain).then(move |result| {
+                    match result {
+                        Ok(records) => Ok(Loop::Break(Some(Zone {
+                            nameserver: format!("{} ({})", nameserver, addr.ip()),
+                            records,
+                        }))),
+                        Err(e) => {
+                            debug!("zone transfer of {} from {} failed: {}", domain, addr, e);
+                            Ok(Loop::Continue(index + 1))
+                        }
+                    }
+                }))
+            })
+        })
+}
+
+pub fn zone_results(target: &str, zone: &Zone) -> Vec<SingleDnsScanResult> {
+    let mut results: Vec<SingleDnsScanResult> = Vec::new();
+
+    for record in zone.records.iter() {
+        let record = DnsRecord::from(record);
+        match results.iter_mut().find(|result| result.domain == record.name) {
+            Some(result) => result.extra.get_or_insert_with(Vec::new).push(record),
+            None => results.push(SingleDnsScanResult {
+                domain: record.name.clone(),
+                target: target.to_owned(),
+                status: true,
+                extra: Some(vec![record]),
+                error: None,
+                wildcard: None,
+                takeover: None,
+            }),
+        }
+    }
+
+    results
+}
@@ -0,0 +1,88 @@
+use sha2::{Digest, Sha256};
+
+mod spec;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Encoder {
+    UrlEncode,
+    DoubleUrlEncode,
+    Base64,
+    Hex,
+    Md5,
+    Sha1,
+    Sha256,
+    Prefix(String),
+    Suffix(String),
+}
+
+impl Encoder {
+    pub fn parse(name: &str) -> Option<Encoder> {
+        if let Some(prefix) = name.strip_prefix("prefix=") {
+            return Some(Encoder::Prefix(prefix.to_owned()));
+        }
+
+        if let Some(suffix) = name.strip_prefix("suffix=") {
+            return Some(Encoder::Suffix(suffix.to_owned()));
+        }
+
+        match name {
+            "urlencode" => Some(Encoder::UrlEncode),
+            "double-urlencode" => Some(Encoder::DoubleUrlEncode),
+            "base64" => Some(Encoder::Base64),
+            "hex" => Some(Encoder::Hex),
+            "md5" => Some(Encoder::Md5),
+            "sha1" => Some(Encoder::Sha1),
+            "sha256" => Some(Encoder::Sha256),
+            _ => None,
+        }
+    }
+
+    pub fn encode(&self, word: &str) -> String {
+        match self {
+            Encoder::UrlEncode => url_encode(word),
+            Encoder::DoubleUrlEncode => url_encode(&url_encode(word)),
+            Encoder::Base64 => base64::encode(word),
+            Encoder::Hex => to_hex(word.as_bytes()),
+            Encoder::Md5 => format!("{:x}", md5::compute(word)),
+            Encoder::Sha1 => sha1::Sha1::from(word).digest().to_string(),
+            Encoder::Sha256 => to_hex(&Sha256::digest(word.as_bytes())),
+            Encoder::Prefix(prefix) => format!("{}{}", prefix, word),
+            Encoder::Suffix(suffix) => format!("{}{}", word, suffix),
+        }
+    }
+}
+
+pub fn parse_chain(chain: &str) -> Option<Vec<Encoder>> {
+    chain.split(',').map(Encoder::parse).collect()
+}
+
+pub fn split_encoders(wordlist: &str) -> (String, Vec<Encoder>) {
+    match wordlist.rfind('@') {
+        Some(index) => match parse_chain(&wordlist[index + 1..]) {
+            Some(encoders) => (wordlist[..index].to_owned(), encoders),
+            None => (wordlist.to_owned(), Vec::new()),
+        },
+        None => (wordlist.to_owned(), Vec::new()),
+    }
+}
+
+pub fn encode(encoders: &[Encoder], word: &str) -> String {
+    encoders
+        .iter()
+        .fold(word.to_owned(), |acc, encoder| encoder.encode(&acc))
+}
+
+fn url_encode(word: &str) -> String {
+    word.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
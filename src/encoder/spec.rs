@@ -0,0 +1,20 @@
+use galvanic_test::test_suite;
+
+test_suite! {
+    name encoder;
+
+    use crate::encoder::{split_encoders, Encoder};
+
+    test encoders() {
+        assert_eq!("c4ca4238a0b923820dcc509a6f75849b", Encoder::Md5.encode("1"));
+        assert_eq!("356a192b7913b04c54574d18c28d46e6395428ab", Encoder::Sha1.encode("1"));
+        assert_eq!("6b86b273ff34fce19d6b804eff5a3f5747ada4eaa22f1d49c01e52ddb7875b4b", Encoder::Sha256.encode("1"));
+        assert_eq!("%25251", Encoder::DoubleUrlEncode.encode("%1"));
+        assert_eq!("3c2f3e", Encoder::Hex.encode("</>"));
+    }
+
+    test split_wordlist_encoders() {
+        assert_eq!((String::from("users.txt:USER"), vec![Encoder::UrlEncode, Encoder::Base64]), split_encoders("users.txt:USER@urlencode,base64"));
+        assert_eq!((String::from("user@example.com"), vec![]), split_encoders("user@example.com"));
+    }
+}
@@ -1,19 +1,28 @@
-use futures::Stream;
+use futures::{sync::mpsc, Stream};
 use hyper::{
-    client::HttpConnector,
     rt::{self, Future},
     Body, Client, Request, StatusCode,
 };
 use hyper_tls::{self, HttpsConnector};
-use itertools::Itertools;
-use native_tls;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::mpsc::channel;
 use std::sync::mpsc::Sender;
 use std::thread;
-use std::{fs, time::SystemTime};
+use std::time::SystemTime;
 use indicatif::{ProgressBar, ProgressStyle};
 use regex::Regex;
 
+use crate::calibration::{random_token, save_calibration, Calibration, CALIBRATION_REQUESTS};
+use crate::matcher::{format_headers, Matcher, NumericRange};
+use crate::output::{JsonlWriter, OutputFormat};
+use crate::resume::{is_interrupted, receive, Cursor, InFlight, ResumeState};
+use crate::proxy::{build_client, ProxyConnector, Replayer};
+use crate::retry::{send_request, RetryPolicy};
+use crate::targets::{self, interleave};
+use crate::throttle::{is_throttled, parse_retry_after, HostLimiter, Throttle, MAX_REQUEUES};
+use crate::encoder::{self, Encoder};
+use crate::wordlist::{self, CartesianProduct, WordlistOptions};
+
 mod spec;
 pub mod utils;
 pub mod result_processor;
@@ -28,17 +37,39 @@ pub struct FuzzBuster {
     pub user_agent: String,
     pub http_headers: Vec<(String, String)>,
     pub wordlist_paths: Vec<String>,
+    pub wordlist_keywords: Vec<Option<String>>,
+    pub wordlist_encoders: Vec<Vec<Encoder>>,
+    pub wordlist_options: WordlistOptions,
     pub url: String,
-    pub include_status_codes: Vec<String>,
-    pub ignore_status_codes: Vec<String>,
-    pub include_body: Vec<String>,
-    pub ignore_body: Vec<String>,
+    pub targets: Vec<String>,
+    pub host_threads: usize,
+    pub matcher: Matcher,
     pub no_progress_bar: bool,
     pub exit_on_connection_errors: bool,
     pub output: String,
+    pub output_format: OutputFormat,
     pub csrf_url: Option<String>,
     pub csrf_regex: Option<String>,
     pub csrf_headers: Option<Vec<(String, String)>>,
+    pub auto_calibrate: bool,
+    pub mode: FuzzMode,
+    pub default_value: String,
+    pub proxy: String,
+    pub replay_proxy: String,
+    pub requeue: bool,
+    pub rate: u64,
+    pub delay: Option<NumericRange>,
+    pub timeout: u64,
+    pub retries: usize,
+    pub resume: ResumeState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FuzzMode {
+    Sniper,
+    BatteringRam,
+    Pitchfork,
+    ClusterBomb,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -48,7 +79,7 @@ pub struct FuzzRequest {
     pub http_headers: Vec<(String, String)>,
     pub http_body: String,
     pub user_agent: String,
-    pub payload: Vec<String>,
+    pub payload: BTreeMap<String, String>,
     pub csrf_uri: Option<hyper::Uri>,
     pub csrf_regex: Option<String>,
     pub csrf_headers: Option<Vec<(String, String)>>,
@@ -56,47 +87,126 @@ pub struct FuzzRequest {
 
 impl FuzzBuster {
     pub fn run(self) {
+        let resume = self.resume.clone();
+        let cursor = Cursor::new(resume.offset);
+        let in_flight = InFlight::default();
+        let stream_in_flight = in_flight.clone();
         let (tx, rx) = channel::<SingleFuzzScanResult>();
-        let mut tls_connector_builder = native_tls::TlsConnector::builder();
-        tls_connector_builder.danger_accept_invalid_certs(self.ignore_certificate);
-        let tls_connector = tls_connector_builder
-            .build()
-            .expect("TLS initialization failed");
-        let mut http_connector = HttpConnector::new(self.n_threads);
-        http_connector.enforce_http(false);
-        let https_connector = HttpsConnector::from((http_connector, tls_connector));
-        let client = Client::builder().build(https_connector);
+        let client = build_client(self.n_threads, self.ignore_certificate, &self.proxy);
+        let replayer = Replayer::new(self.n_threads, self.ignore_certificate, &self.replay_proxy);
+        let throttle = Throttle::new(self.rate, self.delay.clone());
+        let calibration_throttle = throttle.clone();
+        let controller = throttle.clone();
+        let limiter = HostLimiter::new(self.host_threads);
+        let calibration_limiter = limiter.clone();
+        let (tx_requeue, rx_requeue) = mpsc::unbounded::<(String, FuzzRequest)>();
+        let policy = RetryPolicy::new(self.timeout, self.retries);
         let n_threads = self.n_threads;
-        let rp_config = FuzzScanProcessorConfig {
-            include: self.include_status_codes.clone(),
-            ignore: self.ignore_status_codes.clone(),
-            include_body: self.include_body.clone(),
-            ignore_body: self.ignore_body.clone(),
+        let requests = self.build_target_requests(&cursor);
+        let calibration_requests = if self.auto_calibrate {
+            self.target_busters()
+                .into_iter()
+                .flat_map(|buster| {
+                    let target = buster.url.clone();
+                    buster
+                        .build_calibration_requests()
+                        .into_iter()
+                        .map(move |request| (target.clone(), request))
+                })
+                .collect()
+        } else {
+            Vec::new()
         };
-        let requests = self.build_requests();
-        let mut current_numbers_of_request = 0;
-        let total_numbers_of_request = requests.len();
+        let total_calibration_requests = calibration_requests.len();
+        let (tx_calibration, rx_calibration) = channel::<SingleFuzzScanResult>();
+        let calibration_client = client.clone();
+        let mut current_numbers_of_request = resume.offset;
+        let mut total_numbers_of_request = self
+            .target_busters()
+            .iter()
+            .map(|buster| buster.count_requests())
+            .sum::<usize>();
         let start_time = SystemTime::now();
-        let mut result_processor = FuzzScanProcessor::new(rp_config);
         let bar = if self.no_progress_bar {
             ProgressBar::hidden()
         } else {
-            ProgressBar::new(requests.len() as u64)
+            ProgressBar::new(total_numbers_of_request as u64)
         };
+        bar.set_position(current_numbers_of_request as u64);
         bar.set_draw_delta(100);
         bar.set_style(ProgressStyle::default_bar()
             .template("{spinner} [{elapsed_precise}] {bar:40.red/white} {pos:>7}/{len:7} ETA: {eta_precise} req/s: {msg}")
             .progress_chars("#>-"));
 
+        let calibration_stream = futures::stream::iter_ok::<_, ()>(calibration_requests)
+            .map(move |(target, request)| {
+                let host = targets::host(&target);
+                let request_future = FuzzBuster::make_request_future(
+                    tx_calibration.clone(),
+                    calibration_client.clone(),
+                    policy,
+                    target,
+                    request,
+                );
+
+                calibration_throttle.run(calibration_limiter.run(&host, request_future))
+            })
+            .buffer_unordered(n_threads)
+            .for_each(Ok);
+
         let stream = futures::stream::iter_ok(requests)
-            .map(move |request| {
-                FuzzBuster::make_request_future(tx.clone(), client.clone(), request)
+            .select(rx_requeue)
+            .map(move |(target, request)| {
+                let host = targets::host(&target);
+                let request_future =
+                    FuzzBuster::make_request_future(tx.clone(), client.clone(), policy, target, request);
+                stream_in_flight.track(throttle.run(limiter.run(&host, request_future)))
             })
             .buffer_unordered(n_threads)
             .for_each(Ok)
             .map_err(|err| eprintln!("Err {:?}", err));
 
-        let _ = thread::spawn(move || rt::run(stream));
+        let _ = thread::spawn(move || rt::run(calibration_stream.then(|_| stream)));
+
+        let mut calibration = Calibration::new();
+        for _ in 0..total_calibration_requests {
+            let msg = match rx_calibration.recv() {
+                Ok(msg) => msg,
+                Err(_err) => {
+                    error!("{:?}", _err);
+                    break;
+                }
+            };
+
+            match &msg.error {
+                Some(e) => error!("{} - {:?}", msg.url, e),
+                None => calibration.add_baseline(msg.fingerprint()),
+            }
+        }
+
+        if self.auto_calibrate {
+            println!("{}\n", crate::banner::calibration(&calibration));
+            if !self.output.is_empty() {
+                save_calibration(&self.output, &calibration);
+            }
+        }
+
+        let rp_config = FuzzScanProcessorConfig {
+            matcher: self.matcher.clone(),
+            calibration: if self.auto_calibrate {
+                Some(calibration)
+            } else {
+                None
+            },
+        };
+        let mut result_processor = FuzzScanProcessor::new(rp_config);
+        let mut jsonl_writer = JsonlWriter::new(&self.output, self.output_format);
+        for result in resume.results::<SingleFuzzScanResult>() {
+            jsonl_writer.write(&result);
+            result_processor.results.push(result);
+        }
+        let mut failed_requests = Vec::new();
+        let mut requeued = HashMap::new();
 
         while current_numbers_of_request != total_numbers_of_request {
             current_numbers_of_request = current_numbers_of_request + 1;
@@ -110,17 +220,15 @@ impl FuzzBuster {
                 bar.set_message("warming up...")
             }
 
-            let msg = match rx.recv() {
-                Ok(msg) => msg,
-                Err(_err) => {
-                    error!("{:?}", _err);
-                    break;
-                }
+            let msg = match receive(&rx, &in_flight) {
+                Some(msg) => msg,
+                None => break,
             };
 
             match &msg.error {
                 Some(e) => {
                     error!("{} - {:?}", msg.url, e);
+                    failed_requests.push(format!("{} ({:?})", msg.url, msg.payload));
                     if current_numbers_of_request == 1 || self.exit_on_connection_errors {
                         warn!("Check connectivity to the target");
                         break;
@@ -131,8 +239,33 @@ impl FuzzBuster {
                 None => (),
             }
 
+            let throttled = is_throttled(&msg.status);
+            controller.observe(throttled, parse_retry_after(&msg.headers));
+            if throttled && self.requeue {
+                let key = format!("{} ({:?})", msg.url, msg.payload);
+                let requeues = requeued.entry(key.clone()).or_insert(0);
+                if *requeues < MAX_REQUEUES && !is_interrupted() {
+                    if let Some(request) = msg.original_request {
+                        *requeues += 1;
+                        debug!("requeueing {}", key);
+                        total_numbers_of_request += 1;
+                        bar.set_length(total_numbers_of_request as u64);
+                        tx_requeue.unbounded_send((msg.target.clone(), request)).unwrap();
+                        continue;
+                    }
+                }
+
+                failed_requests.push(key);
+                continue;
+            }
+
             let was_added = result_processor.maybe_add_result(msg.clone());
             if was_added {
+                jsonl_writer.write(&msg);
+                if let Some(request) = &msg.request {
+                    replayer.replay(FuzzBuster::build_hyper_request(request));
+                }
+
                 let mut extra = msg.extra.unwrap_or("".to_owned());
 
                 if !extra.is_empty() {
@@ -172,36 +305,55 @@ impl FuzzBuster {
         }
 
         bar.finish();
+        replayer.finish();
+        if !failed_requests.is_empty() {
+            println!("{}", crate::banner::failed_requests(&failed_requests));
+        }
         println!("{}", crate::banner::ending_time());
 
-        if !self.output.is_empty() {
+        if is_interrupted() {
+            let state = ResumeState {
+                offset: cursor.position(),
+                results: serde_json::to_value(&result_processor.results).unwrap(),
+                ..resume
+            };
+            state.save();
+            println!("{}", crate::banner::interrupted(&state.path));
+        }
+
+        if !self.output.is_empty() && self.output_format == OutputFormat::Json {
             result_processor.save_fuzz_results(&self.output);
         }
     }
 
     fn make_request_future(
         tx: Sender<SingleFuzzScanResult>,
-        client: Client<HttpsConnector<HttpConnector>>,
+        client: Client<HttpsConnector<ProxyConnector>>,
+        policy: RetryPolicy,
+        target_url: String,
         request: FuzzRequest,
     ) -> impl Future<Item = (), Error = ()> {
         let tx_err = tx.clone();
         let tx_err2 = tx.clone();
         let mut target = SingleFuzzScanResult {
             url: request.uri.to_string(),
+            target: target_url,
             method: request.http_method.clone(),
             status: StatusCode::default().to_string(),
             payload: request.payload.clone(),
             body: request.http_body.clone(),
             error: None,
             extra: None,
+            content_length: 0,
+            words: 0,
+            lines: 0,
+            response_time: 0,
+            headers: String::new(),
+            request: None,
+            original_request: Some(request.clone()),
         };
         let mut target_err = target.clone();
         let mut target_err2 = target.clone();
-        let mut request_builder = Request::builder();
-
-        for header_tuple in &request.http_headers {
-            request_builder.header(header_tuple.0.as_str(), header_tuple.1.as_str());
-        }
 
         let csrf_fut = match &request.csrf_uri {
             None => futures::future::Either::A(futures::future::ok::<
@@ -209,33 +361,36 @@ impl FuzzBuster {
                 _,
             >((None, request))),
             Some(uri) => {
-                let mut csrf_request_builder = Request::builder();
-
-                match &request.csrf_headers {
-                    None => (),
-                    Some(v) => {
-                        for header_tuple in v.iter() {
-                            csrf_request_builder
-                                .header(header_tuple.0.as_str(), header_tuple.1.as_str());
+                let uri = uri.clone();
+                let csrf_headers = request.csrf_headers.clone();
+                let user_agent = request.user_agent.clone();
+                let build_csrf_request = move || {
+                    let mut csrf_request_builder = Request::builder();
+
+                    match &csrf_headers {
+                        None => (),
+                        Some(v) => {
+                            for header_tuple in v.iter() {
+                                csrf_request_builder
+                                    .header(header_tuple.0.as_str(), header_tuple.1.as_str());
+                            }
                         }
                     }
-                }
 
-                let hyper_request = csrf_request_builder
-                    .header("User-Agent", &request.user_agent[..])
-                    .method(hyper::Method::GET)
-                    .uri(uri)
-                    .body(Body::from(""))
-                    .expect("Request builder");
+                    csrf_request_builder
+                        .header("User-Agent", &user_agent[..])
+                        .method(hyper::Method::GET)
+                        .uri(&uri)
+                        .body(Body::from(""))
+                        .expect("Request builder")
+                };
                 let csrf_regex = request.csrf_regex.clone();
                 let csrf_regex = &csrf_regex.expect("Missing regex");
                 match Regex::new(&csrf_regex) {
                     Ok(re) => futures::future::Either::B(
-                        client
-                            .request(hyper_request)
-                            .and_then(|res| res.into_body().concat2())
+                        send_request(client.clone(), policy, build_csrf_request)
                             .join3(futures::future::ok(re), futures::future::ok(request))
-                            .and_then(|(body, re, request)| {
+                            .and_then(|((_, body, _), re, request)| {
                                 let vec = body.iter().cloned().collect();
                                 let body = String::from_utf8(vec).unwrap();
                                 match re.captures_iter(&body).take(1).next() {
@@ -249,6 +404,7 @@ impl FuzzBuster {
                     ),
                     Err(e) => {
                         error!("Invalid regex: {}", e);
+                        crate::wordlist::remove_stdin_spool();
                         std::process::exit(-1);
                     }
                 }
@@ -265,21 +421,17 @@ impl FuzzBuster {
                     _ => request,
                 };
 
-                let request = request_builder
-                    .header("User-Agent", &request.user_agent[..])
-                    .method(&request.http_method[..])
-                    .uri(&request.uri)
-                    .body(Body::from(request.http_body.clone()))
-                    .expect("Request builder");
-
-                client
-                    .request(request)
-                    .and_then(move |res| {
-                        let status = res.status();
+                target.request = Some(request.clone());
+
+                send_request(client, policy, move || FuzzBuster::build_hyper_request(&request))
+                    .and_then(move |(parts, body, elapsed)| {
+                        let status = parts.status;
                         target.status = status.to_string();
+                        target.headers = format_headers(&parts.headers);
                         if status.is_redirection() {
                             target.extra = Some(
-                                res.headers()
+                                parts
+                                    .headers
                                     .get("Location")
                                     .unwrap()
                                     .to_str()
@@ -288,12 +440,12 @@ impl FuzzBuster {
                             );
                         }
 
-                        futures::future::ok(target).join(res.into_body().concat2())
-                    })
-                    .and_then(move |(target, body)| {
-                        let mut target = target;
                         let vec = body.iter().cloned().collect();
                         let body = String::from_utf8(vec).unwrap();
+                        target.content_length = body.len();
+                        target.words = body.split_whitespace().count();
+                        target.lines = body.lines().count();
+                        target.response_time = elapsed.as_millis() as u64;
                         target.body = body;
                         tx.send(target.clone()).unwrap();
                         Ok(())
@@ -311,89 +463,361 @@ impl FuzzBuster {
             })
     }
 
-    pub fn build_requests(&self) -> Vec<FuzzRequest> {
+    fn build_hyper_request(request: &FuzzRequest) -> Request<Body> {
+        let mut request_builder = Request::builder();
+
+        for header_tuple in &request.http_headers {
+            request_builder.header(header_tuple.0.as_str(), header_tuple.1.as_str());
+        }
+
+        request_builder
+            .header("User-Agent", &request.user_agent[..])
+            .method(&request.http_method[..])
+            .uri(&request.uri)
+            .body(Body::from(request.http_body.clone()))
+            .expect("Request builder")
+    }
+
+    pub fn build_requests(&self) -> Box<dyn Iterator<Item = FuzzRequest> + Send> {
+        self.build_requests_from(&Cursor::default())
+    }
+
+    pub fn build_requests_from(&self, cursor: &Cursor) -> Box<dyn Iterator<Item = FuzzRequest> + Send> {
         debug!("building requests");
-        let mut requests: Vec<FuzzRequest> = Vec::new();
-        let wordlists_iter = self
-            .wordlist_paths
+        let buster = self.clone();
+        let offset = cursor.position();
+
+        match self.mode {
+            FuzzMode::Sniper => {
+                let wordlist_paths = self.wordlist_paths.clone();
+                let wordlist_options = self.wordlist_options.clone();
+                let positions = self.sniper_positions();
+                let named = self.wordlist_keyword(0).is_some();
+                let words = (0..positions)
+                    .flat_map(move |position| {
+                        let wordlist_path = if named {
+                            &wordlist_paths[position]
+                        } else {
+                            &wordlist_paths[0]
+                        };
+                        wordlist::words(wordlist_path, &wordlist_options)
+                            .map(move |word| (position, word))
+                    })
+                    .skip(offset);
+                Box::new(
+                    cursor
+                        .track(words)
+                        .filter_map(move |(position, word)| {
+                            buster.build_sniper_request(position, word)
+                        }),
+                )
+            }
+            FuzzMode::BatteringRam => Box::new(
+                cursor
+                    .track(wordlist::words(&self.wordlist_paths[0], &self.wordlist_options).skip(offset))
+                    .filter_map(move |word| buster.build_battering_ram_request(word)),
+            ),
+            FuzzMode::Pitchfork => Box::new(
+                cursor
+                    .track(wordlist::pitchfork(&self.wordlist_paths, &self.wordlist_options).skip(offset))
+                    .filter_map(move |words| buster.build_request(words)),
+            ),
+            FuzzMode::ClusterBomb => Box::new(
+                cursor
+                    .track(CartesianProduct::starting_at(
+                        &self.wordlist_paths,
+                        &self.wordlist_options,
+                        offset,
+                    ))
+                    .filter_map(move |words| buster.build_request(words)),
+            ),
+        }
+    }
+
+    fn target_busters(&self) -> Vec<FuzzBuster> {
+        if self.targets.is_empty() {
+            return vec![self.clone()];
+        }
+
+        self.targets
             .iter()
-            .map(|wordlist| {
-                fs::read_to_string(wordlist)
-                    .expect("Something went wrong reading the wordlist file")
-                    .lines()
-                    .filter(|word| !word.starts_with('#') && !word.starts_with(' '))
-                    .map(|x| x.to_owned())
-                    .collect::<Vec<String>>()
+            .map(|target| FuzzBuster {
+                url: target.clone(),
+                ..self.clone()
+            })
+            .collect()
+    }
+
+    pub fn build_target_requests(
+        &self,
+        cursor: &Cursor,
+    ) -> Box<dyn Iterator<Item = (String, FuzzRequest)> + Send> {
+        let busters = self.target_busters();
+        if busters.len() == 1 {
+            let target = busters[0].url.clone();
+            return Box::new(
+                busters[0]
+                    .build_requests_from(cursor)
+                    .map(move |request| (target.clone(), request)),
+            );
+        }
+
+        let requests = busters
+            .into_iter()
+            .map(|buster| {
+                let target = buster.url.clone();
+                Box::new(
+                    buster
+                        .build_requests()
+                        .map(move |request| (target.clone(), request)),
+                ) as Box<dyn Iterator<Item = (String, FuzzRequest)> + Send>
             })
-            .multi_cartesian_product();
+            .collect();
 
-        for words in wordlists_iter {
-            let mut url = self.url.clone();
-            let mut http_body = self.http_body.clone();
-            let mut http_headers = self.http_headers.clone();
-            let payload = words.clone();
+        Box::new(cursor.track(interleave(requests).skip(cursor.position())))
+    }
 
-            for word in words {
-                if url.contains("FUZZ") {
-                    url = url.replacen("FUZZ", &word, 1);
+    pub fn count_requests(&self) -> usize {
+        let counts = self
+            .wordlist_paths
+            .iter()
+            .map(|wordlist_path| wordlist::count(wordlist_path, &self.wordlist_options));
+
+        match self.mode {
+            FuzzMode::Sniper => {
+                if self.wordlist_keyword(0).is_some() {
+                    counts.sum()
+                } else {
+                    counts.take(1).sum::<usize>() * self.sniper_positions()
                 }
+            }
+            FuzzMode::BatteringRam => counts.take(1).sum(),
+            FuzzMode::Pitchfork => counts.min().unwrap_or(0),
+            FuzzMode::ClusterBomb => counts.product(),
+        }
+    }
 
-                for (header, value) in http_headers.iter_mut() {
-                    if header.contains("FUZZ") {
-                        *header = header.replacen("FUZZ", &word, 1);
-                        break;
-                    } else if value.contains("FUZZ") {
-                        *value = value.replacen("FUZZ", &word, 1);
-                        break;
+    fn sniper_positions(&self) -> usize {
+        if self.wordlist_keyword(0).is_some() {
+            return self.wordlist_paths.len();
+        }
+
+        self.url.matches("FUZZ").count()
+            + self
+                .http_headers
+                .iter()
+                .map(|(header, value)| header.matches("FUZZ").count() + value.matches("FUZZ").count())
+                .sum::<usize>()
+            + self.http_body.matches("FUZZ").count()
+    }
+
+    fn build_request(&self, words: Vec<String>) -> Option<FuzzRequest> {
+        let mut keywords = Vec::new();
+        let mut values = Vec::new();
+        let mut positional = Vec::new();
+        let mut payload = BTreeMap::new();
+
+        for (index, word) in words.into_iter().enumerate() {
+            let encoded = self.encode_word(index, &word);
+            match self.wordlist_keyword(index) {
+                Some(keyword) => {
+                    keywords.push(keyword);
+                    values.push(encoded);
+                    payload.insert(keyword.to_owned(), word);
+                }
+                None => {
+                    positional.push(encoded);
+                    payload.insert(positional_keyword(positional.len()), word);
+                }
+            }
+        }
+
+        let named = keywords.len();
+        keywords.push("FUZZ");
+        let replace = |template: &str, positional: &[String], occurrence: &mut usize| {
+            substitute(template, &keywords, |index| {
+                if index < named {
+                    return Some(values[index].clone());
+                }
+
+                *occurrence += 1;
+                positional.get(*occurrence - 1).cloned()
+            })
+        };
+        let url = replace(&self.url, &positional, &mut 0);
+        let http_method = replace(&self.http_method, &[], &mut 0);
+        let mut occurrence = 0;
+        let http_headers = self
+            .http_headers
+            .iter()
+            .map(|(header, value)| {
+                let header = replace(header, &positional, &mut occurrence);
+                (header, replace(value, &positional, &mut occurrence))
+            })
+            .collect();
+        let http_body = replace(&self.http_body, &positional, &mut 0);
+
+        self.make_fuzz_request(url, http_method, http_headers, http_body, payload)
+    }
+
+    fn wordlist_keyword(&self, index: usize) -> Option<&str> {
+        self.wordlist_keywords
+            .get(index)
+            .and_then(|keyword| keyword.as_deref())
+    }
+
+    fn encode_word(&self, index: usize, word: &str) -> String {
+        match self.wordlist_encoders.get(index) {
+            Some(encoders) => encoder::encode(encoders, word),
+            None => word.to_owned(),
+        }
+    }
+
+    fn build_sniper_request(&self, position: usize, word: String) -> Option<FuzzRequest> {
+        let mut payload = BTreeMap::new();
+
+        if self.wordlist_keyword(0).is_none() {
+            let encoded = self.encode_word(0, &word);
+            let mut occurrence = 0;
+            let mut replace = |template: &str| {
+                substitute(template, &["FUZZ"], |_| {
+                    occurrence += 1;
+                    if occurrence - 1 == position {
+                        Some(encoded.clone())
+                    } else {
+                        Some(self.default_value.clone())
                     }
+                })
+            };
+            let url = replace(&self.url);
+            let http_headers = self
+                .http_headers
+                .iter()
+                .map(|(header, value)| (replace(header), replace(value)))
+                .collect();
+            let http_body = replace(&self.http_body);
+            payload.insert(positional_keyword(position + 1), word.clone());
+
+            return self.make_fuzz_request(
+                url,
+                self.http_method.clone(),
+                http_headers,
+                http_body,
+                payload,
+            );
+        }
+
+        let encoded = self.encode_word(position, &word);
+        let keywords = (0..self.wordlist_paths.len())
+            .filter_map(|index| self.wordlist_keyword(index))
+            .collect::<Vec<_>>();
+        let replace = |template: &str| {
+            substitute(template, &keywords, |index| {
+                if index == position {
+                    Some(encoded.clone())
+                } else {
+                    Some(self.default_value.clone())
                 }
+            })
+        };
+        let url = replace(&self.url);
+        let http_method = replace(&self.http_method);
+        let http_headers = self
+            .http_headers
+            .iter()
+            .map(|(header, value)| (replace(header), replace(value)))
+            .collect();
+        let http_body = replace(&self.http_body);
+        if let Some(keyword) = self.wordlist_keyword(position) {
+            payload.insert(keyword.to_owned(), word.clone());
+        }
 
-                if http_body.contains("FUZZ") {
-                    http_body = http_body.replacen("FUZZ", &word, 1);
+        self.make_fuzz_request(url, http_method, http_headers, http_body, payload)
+    }
+
+    fn build_battering_ram_request(&self, word: String) -> Option<FuzzRequest> {
+        let mut keywords = vec![("FUZZ".to_owned(), self.encode_word(0, &word))];
+        let mut payload = BTreeMap::new();
+        let mut positional_index = 0;
+        for index in 0..self.wordlist_paths.len() {
+            match self.wordlist_keyword(index) {
+                Some(keyword) => {
+                    keywords.push((keyword.to_owned(), self.encode_word(index, &word)));
+                    payload.insert(keyword.to_owned(), word.clone());
+                }
+                None => {
+                    positional_index += 1;
+                    payload.insert(positional_keyword(positional_index), word.clone());
                 }
             }
+        }
 
-            match url.parse::<hyper::Uri>() {
-                Ok(uri) => match &self.csrf_url {
-                    Some(csrf_url) => match csrf_url.parse::<hyper::Uri>() {
-                        Ok(csrf_uri) => {
-                            requests.push(FuzzRequest {
-                                http_body,
-                                uri,
-                                http_headers,
-                                payload,
-                                user_agent: self.user_agent.clone(),
-                                http_method: self.http_method.clone(),
-                                csrf_uri: Some(csrf_uri),
-                                csrf_regex: self.csrf_regex.to_owned(),
-                                csrf_headers: self.csrf_headers.clone(),
-                            });
-                        }
-                        Err(e) => {
-                            debug!("CSRF URI: {}", e);
-                        }
-                    },
-                    None => {
-                        requests.push(FuzzRequest {
-                            http_body,
-                            uri,
-                            http_headers,
-                            payload,
-                            user_agent: self.user_agent.clone(),
-                            http_method: self.http_method.clone(),
-                            csrf_uri: None,
-                            csrf_regex: None,
-                            csrf_headers: None,
-                        });
+        let names = keywords.iter().map(|(keyword, _)| keyword.as_str()).collect::<Vec<_>>();
+        let replace = |value: &str| substitute(value, &names, |index| Some(keywords[index].1.clone()));
+        let url = replace(&self.url);
+        let http_method = replace(&self.http_method);
+        let http_body = replace(&self.http_body);
+        let http_headers = self
+            .http_headers
+            .iter()
+            .map(|(header, value)| (replace(header), replace(value)))
+            .collect();
+
+        self.make_fuzz_request(url, http_method, http_headers, http_body, payload)
+    }
+
+    pub fn build_calibration_requests(&self) -> Vec<FuzzRequest> {
+        debug!("building calibration requests");
+
+        (0..CALIBRATION_REQUESTS)
+            .filter_map(|_| self.build_battering_ram_request(random_token()))
+            .collect()
+    }
+
+    fn make_fuzz_request(
+        &self,
+        url: String,
+        http_method: String,
+        http_headers: Vec<(String, String)>,
+        http_body: String,
+        payload: BTreeMap<String, String>,
+    ) -> Option<FuzzRequest> {
+        match url.parse::<hyper::Uri>() {
+            Ok(uri) => match &self.csrf_url {
+                Some(csrf_url) => match csrf_url.parse::<hyper::Uri>() {
+                    Ok(csrf_uri) => Some(FuzzRequest {
+                        http_body,
+                        uri,
+                        http_headers,
+                        payload,
+                        user_agent: self.user_agent.clone(),
+                        http_method,
+                        csrf_uri: Some(csrf_uri),
+                        csrf_regex: self.csrf_regex.to_owned(),
+                        csrf_headers: self.csrf_headers.clone(),
+                    }),
+                    Err(e) => {
+                        debug!("CSRF URI: {}", e);
+                        None
                     }
                 },
-                Err(e) => {
-                    debug!("URI: {}", e);
-                }
+                None => Some(FuzzRequest {
+                    http_body,
+                    uri,
+                    http_headers,
+                    payload,
+                    user_agent: self.user_agent.clone(),
+                    http_method,
+                    csrf_uri: None,
+                    csrf_regex: None,
+                    csrf_headers: None,
+                }),
+            },
+            Err(e) => {
+                debug!("URI: {}", e);
+                None
             }
         }
-
-        requests
     }
 
     pub fn replace_csrf(request: FuzzRequest, csrf: String) -> FuzzRequest {
@@ -413,3 +837,39 @@ impl FuzzBuster {
         p
     }
 }
+
+fn positional_keyword(index: usize) -> String {
+    if index == 1 {
+        "FUZZ".to_owned()
+    } else {
+        format!("FUZZ{}", index)
+    }
+}
+
+fn substitute(template: &str, keywords: &[&str], mut value: impl FnMut(usize) -> Option<String>) -> String {
+    let mut order = (0..keywords.len())
+        .filter(|index| !keywords[*index].is_empty())
+        .collect::<Vec<_>>();
+    order.sort_by_key(|index| std::cmp::Reverse(keywords[*index].len()));
+
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(c) = rest.chars().next() {
+        match order.iter().find(|index| rest.starts_with(keywords[**index])) {
+            Some(index) => {
+                let keyword = keywords[*index];
+                match value(*index) {
+                    Some(replacement) => result.push_str(&replacement),
+                    None => result.push_str(keyword),
+                }
+                rest = &rest[keyword.len()..];
+            }
+            None => {
+                result.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+
+    result
+}
@@ -1,23 +1,60 @@
 use serde::{Deserialize, Serialize};
-use std::{fs::File, io::Write, path::Path, str};
+use std::{collections::BTreeMap, fs::File, io::Write, path::Path, str};
+
+use crate::calibration::{Calibration, ResponseFingerprint};
+use crate::matcher::{MatchTarget, Matcher};
+use super::FuzzRequest;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SingleFuzzScanResult {
     pub url: String,
+    pub target: String,
     pub method: String,
     pub status: String,
     pub error: Option<String>,
     pub extra: Option<String>,
-    pub payload: Vec<String>,
+    pub payload: BTreeMap<String, String>,
     pub body: String,
+    pub content_length: usize,
+    pub words: usize,
+    pub lines: usize,
+    pub response_time: u64,
+    #[serde(skip)]
+    pub headers: String,
+    #[serde(skip)]
+    pub request: Option<FuzzRequest>,
+    #[serde(skip)]
+    pub original_request: Option<FuzzRequest>,
+}
+
+impl SingleFuzzScanResult {
+    pub fn fingerprint(&self) -> ResponseFingerprint {
+        ResponseFingerprint::new(
+            &self.target,
+            &self.status,
+            self.extra.as_deref(),
+            &self.body,
+            &self.payload.values().cloned().collect::<Vec<String>>(),
+        )
+    }
+
+    pub fn match_target(&self) -> MatchTarget<'_> {
+        MatchTarget {
+            status: &self.status,
+            content_length: self.content_length,
+            words: self.words,
+            lines: self.lines,
+            response_time: self.response_time,
+            headers: &self.headers,
+            body: &self.body,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FuzzScanProcessorConfig {
-    pub include: Vec<String>,
-    pub ignore: Vec<String>,
-    pub include_body: Vec<String>,
-    pub ignore_body: Vec<String>,
+    pub matcher: Matcher,
+    pub calibration: Option<Calibration>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -37,41 +74,13 @@ impl FuzzScanProcessor {
     pub fn maybe_add_result(&mut self, res: SingleFuzzScanResult) -> bool {
         trace!("{:?}", res);
 
-        if self.config.ignore_body.len() != 0 {
-            for ignore in &self.config.ignore_body {
-                if res.body.contains(ignore) {
-                    return false;
-                }
-            }
-        }
-
-        if self.config.include_body.len() != 0 {
-            for include in &self.config.include_body {
-                if res.body.contains(include) {
-                    self.results.push(res);
-                    return true;
-                }
-            }
-        }
-
-        if self.config.ignore.len() != 0 {
-            for code in &self.config.ignore {
-                if res.status.starts_with(code) {
-                    return false;
-                }
-            }
-        }
-
-        if self.config.include.len() != 0 {
-            for code in &self.config.include {
-                if res.status.starts_with(code) {
-                    self.results.push(res);
-                    return true;
-                }
+        if let Some(calibration) = &self.config.calibration {
+            if calibration.matches(&res.fingerprint()) {
+                return false;
             }
         }
 
-        if self.config.include.len() == 0 && self.config.include_body.len() == 0 {
+        if self.config.matcher.is_match(&res.match_target()) {
             self.results.push(res);
             return true;
         }
@@ -13,17 +13,31 @@ test_suite! {
                 user_agent: "ua".to_owned(),
                 http_headers: vec![("Header".to_owned(), "Value".to_owned())],
                 wordlist_paths: vec!["./examples/wordlist_short".to_owned()],
+                wordlist_keywords: vec![None],
+                wordlist_encoders: vec![],
+                wordlist_options: crate::wordlist::WordlistOptions::default(),
                 url: "http://localhost/FUZZ".to_owned(),
-                include_status_codes: vec![],
-                ignore_status_codes: vec!["404".to_owned()],
-                include_body: vec![],
-                ignore_body: vec![],
+                targets: vec![],
+                host_threads: 0,
+                matcher: crate::matcher::Matcher::default(),
                 no_progress_bar: true,
                 exit_on_connection_errors: false,
                 output: "".to_owned(),
+                output_format: crate::output::OutputFormat::Json,
                 csrf_url: None,
                 csrf_regex: None,
                 csrf_headers: None,
+                auto_calibrate: false,
+                mode: crate::fuzzbuster::FuzzMode::ClusterBomb,
+                default_value: "".to_owned(),
+                proxy: "".to_owned(),
+                replay_proxy: "".to_owned(),
+                requeue: false,
+                rate: 0,
+                delay: None,
+                timeout: 10000,
+                retries: 2,
+                resume: crate::resume::ResumeState::default(),
             }
         }
     }
@@ -38,17 +52,31 @@ test_suite! {
                 user_agent: "ua".to_owned(),
                 http_headers: vec![("Header".to_owned(), "Value".to_owned())],
                 wordlist_paths: vec!["./examples/wordlist_short".to_owned(), "./examples/wordlist_short".to_owned()],
+                wordlist_keywords: vec![None, None],
+                wordlist_encoders: vec![],
+                wordlist_options: crate::wordlist::WordlistOptions::default(),
                 url: "http://localhost/FUZZ/FUZZ".to_owned(),
-                include_status_codes: vec![],
-                ignore_status_codes: vec!["404".to_owned()],
-                include_body: vec![],
-                ignore_body: vec![],
+                targets: vec![],
+                host_threads: 0,
+                matcher: crate::matcher::Matcher::default(),
                 no_progress_bar: true,
                 exit_on_connection_errors: false,
                 output: "".to_owned(),
+                output_format: crate::output::OutputFormat::Json,
                 csrf_url: None,
                 csrf_regex: None,
                 csrf_headers: None,
+                auto_calibrate: false,
+                mode: crate::fuzzbuster::FuzzMode::ClusterBomb,
+                default_value: "".to_owned(),
+                proxy: "".to_owned(),
+                replay_proxy: "".to_owned(),
+                requeue: false,
+                rate: 0,
+                delay: None,
+                timeout: 10000,
+                retries: 2,
+                resume: crate::resume::ResumeState::default(),
             }
         }
     }
@@ -63,17 +91,31 @@ test_suite! {
                 user_agent: "ua".to_owned(),
                 http_headers: vec![("Header".to_owned(), "FUZZ".to_owned())],
                 wordlist_paths: vec!["./examples/wordlist_short".to_owned()],
+                wordlist_keywords: vec![None],
+                wordlist_encoders: vec![],
+                wordlist_options: crate::wordlist::WordlistOptions::default(),
                 url: "http://localhost/".to_owned(),
-                include_status_codes: vec![],
-                ignore_status_codes: vec!["404".to_owned()],
-                include_body: vec![],
-                ignore_body: vec![],
+                targets: vec![],
+                host_threads: 0,
+                matcher: crate::matcher::Matcher::default(),
                 no_progress_bar: true,
                 exit_on_connection_errors: false,
                 output: "".to_owned(),
+                output_format: crate::output::OutputFormat::Json,
                 csrf_url: None,
                 csrf_regex: None,
                 csrf_headers: None,
+                auto_calibrate: false,
+                mode: crate::fuzzbuster::FuzzMode::ClusterBomb,
+                default_value: "".to_owned(),
+                proxy: "".to_owned(),
+                replay_proxy: "".to_owned(),
+                requeue: false,
+                rate: 0,
+                delay: None,
+                timeout: 10000,
+                retries: 2,
+                resume: crate::resume::ResumeState::default(),
             }
         }
     }
@@ -88,17 +130,31 @@ test_suite! {
                 user_agent: "ua".to_owned(),
                 http_headers: vec![("FUZZ".to_owned(), "FUZZ".to_owned())],
                 wordlist_paths: vec!["./examples/wordlist_short".to_owned(), "./examples/wordlist_short".to_owned()],
+                wordlist_keywords: vec![None, None],
+                wordlist_encoders: vec![],
+                wordlist_options: crate::wordlist::WordlistOptions::default(),
                 url: "http://localhost/".to_owned(),
-                include_status_codes: vec![],
-                ignore_status_codes: vec!["404".to_owned()],
-                include_body: vec![],
-                ignore_body: vec![],
+                targets: vec![],
+                host_threads: 0,
+                matcher: crate::matcher::Matcher::default(),
                 no_progress_bar: true,
                 exit_on_connection_errors: false,
                 output: "".to_owned(),
+                output_format: crate::output::OutputFormat::Json,
                 csrf_url: None,
                 csrf_regex: None,
                 csrf_headers: None,
+                auto_calibrate: false,
+                mode: crate::fuzzbuster::FuzzMode::ClusterBomb,
+                default_value: "".to_owned(),
+                proxy: "".to_owned(),
+                replay_proxy: "".to_owned(),
+                requeue: false,
+                rate: 0,
+                delay: None,
+                timeout: 10000,
+                retries: 2,
+                resume: crate::resume::ResumeState::default(),
             }
         }
     }
@@ -113,17 +169,31 @@ test_suite! {
                 user_agent: "ua".to_owned(),
                 http_headers: vec![("Header".to_owned(), "Value".to_owned())],
                 wordlist_paths: vec!["./examples/wordlist_short".to_owned()],
+                wordlist_keywords: vec![None],
+                wordlist_encoders: vec![],
+                wordlist_options: crate::wordlist::WordlistOptions::default(),
                 url: "http://localhost/".to_owned(),
-                include_status_codes: vec![],
-                ignore_status_codes: vec!["404".to_owned()],
-                include_body: vec![],
-                ignore_body: vec![],
+                targets: vec![],
+                host_threads: 0,
+                matcher: crate::matcher::Matcher::default(),
                 no_progress_bar: true,
                 exit_on_connection_errors: false,
                 output: "".to_owned(),
+                output_format: crate::output::OutputFormat::Json,
                 csrf_url: None,
                 csrf_regex: None,
                 csrf_headers: None,
+                auto_calibrate: false,
+                mode: crate::fuzzbuster::FuzzMode::ClusterBomb,
+                default_value: "".to_owned(),
+                proxy: "".to_owned(),
+                replay_proxy: "".to_owned(),
+                requeue: false,
+                rate: 0,
+                delay: None,
+                timeout: 10000,
+                retries: 2,
+                resume: crate::resume::ResumeState::default(),
             }
         }
     }
@@ -138,17 +208,70 @@ test_suite! {
                 user_agent: "ua".to_owned(),
                 http_headers: vec![("Header".to_owned(), "Value".to_owned())],
                 wordlist_paths: vec!["./examples/wordlist_short".to_owned(), "./examples/wordlist_short".to_owned()],
+                wordlist_keywords: vec![None, None],
+                wordlist_encoders: vec![],
+                wordlist_options: crate::wordlist::WordlistOptions::default(),
                 url: "http://localhost/".to_owned(),
-                include_status_codes: vec![],
-                ignore_status_codes: vec!["404".to_owned()],
-                include_body: vec![],
-                ignore_body: vec![],
+                targets: vec![],
+                host_threads: 0,
+                matcher: crate::matcher::Matcher::default(),
                 no_progress_bar: true,
                 exit_on_connection_errors: false,
                 output: "".to_owned(),
+                output_format: crate::output::OutputFormat::Json,
                 csrf_url: None,
                 csrf_regex: None,
                 csrf_headers: None,
+                auto_calibrate: false,
+                mode: crate::fuzzbuster::FuzzMode::ClusterBomb,
+                default_value: "".to_owned(),
+                proxy: "".to_owned(),
+                replay_proxy: "".to_owned(),
+                requeue: false,
+                rate: 0,
+                delay: None,
+                timeout: 10000,
+                retries: 2,
+                resume: crate::resume::ResumeState::default(),
+            }
+        }
+    }
+
+    fixture fuzzbuster_keywords() -> crate::fuzzbuster::FuzzBuster {
+        setup(&mut self) {
+            crate::fuzzbuster::FuzzBuster {
+                n_threads: 1,
+                ignore_certificate: true,
+                http_method: "GET".to_owned(),
+                http_body: "user=USER&pass=PASS".to_owned(),
+                user_agent: "ua".to_owned(),
+                http_headers: vec![("X-User".to_owned(), "USER".to_owned())],
+                wordlist_paths: vec!["./examples/wordlist_short".to_owned(), "./examples/wordlist_short".to_owned()],
+                wordlist_keywords: vec![Some("USER".to_owned()), Some("PASS".to_owned())],
+                wordlist_encoders: vec![],
+                wordlist_options: crate::wordlist::WordlistOptions::default(),
+                url: "http://localhost/USER/USER".to_owned(),
+                targets: vec![],
+                host_threads: 0,
+                matcher: crate::matcher::Matcher::default(),
+                no_progress_bar: true,
+                exit_on_connection_errors: false,
+                output: "".to_owned(),
+                output_format: crate::output::OutputFormat::Json,
+                csrf_url: None,
+                csrf_regex: None,
+                csrf_headers: None,
+                auto_calibrate: false,
+                mode: crate::fuzzbuster::FuzzMode::ClusterBomb,
+                default_value: "".to_owned(),
+                proxy: "".to_owned(),
+                replay_proxy: "".to_owned(),
+                requeue: false,
+                rate: 0,
+                delay: None,
+                timeout: 10000,
+                retries: 2,
+                resume: crate::resume::ResumeState::default(),
             }
         }
     }
@@ -161,7 +284,7 @@ test_suite! {
                 http_headers: vec![],
                 http_body: "CSRFCSRF".to_owned(),
                 user_agent: "ua".to_owned(),
-                payload: vec!["1".to_owned()],
+                payload: vec![("FUZZ".to_owned(), "1".to_owned())].into_iter().collect(),
                 csrf_uri: Some("http://localhost/".parse::<hyper::Uri>().unwrap()),
                 csrf_regex: Some("(\\w+)".to_owned()),
                 csrf_headers: None,
@@ -177,7 +300,7 @@ test_suite! {
                 http_headers: vec![("X-CSRF-Token".to_owned(), "CSRFCSRF".to_owned())],
                 http_body: "body".to_owned(),
                 user_agent: "ua".to_owned(),
-                payload: vec!["1".to_owned()],
+                payload: vec![("FUZZ".to_owned(), "1".to_owned())].into_iter().collect(),
                 csrf_uri: Some("http://localhost/".parse::<hyper::Uri>().unwrap()),
                 csrf_regex: Some("(\\w+)".to_owned()),
                 csrf_headers: None,
@@ -193,7 +316,7 @@ test_suite! {
                 http_headers: vec![],
                 http_body: "body".to_owned(),
                 user_agent: "ua".to_owned(),
-                payload: vec!["1".to_owned()],
+                payload: vec![("FUZZ".to_owned(), "1".to_owned())].into_iter().collect(),
                 csrf_uri: Some("http://localhost/".parse::<hyper::Uri>().unwrap()),
                 csrf_regex: Some("(\\w+)".to_owned()),
                 csrf_headers: None,
@@ -211,65 +334,209 @@ test_suite! {
     }
 
     test build_requests_fuzz_url_single(fuzzbuster_url_single()) {
-        let requests = fuzzbuster_url_single.val.build_requests();
+        let requests = fuzzbuster_url_single.val.build_requests().collect::<Vec<_>>();
         let expected: Vec<crate::fuzzbuster::FuzzRequest> = vec![
-            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/1".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec!["1".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
-            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/2".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec!["2".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/1".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "1".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/2".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "2".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
         ];
         assert_eq!(expected, requests);
     }
 
     test build_requests_fuzz_url_multiple(fuzzbuster_url_multiple()) {
-        let requests = fuzzbuster_url_multiple.val.build_requests();
+        let requests = fuzzbuster_url_multiple.val.build_requests().collect::<Vec<_>>();
         let expected: Vec<crate::fuzzbuster::FuzzRequest> = vec![
-            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/1/1".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec!["1".to_owned(), "1".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
-            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/1/2".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec!["1".to_owned(), "2".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
-            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/2/1".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec!["2".to_owned(), "1".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
-            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/2/2".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec!["2".to_owned(), "2".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None }
+            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/1/1".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "1".to_owned()), ("FUZZ2".to_owned(), "1".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/1/2".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "1".to_owned()), ("FUZZ2".to_owned(), "2".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/2/1".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "2".to_owned()), ("FUZZ2".to_owned(), "1".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/2/2".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "2".to_owned()), ("FUZZ2".to_owned(), "2".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None }
         ];
         assert_eq!(expected, requests);
     }
 
     test build_requests_fuzz_header_single(fuzzbuster_header_single()) {
-        let requests = fuzzbuster_header_single.val.build_requests();
+        let requests = fuzzbuster_header_single.val.build_requests().collect::<Vec<_>>();
         let expected: Vec<crate::fuzzbuster::FuzzRequest> = vec![
-            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "1".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec!["1".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
-            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "2".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec!["2".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "1".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "1".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "2".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "2".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
         ];
         assert_eq!(expected, requests);
     }
 
     test build_requests_fuzz_header_multiple(fuzzbuster_header_multiple()) {
-        let requests = fuzzbuster_header_multiple.val.build_requests();
+        let requests = fuzzbuster_header_multiple.val.build_requests().collect::<Vec<_>>();
         let expected: Vec<crate::fuzzbuster::FuzzRequest> = vec![
-            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("1".to_owned(), "1".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec!["1".to_owned(), "1".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
-            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("1".to_owned(), "2".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec!["1".to_owned(), "2".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
-            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("2".to_owned(), "1".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec!["2".to_owned(), "1".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
-            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("2".to_owned(), "2".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec!["2".to_owned(), "2".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("1".to_owned(), "1".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "1".to_owned()), ("FUZZ2".to_owned(), "1".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("1".to_owned(), "2".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "1".to_owned()), ("FUZZ2".to_owned(), "2".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("2".to_owned(), "1".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "2".to_owned()), ("FUZZ2".to_owned(), "1".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("2".to_owned(), "2".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "2".to_owned()), ("FUZZ2".to_owned(), "2".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
         ];
         assert_eq!(expected, requests);
     }
 
     test build_requests_fuzz_body_single(fuzzbuster_body_single()) {
-        let requests = fuzzbuster_body_single.val.build_requests();
+        let requests = fuzzbuster_body_single.val.build_requests().collect::<Vec<_>>();
         let expected: Vec<crate::fuzzbuster::FuzzRequest> = vec![
-            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "1".to_owned(), user_agent: "ua".to_owned(), payload: vec!["1".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
-            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "2".to_owned(), user_agent: "ua".to_owned(), payload: vec!["2".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "1".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "1".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "2".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "2".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
         ];
         assert_eq!(expected, requests);
     }
 
     test build_requests_fuzz_body_multiple(fuzzbuster_body_multiple()) {
-        let requests = fuzzbuster_body_multiple.val.build_requests();
+        let requests = fuzzbuster_body_multiple.val.build_requests().collect::<Vec<_>>();
         let expected: Vec<crate::fuzzbuster::FuzzRequest> = vec![
-            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "1:1".to_owned(), user_agent: "ua".to_owned(), payload: vec!["1".to_owned(), "1".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
-            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "1:2".to_owned(), user_agent: "ua".to_owned(), payload: vec!["1".to_owned(), "2".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
-            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "2:1".to_owned(), user_agent: "ua".to_owned(), payload: vec!["2".to_owned(), "1".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
-            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "2:2".to_owned(), user_agent: "ua".to_owned(), payload: vec!["2".to_owned(), "2".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "1:1".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "1".to_owned()), ("FUZZ2".to_owned(), "1".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "1:2".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "1".to_owned()), ("FUZZ2".to_owned(), "2".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "2:1".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "2".to_owned()), ("FUZZ2".to_owned(), "1".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            crate::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "2:2".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "2".to_owned()), ("FUZZ2".to_owned(), "2".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
         ];
         assert_eq!(expected, requests);
     }
 
+    test count_requests_fuzz_url_multiple(fuzzbuster_url_multiple()) {
+        assert_eq!(4, fuzzbuster_url_multiple.val.count_requests());
+    }
+
+    test build_requests_fuzz_keywords(fuzzbuster_keywords()) {
+        let requests = fuzzbuster_keywords.val.build_requests().collect::<Vec<_>>();
+        assert_eq!(4, requests.len());
+        assert_eq!("http://localhost/1/1", requests[1].uri.to_string());
+        assert_eq!(vec![("X-User".to_owned(), "1".to_owned())], requests[1].http_headers);
+        assert_eq!("user=1&pass=2", requests[1].http_body);
+        let expected: std::collections::BTreeMap<String, String> = vec![("PASS".to_owned(), "2".to_owned()), ("USER".to_owned(), "1".to_owned())].into_iter().collect();
+        assert_eq!(expected, requests[1].payload);
+    }
+
+    test build_requests_fuzz_pitchfork(fuzzbuster_url_multiple()) {
+        let mut buster = fuzzbuster_url_multiple.val.clone();
+        buster.mode = crate::fuzzbuster::FuzzMode::Pitchfork;
+        let uris = buster.build_requests().map(|r| r.uri.to_string()).collect::<Vec<_>>();
+        assert_eq!(vec!["http://localhost/1/1", "http://localhost/2/2"], uris);
+        assert_eq!(2, buster.count_requests());
+    }
+
+    test build_requests_fuzz_battering_ram(fuzzbuster_url_multiple()) {
+        let mut buster = fuzzbuster_url_multiple.val.clone();
+        buster.mode = crate::fuzzbuster::FuzzMode::BatteringRam;
+        let uris = buster.build_requests().map(|r| r.uri.to_string()).collect::<Vec<_>>();
+        assert_eq!(vec!["http://localhost/1/1", "http://localhost/2/2"], uris);
+        assert_eq!(2, buster.count_requests());
+    }
+
+    test build_requests_fuzz_sniper(fuzzbuster_url_multiple()) {
+        let mut buster = fuzzbuster_url_multiple.val.clone();
+        buster.mode = crate::fuzzbuster::FuzzMode::Sniper;
+        buster.wordlist_paths.truncate(1);
+        buster.wordlist_keywords.truncate(1);
+        buster.default_value = "x".to_owned();
+        let uris = buster.build_requests().map(|r| r.uri.to_string()).collect::<Vec<_>>();
+        assert_eq!(vec!["http://localhost/1/x", "http://localhost/2/x", "http://localhost/x/1", "http://localhost/x/2"], uris);
+        assert_eq!(4, buster.count_requests());
+    }
+
+    test build_requests_wordlist_options(fuzzbuster_url_single()) {
+        let mut buster = fuzzbuster_url_single.val.clone();
+        buster.wordlist_options.prefix = "a".to_owned();
+        buster.wordlist_options.exclude = Some(crate::matcher::Pattern::new("^2$").unwrap());
+        let uris = buster.build_requests().map(|r| r.uri.to_string()).collect::<Vec<_>>();
+        assert_eq!(vec!["http://localhost/a1"], uris);
+        assert_eq!(1, buster.count_requests());
+    }
+
+    test build_requests_wordlist_rules(fuzzbuster_url_single()) {
+        let mut buster = fuzzbuster_url_single.val.clone();
+        buster.wordlist_options.rules = vec![
+            crate::rules::parse_rule(":").unwrap(),
+            crate::rules::parse_rule("$1").unwrap(),
+            crate::rules::parse_rule("^a d").unwrap(),
+            crate::rules::parse_rule("/2 s2x").unwrap(),
+        ];
+        let uris = buster.build_requests().map(|r| r.uri.to_string()).collect::<Vec<_>>();
+        assert_eq!(vec!["http://localhost/1", "http://localhost/11", "http://localhost/a1a1", "http://localhost/2", "http://localhost/21", "http://localhost/a2a2", "http://localhost/x"], uris);
+        assert_eq!(7, buster.count_requests());
+    }
+
+    test build_requests_encoder_chains(fuzzbuster_keywords()) {
+        let mut buster = fuzzbuster_keywords.val.clone();
+        buster.wordlist_encoders = vec![
+            vec![crate::encoder::Encoder::Base64],
+            vec![crate::encoder::Encoder::Prefix("a b".to_owned()), crate::encoder::Encoder::UrlEncode],
+        ];
+        let request = buster.build_requests().next().unwrap();
+        assert_eq!("user=MQ==&pass=a%20b1", request.http_body);
+        assert_eq!(vec![("X-User".to_owned(), "MQ==".to_owned())], request.http_headers);
+        assert_eq!(Some(&"1".to_owned()), request.payload.get("USER"));
+        assert_eq!(Some(&"1".to_owned()), request.payload.get("PASS"));
+    }
+
+    test build_requests_overlapping_keywords(fuzzbuster_keywords()) {
+        let mut buster = fuzzbuster_keywords.val.clone();
+        buster.wordlist_keywords = vec![Some("FUZZ".to_owned()), Some("FUZZ2".to_owned())];
+        buster.wordlist_encoders = vec![vec![crate::encoder::Encoder::Prefix("FUZZ2".to_owned())], vec![]];
+        buster.http_body = "a=FUZZ&b=FUZZ2".to_owned();
+        let request = buster.build_requests().nth(1).unwrap();
+        assert_eq!("a=FUZZ21&b=2", request.http_body);
+
+        buster.mode = crate::fuzzbuster::FuzzMode::BatteringRam;
+        let request = buster.build_requests().next().unwrap();
+        assert_eq!("a=FUZZ21&b=1", request.http_body);
+
+        buster.mode = crate::fuzzbuster::FuzzMode::Sniper;
+        buster.default_value = "x".to_owned();
+        let bodies = buster.build_requests().map(|r| r.http_body).collect::<Vec<_>>();
+        assert_eq!(vec!["a=FUZZ21&b=x", "a=FUZZ22&b=x", "a=x&b=1", "a=x&b=2"], bodies);
+    }
+
+    test build_requests_from_cursor(fuzzbuster_url_multiple()) {
+        let buster = fuzzbuster_url_multiple.val.clone();
+        let cursor = crate::resume::Cursor::new(1);
+        let uris = buster.build_requests_from(&cursor).map(|r| r.uri.to_string()).collect::<Vec<_>>();
+        assert_eq!(vec!["http://localhost/1/2", "http://localhost/2/1", "http://localhost/2/2"], uris);
+        assert_eq!(4, cursor.position());
+    }
+
+    test build_target_requests(fuzzbuster_url_single()) {
+        let mut buster = fuzzbuster_url_single.val.clone();
+        buster.targets = vec!["http://a/FUZZ".to_owned(), "http://b/FUZZ".to_owned()];
+        let cursor = crate::resume::Cursor::new(1);
+        let requests = buster
+            .build_target_requests(&cursor)
+            .map(|(target, r)| (target, r.uri.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("http://b/FUZZ".to_owned(), "http://b/1".to_owned()),
+                ("http://a/FUZZ".to_owned(), "http://a/2".to_owned()),
+                ("http://b/FUZZ".to_owned(), "http://b/2".to_owned()),
+            ],
+            requests
+        );
+        assert_eq!(4, cursor.position());
+    }
+
+    test build_requests_fuzz_sniper_keywords(fuzzbuster_keywords()) {
+        let mut buster = fuzzbuster_keywords.val.clone();
+        buster.mode = crate::fuzzbuster::FuzzMode::Sniper;
+        buster.default_value = "x".to_owned();
+        let bodies = buster.build_requests().map(|r| r.http_body).collect::<Vec<_>>();
+        assert_eq!(vec!["user=1&pass=x", "user=2&pass=x", "user=x&pass=1", "user=x&pass=2"], bodies);
+        assert_eq!(4, buster.count_requests());
+    }
+
+    test split_wordlist_keyword() {
+        assert_eq!(("users.txt".to_owned(), Some("USER".to_owned())), crate::fuzzbuster::utils::split_wordlist_keyword("users.txt:USER"));
+        assert_eq!(("users.txt".to_owned(), None), crate::fuzzbuster::utils::split_wordlist_keyword("users.txt"));
+        assert_eq!(("users.txt:".to_owned(), None), crate::fuzzbuster::utils::split_wordlist_keyword("users.txt:"));
+    }
+
+    test check_sniper_wordlists() {
+        let check = crate::fuzzbuster::utils::check_sniper_wordlists;
+        assert!(check(&[None]).is_ok());
+        assert!(check(&[Some("USER".to_owned()), Some("PASS".to_owned())]).is_ok());
+        assert!(check(&[Some("USER".to_owned()), None]).is_err());
+        assert!(check(&[None, None]).is_err());
+    }
+
     test split_http_headers() {
         let header = "Header: Value";
         let expected = ("Header".to_owned(), "Value".to_owned());
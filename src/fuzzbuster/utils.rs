@@ -4,3 +4,34 @@ pub fn split_http_headers(header: &str) -> (String, String) {
     let header_value = header[index + 2..].to_owned();
     (header_name, header_value)
 }
+
+pub fn split_wordlist_keyword(wordlist: &str) -> (String, Option<String>) {
+    match wordlist.rfind(':') {
+        Some(index)
+            if index + 1 < wordlist.len()
+                && wordlist[index + 1..]
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_') =>
+        {
+            (
+                wordlist[..index].to_owned(),
+                Some(wordlist[index + 1..].to_owned()),
+            )
+        }
+        _ => (wordlist.to_owned(), None),
+    }
+}
+
+pub fn check_sniper_wordlists(wordlist_keywords: &[Option<String>]) -> Result<(), String> {
+    let positional = wordlist_keywords.iter().filter(|keyword| keyword.is_none()).count();
+
+    if positional > 0 && positional < wordlist_keywords.len() {
+        return Err("Sniper mode cannot mix wordlists bound to a keyword with positional wordlists".to_owned());
+    }
+
+    if positional > 1 {
+        return Err("Sniper mode accepts a single positional wordlist, bind each wordlist to a keyword to use more".to_owned());
+    }
+
+    Ok(())
+}
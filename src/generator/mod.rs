@@ -0,0 +1,249 @@
+use chrono::{
+    format::{Item, StrftimeItems},
+    NaiveDate,
+};
+
+mod spec;
+
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Generator {
+    Range {
+        start: u64,
+        end: u64,
+        step: u64,
+        width: usize,
+    },
+    Charset {
+        chars: Vec<char>,
+        min_length: usize,
+        max_length: usize,
+    },
+    Date {
+        start: NaiveDate,
+        end: NaiveDate,
+        format: String,
+    },
+    List(Vec<String>),
+}
+
+impl Generator {
+    pub fn is_generator(spec: &str) -> bool {
+        match spec.find(':') {
+            Some(index) => ["range", "charset", "date", "list"].contains(&&spec[..index]),
+            None => false,
+        }
+    }
+
+    pub fn parse(spec: &str) -> Result<Option<Generator>, String> {
+        if !Generator::is_generator(spec) {
+            return Ok(None);
+        }
+
+        let index = spec.find(':').unwrap();
+        let args = &spec[index + 1..];
+        let generator = match &spec[..index] {
+            "range" => Generator::parse_range(args),
+            "charset" => Generator::parse_charset(args),
+            "date" => Generator::parse_date(args),
+            _ => Ok(Generator::List(
+                args.split(';')
+                    .filter(|word| !word.is_empty())
+                    .map(|word| word.to_owned())
+                    .collect(),
+            )),
+        };
+
+        generator
+            .map(Some)
+            .map_err(|e| format!("Invalid payload generator {}: {}", spec, e))
+    }
+
+    fn parse_range(args: &str) -> Result<Generator, String> {
+        let (bounds, step) = match args.find('/') {
+            Some(index) => (&args[..index], &args[index + 1..]),
+            None => (args, "1"),
+        };
+        let (start, end) = match bounds.find('-') {
+            Some(index) => (&bounds[..index], &bounds[index + 1..]),
+            None => return Err("expected START-END[/STEP]".to_owned()),
+        };
+        let width = if start.len() > 1 && start.starts_with('0') {
+            start.len()
+        } else {
+            0
+        };
+        let start = start.parse::<u64>().map_err(|e| e.to_string())?;
+        let end = end.parse::<u64>().map_err(|e| e.to_string())?;
+        let step = step.parse::<u64>().map_err(|e| e.to_string())?;
+
+        if start > end || step == 0 {
+            return Err("the range must be ascending with a positive step".to_owned());
+        }
+
+        Ok(Generator::Range {
+            start,
+            end,
+            step,
+            width,
+        })
+    }
+
+    fn parse_charset(args: &str) -> Result<Generator, String> {
+        let (charset, lengths) = match args.rfind('/') {
+            Some(index) => (&args[..index], &args[index + 1..]),
+            None => return Err("expected CHARS/MIN-MAX".to_owned()),
+        };
+        let (min_length, max_length) = match lengths.find('-') {
+            Some(index) => (&lengths[..index], &lengths[index + 1..]),
+            None => (lengths, lengths),
+        };
+        let min_length = min_length.parse::<usize>().map_err(|e| e.to_string())?;
+        let max_length = max_length.parse::<usize>().map_err(|e| e.to_string())?;
+
+        let input = charset.chars().collect::<Vec<_>>();
+        let mut chars = Vec::new();
+        let mut index = 0;
+        while index < input.len() {
+            let (first, last) = if index + 2 < input.len() && input[index + 1] == '-' {
+                index += 3;
+                (input[index - 3], input[index - 1])
+            } else {
+                index += 1;
+                (input[index - 1], input[index - 1])
+            };
+
+            for c in first..=last {
+                if !chars.contains(&c) {
+                    chars.push(c);
+                }
+            }
+        }
+
+        if chars.is_empty() || min_length > max_length {
+            return Err("the charset must not be empty and MIN must not exceed MAX".to_owned());
+        }
+
+        Ok(Generator::Charset {
+            chars,
+            min_length,
+            max_length,
+        })
+    }
+
+    fn parse_date(args: &str) -> Result<Generator, String> {
+        let (bounds, format) = match args.find('/') {
+            Some(index) => (&args[..index], &args[index + 1..]),
+            None => (args, DEFAULT_DATE_FORMAT),
+        };
+        let (start, end) = match bounds.find("..") {
+            Some(index) => (&bounds[..index], &bounds[index + 2..]),
+            None => return Err("expected START..END[/FORMAT]".to_owned()),
+        };
+        let start = NaiveDate::parse_from_str(start, DEFAULT_DATE_FORMAT).map_err(|e| e.to_string())?;
+        let end = NaiveDate::parse_from_str(end, DEFAULT_DATE_FORMAT).map_err(|e| e.to_string())?;
+
+        if start > end {
+            return Err("the date range must be ascending".to_owned());
+        }
+
+        if StrftimeItems::new(format).any(|item| item == Item::Error) {
+            return Err(format!("invalid date format {}", format));
+        }
+
+        Ok(Generator::Date {
+            start,
+            end,
+            format: format.to_owned(),
+        })
+    }
+
+    pub fn count(&self) -> usize {
+        match self {
+            Generator::Range { start, end, step, .. } => ((end - start) / step + 1) as usize,
+            Generator::Charset {
+                chars,
+                min_length,
+                max_length,
+            } => charset_count(chars.len(), *min_length, *max_length),
+            Generator::Date { start, end, .. } => ((*end - *start).num_days() + 1) as usize,
+            Generator::List(words) => words.len(),
+        }
+    }
+
+    pub fn count_within(&self, min_length: usize, max_length: Option<usize>) -> Option<usize> {
+        match self {
+            Generator::Charset {
+                chars,
+                min_length: shortest,
+                max_length: longest,
+            } => {
+                let longest = match max_length {
+                    Some(max_length) => (*longest).min(max_length),
+                    None => *longest,
+                };
+                Some(charset_count(chars.len(), (*shortest).max(min_length), longest))
+            }
+            _ if min_length == 0 && max_length.is_none() => Some(self.count()),
+            _ => None,
+        }
+    }
+
+    pub fn is_distinct(&self) -> bool {
+        matches!(self, Generator::Range { .. } | Generator::Charset { .. })
+    }
+
+    pub fn words(self) -> Box<dyn Iterator<Item = String> + Send> {
+        match self {
+            Generator::Range {
+                start,
+                end,
+                step,
+                width,
+            } => Box::new(
+                (start..=end)
+                    .step_by(step as usize)
+                    .map(move |n| format!("{:0width$}", n, width = width)),
+            ),
+            Generator::Charset {
+                chars,
+                min_length,
+                max_length,
+            } => Box::new((min_length..=max_length).flat_map(move |length| {
+                let chars = chars.clone();
+                let mut indexes: Option<Vec<usize>> = Some(vec![0; length]);
+
+                std::iter::from_fn(move || {
+                    let current = indexes.take()?;
+                    let word = current.iter().map(|index| chars[*index]).collect::<String>();
+
+                    let mut next = current;
+                    for position in (0..length).rev() {
+                        if next[position] + 1 < chars.len() {
+                            next[position] += 1;
+                            indexes = Some(next);
+                            break;
+                        }
+
+                        next[position] = 0;
+                    }
+
+                    Some(word)
+                })
+            })),
+            Generator::Date { start, end, format } => Box::new(
+                std::iter::successors(Some(start), |date| date.succ_opt())
+                    .take_while(move |date| *date <= end)
+                    .map(move |date| date.format(&format).to_string()),
+            ),
+            Generator::List(words) => Box::new(words.into_iter()),
+        }
+    }
+}
+
+fn charset_count(chars: usize, min_length: usize, max_length: usize) -> usize {
+    (min_length..=max_length).fold(0usize, |count, length| {
+        count.saturating_add(chars.saturating_pow(length as u32))
+    })
+}
@@ -0,0 +1,47 @@
+use galvanic_test::test_suite;
+
+test_suite! {
+    name generator;
+
+    use crate::generator::Generator;
+
+    fn words(spec: &str) -> Vec<String> {
+        Generator::parse(spec).unwrap().unwrap().words().collect()
+    }
+
+    test generate_payloads() {
+        assert_eq!(vec!["08", "10", "12"], words("range:08-12/2"));
+        assert_eq!(vec!["a", "b", "aa", "ab", "ba", "bb"], words("charset:a-b/1-2"));
+        assert_eq!(vec!["2802", "2902", "0103"], words("date:2020-02-28..2020-03-01/%d%m"));
+        assert_eq!(vec!["admin", "root"], words("list:admin;root"));
+    }
+
+    test count_payloads() {
+        for spec in &[
+            "range:08-12/2",
+            "range:0-10/3",
+            "charset:a-b/1-2",
+            "charset:a-c/0-3",
+            "date:2020-02-28..2020-03-01/%d%m",
+            "list:admin;root",
+        ] {
+            let generator = Generator::parse(spec).unwrap().unwrap();
+            assert_eq!(generator.clone().words().count(), generator.count(), "{}", spec);
+        }
+
+        let generator = Generator::parse("charset:a-z/1-8").unwrap().unwrap();
+        assert_eq!(217_180_147_158, generator.count());
+        assert_eq!(Some(217_180_147_158), generator.count_within(0, None));
+        assert_eq!(Some(26 * 26 + 26 * 26 * 26), generator.count_within(2, Some(3)));
+        assert_eq!(Some(0), generator.count_within(9, None));
+        assert_eq!(None, Generator::parse("range:1-100").unwrap().unwrap().count_within(2, None));
+    }
+
+    test parse_generators() {
+        assert_eq!(None, Generator::parse("wordlist.txt").unwrap());
+        assert!(Generator::parse("range:5-1").is_err());
+        assert!(Generator::parse("range:1-5/0").is_err());
+        assert!(Generator::parse("charset:ab").is_err());
+        assert!(Generator::parse("date:2020-01-01").is_err());
+    }
+}
@@ -2,7 +2,21 @@
 extern crate log;
 
 pub mod banner;
+pub mod calibration;
+pub mod crawlbuster;
 pub mod dirbuster;
 pub mod dnsbuster;
+pub mod encoder;
 pub mod fuzzbuster;
+pub mod generator;
+pub mod matcher;
+pub mod output;
+pub mod proxy;
+pub mod resolver;
+pub mod resume;
+pub mod rules;
+pub mod retry;
+pub mod targets;
+pub mod throttle;
 pub mod vhostbuster;
+pub mod wordlist;
@@ -3,23 +3,39 @@ extern crate log;
 #[macro_use]
 extern crate clap;
 
-use clap::{App, SubCommand};
+use clap::{App, Arg, SubCommand};
 
 mod args;
 mod banner;
+mod calibration;
+mod crawlbuster;
 mod dirbuster;
 mod dnsbuster;
+mod encoder;
 mod fuzzbuster;
+mod generator;
+mod matcher;
+mod output;
+mod proxy;
+mod resolver;
+mod resume;
+mod rules;
+mod retry;
+mod targets;
+mod throttle;
 mod tildebuster;
 mod vhostbuster;
+mod wordlist;
 
 use args::*;
 
+use crawlbuster::CrawlBuster;
 use dirbuster::DirBuster;
 use dnsbuster::DnsBuster;
 use tildebuster::TildeBuster;
 use vhostbuster::VhostBuster;
 use fuzzbuster::FuzzBuster;
+use resume::ResumeState;
 
 fn main() {
     if std::env::vars()
@@ -32,7 +48,8 @@ fn main() {
     }
 
     pretty_env_logger::init();
-    let matches = App::new("rustbuster")
+    let _stdin_spool = wordlist::StdinSpool;
+    let app = App::new("rustbuster")
         .version(crate_version!())
         .author("by phra & ps1dr3x")
         .about("DirBuster for rust")
@@ -42,7 +59,7 @@ fn main() {
     2. Dns mode:
         rustbuster dns -d google.com -w examples/wordlist
     3. Vhost mode:
-        rustbuster vhost -u http://localhost:3000/ -w examples/wordlist -d test.local -x \"Hello\"
+        rustbuster vhost -u http://localhost:3000/ -w examples/wordlist -d test.local
     4. Fuzz mode:
         rustbuster fuzz -u http://localhost:3000/login \\
             -X POST \\
@@ -55,24 +72,40 @@ fn main() {
             --csrf-regex '\\{\"csrf\":\"(\\w+)\"\\}'
     5. Tilde mode:
         rustbuster tilde -u http://localhost:3000/ -e aspx -X OPTIONS
+    6. Crawl mode:
+        rustbuster crawl -u http://localhost:3000/ --depth 3 -o wordlist.txt
+    7. Multiple targets:
+        rustbuster dir --targets-file targets.txt -w examples/wordlist --host-threads 5 -o results.jsonl --output-format jsonl
 ")
-        .subcommand(set_wordlist_args(set_dir_args(set_http_args(set_common_args(SubCommand::with_name("dir")))))
+        .arg(
+            Arg::with_name("resume")
+                .long("resume")
+                .help("Resumes an interrupted scan from the specified resume file")
+                .takes_value(true),
+        )
+        .subcommand(set_wordlist_args(set_calibration_args(set_dir_args(set_matcher_args(set_body_args(set_requeue_args(set_replay_args(set_targets_args(set_request_args(set_http_args(set_output_format_args(set_common_args(SubCommand::with_name("dir")))))))))))))
             .about("Directories and files enumeration mode")
             .after_help("EXAMPLE:
     rustbuster dir -u http://localhost:3000/ -w examples/wordlist -e php"))
-        .subcommand(set_wordlist_args(set_dns_args(set_common_args(SubCommand::with_name("dns"))))
+        .subcommand(set_wordlist_args(set_dns_args(set_targets_args(set_output_format_args(set_common_args(SubCommand::with_name("dns"))))))
             .about("A/AAAA entries enumeration mode")
             .after_help("EXAMPLE:
-    rustbuster dns -d google.com -w examples/wordlist"))
-        .subcommand(set_wordlist_args(set_vhost_args(set_http_args(set_common_args(SubCommand::with_name("vhost")))))
+    rustbuster dns -d google.com -w examples/wordlist -r 1.1.1.1,8.8.8.8"))
+        .subcommand(set_wordlist_args(set_vhost_args(set_matcher_args(set_body_args(set_requeue_args(set_replay_args(set_targets_args(set_request_args(set_http_args(set_output_format_args(set_common_args(SubCommand::with_name("vhost"))))))))))))
             .about("Virtual hosts enumeration mode")
             .after_help("EXAMPLE:
+    rustbuster vhost -u http://localhost:3000/ -w examples/wordlist -d test.local
+
     rustbuster vhost -u http://localhost:3000/ -w examples/wordlist -d test.local -x \"Hello\""))
-        .subcommand(set_tilde_args(set_http_args(set_common_args(SubCommand::with_name("tilde"))))
+        .subcommand(set_tilde_args(set_matcher_args(set_body_args(set_requeue_args(set_replay_args(set_targets_args(set_request_args(set_http_args(set_output_format_args(set_common_args(SubCommand::with_name("tilde")))))))))))
             .about("IIS 8.3 shortname enumeration mode")
             .after_help("EXAMPLE:
     rustbuster tilde -u http://localhost:3000/ -e aspx -X OPTIONS"))
-        .subcommand(set_wordlist_args(set_fuzz_args(set_body_args(set_http_args(set_common_args(SubCommand::with_name("fuzz"))))))
+        .subcommand(set_crawl_args(set_http_args(set_common_args(SubCommand::with_name("crawl"))))
+            .about("Target-derived wordlist generation mode")
+            .after_help("EXAMPLE:
+    rustbuster crawl -u http://localhost:3000/ --depth 3 -o wordlist.txt"))
+        .subcommand(set_wordlist_args(set_calibration_args(set_fuzz_args(set_matcher_args(set_body_args(set_requeue_args(set_replay_args(set_targets_args(set_request_args(set_http_args(set_output_format_args(set_common_args(SubCommand::with_name("fuzz")))))))))))))
             .about("Custom fuzzing enumeration mode")
             .after_help("EXAMPLE:
     rustbuster fuzz -u http://localhost:3000/login \\
@@ -83,8 +116,36 @@ fn main() {
         -w /usr/share/seclists/Passwords/Common-Credentials/10-million-password-list-top-10000.txt \\
         -s 200 \\
         --csrf-url \"http://localhost:3000/csrf\" \\
-        --csrf-regex '\\{\"csrf\":\"(\\w+)\"\\}'"))
-        .get_matches();
+        --csrf-regex '\\{\"csrf\":\"(\\w+)\"\\}'
+
+    rustbuster fuzz -u http://localhost:3000/login \\
+        -X POST \\
+        -b 'user=USER&password=PASS' \\
+        -w examples/wordlist:USER \\
+        -w /usr/share/seclists/Passwords/Common-Credentials/10-million-password-list-top-10000.txt:PASS \\
+        -s 200"));
+
+    let matches = app.clone().get_matches();
+    let resume = match matches.value_of("resume") {
+        Some(path) => match ResumeState::load(path) {
+            Ok(v) => Some(v),
+            Err(e) => {
+                error!("Unable to load the resume file {}: {}", path, e);
+                return;
+            }
+        },
+        None => None,
+    };
+    let matches = match &resume {
+        Some(state) => match state.matches(app, matches.subcommand_name()) {
+            Ok(v) => v,
+            Err(e) => {
+                error!("Invalid resume file {}: {}", state.path, e);
+                return;
+            }
+        },
+        None => matches,
+    };
 
     let mode = matches.subcommand_name().unwrap_or("dir");
     let submatches = match matches.subcommand_matches(mode) {
@@ -95,7 +156,14 @@ fn main() {
         }
     };
 
-    let common_args = extract_common_args(submatches);
+    let common_args = match extract_common_args(submatches) {
+        Err(_) => return,
+        Ok(v) => v,
+    };
+    let resume = resume.unwrap_or_else(|| {
+        ResumeState::new(&common_args.output, std::env::args().collect())
+    });
+    resume::handle_interrupts();
 
     match submatches.occurrences_of("verbose") {
         0 => trace!("No verbose info"),
@@ -118,30 +186,71 @@ fn main() {
                 Err(_) => return,
                 Ok(v) => v,
             };
+            warn_unsupported_encoders(&wordlist_args);
+            if !wordlist_args.wordlist_options.rules.is_empty() {
+                println!("{}\n", banner::rules(&wordlist_args.wordlist_paths, &wordlist_args.wordlist_options));
+            }
 
-            let http_args = extract_http_args(submatches);
-            if !url_is_valid(&http_args.url) {
+            let http_args = match extract_http_args(submatches) {
+                Err(_) => return,
+                Ok(v) => v,
+            };
+            let request_args = extract_request_args(submatches);
+            let targets_args = match extract_targets_args(submatches, &http_args.url) {
+                Err(_) => return,
+                Ok(v) => v,
+            };
+            if !targets_args.targets.iter().all(|url| url_is_valid(url))
+                || !proxy_is_valid(&http_args.proxy)
+            {
                 return;
             }
 
-            let dir_args = extract_dir_args(submatches);
+            let dir_args = match extract_dir_args(submatches) {
+                Err(_) => return,
+                Ok(v) => v,
+            };
+            let calibration_args = extract_calibration_args(submatches);
+            let replay_args = extract_replay_args(submatches);
+            let requeue_args = extract_requeue_args(submatches);
+            if !proxy_is_valid(&replay_args.replay_proxy) {
+                return;
+            }
+
+            let matcher_args = match extract_matcher_args(submatches, "", "404") {
+                Err(_) => return,
+                Ok(v) => v,
+            };
 
             let dirbuster = DirBuster {
                     n_threads: common_args.n_threads,
                     ignore_certificate: http_args.ignore_certificate,
-                    http_method: http_args.http_method.to_owned(),
-                    http_body: http_args.http_body.to_owned(),
+                    http_method: request_args.http_method.to_owned(),
+                    http_body: request_args.http_body.to_owned(),
                     user_agent: http_args.user_agent.to_owned(),
                     http_headers: http_args.http_headers.clone(),
                     url: http_args.url.to_owned(),
-                    wordlist_path: wordlist_args.wordlist_paths[0].to_owned(),
+                    targets: targets_args.targets,
+                    host_threads: targets_args.host_threads,
+                    wordlist_paths: wordlist_args.wordlist_paths,
+                    wordlist_options: wordlist_args.wordlist_options,
                     extensions: dir_args.extensions.clone(),
                     append_slash: dir_args.append_slash,
-                    include_status_codes: http_args.include_status_codes.clone(),
-                    ignore_status_codes: http_args.ignore_status_codes.clone(),
+                    recursion_depth: dir_args.recursion_depth,
+                    auto_calibrate: calibration_args.auto_calibrate,
+                    matcher: matcher_args.matcher,
                     no_progress_bar: common_args.no_progress_bar,
                     exit_on_connection_errors: common_args.exit_on_connection_errors,
                     output: common_args.output.clone(),
+                    output_format: common_args.output_format,
+                    proxy: http_args.proxy,
+                    replay_proxy: replay_args.replay_proxy,
+                    requeue: requeue_args.requeue,
+                    rate: common_args.rate,
+                    delay: common_args.delay.clone(),
+                    timeout: http_args.timeout,
+                    retries: http_args.retries,
+                    resume,
             };
 
             dirbuster.run();
@@ -151,50 +260,124 @@ fn main() {
                 Err(_) => return,
                 Ok(v) => v,
             };
+            warn_unsupported_encoders(&wordlist_args);
+            if !wordlist_args.wordlist_options.rules.is_empty() {
+                println!("{}\n", banner::rules(&wordlist_args.wordlist_paths, &wordlist_args.wordlist_options));
+            }
             
             let dns_args = extract_dns_args(submatches);
+            let targets_args = match extract_targets_args(submatches, &dns_args.domain) {
+                Err(_) => return,
+                Ok(v) => v,
+            };
+            let resolver_args = match extract_resolver_args(submatches) {
+                Err(_) => return,
+                Ok(v) => v,
+            };
+            println!("{}\n", banner::resolvers(&resolver_args.resolvers));
+
             let dnsbuster = DnsBuster {
                 n_threads: common_args.n_threads,
                 no_progress_bar: common_args.no_progress_bar,
+                exit_on_connection_errors: common_args.exit_on_connection_errors,
                 output: common_args.output,
+                output_format: common_args.output_format,
                 domain: dns_args.domain,
-                wordlist_path: wordlist_args.wordlist_paths[0].to_owned(),
+                targets: targets_args.targets,
+                host_threads: targets_args.host_threads,
+                wordlist_paths: wordlist_args.wordlist_paths,
+                wordlist_options: wordlist_args.wordlist_options,
+                resolvers: resolver_args.resolvers,
+                record_types: resolver_args.record_types,
+                zone_transfer: resolver_args.zone_transfer,
+                takeover_fingerprints: resolver_args.takeover_fingerprints,
+                rate: common_args.rate,
+                delay: common_args.delay.clone(),
+                timeout: resolver_args.timeout,
+                retries: resolver_args.retries,
+                resume,
             };
 
             dnsbuster.run();
         }
         "vhost" => {
             let dns_args = extract_dns_args(submatches);
-            let body_args = extract_body_args(submatches);
-            let http_args = extract_http_args(submatches);
-            if !url_is_valid(&http_args.url) {
+            let http_args = match extract_http_args(submatches) {
+                Err(_) => return,
+                Ok(v) => v,
+            };
+            let request_args = extract_request_args(submatches);
+            let targets_args = match extract_targets_args(submatches, &http_args.url) {
+                Err(_) => return,
+                Ok(v) => v,
+            };
+            if !targets_args.targets.iter().all(|url| url_is_valid(url))
+                || !proxy_is_valid(&http_args.proxy)
+            {
                 return;
             }
 
+            let replay_args = extract_replay_args(submatches);
+            let requeue_args = extract_requeue_args(submatches);
+            if !proxy_is_valid(&replay_args.replay_proxy) {
+                return;
+            }
+
+            let matcher_args = match extract_matcher_args(submatches, "", "404") {
+                Err(_) => return,
+                Ok(v) => v,
+            };
+
             let wordlist_args = match extract_wordlist_args(submatches) {
                 Err(_) => return,
                 Ok(v) => v,
             };
+            warn_unsupported_encoders(&wordlist_args);
+            if !wordlist_args.wordlist_options.rules.is_empty() {
+                println!("{}\n", banner::rules(&wordlist_args.wordlist_paths, &wordlist_args.wordlist_options));
+            }
             let vhostbuster = VhostBuster {
                 n_threads: common_args.n_threads,
                 ignore_certificate: http_args.ignore_certificate,
-                http_method: http_args.http_method.to_owned(),
+                http_method: request_args.http_method.to_owned(),
                 user_agent: http_args.user_agent.to_owned(),
-                ignore_strings: body_args.ignore_strings,
                 original_url: http_args.url.to_owned(),
-                wordlist_path: wordlist_args.wordlist_paths[0].to_owned(),
+                targets: targets_args.targets,
+                host_threads: targets_args.host_threads,
+                wordlist_paths: wordlist_args.wordlist_paths,
+                wordlist_options: wordlist_args.wordlist_options,
                 domain: dns_args.domain.to_owned(),
                 no_progress_bar: common_args.no_progress_bar,
                 exit_on_connection_errors: common_args.exit_on_connection_errors,
                 output: common_args.output.to_owned(),
+                output_format: common_args.output_format,
+                matcher: matcher_args.matcher,
+                proxy: http_args.proxy,
+                replay_proxy: replay_args.replay_proxy,
+                requeue: requeue_args.requeue,
+                rate: common_args.rate,
+                delay: common_args.delay.clone(),
+                timeout: http_args.timeout,
+                retries: http_args.retries,
+                resume,
             };
 
             vhostbuster.run();
 
         }
         "fuzz" => {
-            let http_args = extract_http_args(submatches);
-            if !url_is_valid(&http_args.url) {
+            let http_args = match extract_http_args(submatches) {
+                Err(_) => return,
+                Ok(v) => v,
+            };
+            let request_args = extract_request_args(submatches);
+            let targets_args = match extract_targets_args(submatches, &http_args.url) {
+                Err(_) => return,
+                Ok(v) => v,
+            };
+            if !targets_args.targets.iter().all(|url| url_is_valid(url))
+                || !proxy_is_valid(&http_args.proxy)
+            {
                 return;
             }
 
@@ -202,29 +385,58 @@ fn main() {
                 Err(_) => return,
                 Ok(v) => v,
             };
+            if !wordlist_args.wordlist_options.rules.is_empty() {
+                println!("{}\n", banner::rules(&wordlist_args.wordlist_paths, &wordlist_args.wordlist_options));
+            }
 
-            let body_args = extract_body_args(submatches);
-            let fuzz_args = extract_fuzz_args(submatches);
+            let matcher_args = match extract_matcher_args(submatches, "", "404") {
+                Err(_) => return,
+                Ok(v) => v,
+            };
+            let fuzz_args = match extract_fuzz_args(submatches, &wordlist_args) {
+                Err(_) => return,
+                Ok(v) => v,
+            };
+            let calibration_args = extract_calibration_args(submatches);
+            let replay_args = extract_replay_args(submatches);
+            let requeue_args = extract_requeue_args(submatches);
+            if !proxy_is_valid(&replay_args.replay_proxy) {
+                return;
+            }
 
             let fuzzbuster = FuzzBuster {
                 n_threads: common_args.n_threads,
                 ignore_certificate: http_args.ignore_certificate,
-                http_method: http_args.http_method.to_owned(),
-                http_body: http_args.http_body.to_owned(),
+                http_method: request_args.http_method.to_owned(),
+                http_body: request_args.http_body.to_owned(),
                 user_agent: http_args.user_agent.to_owned(),
                 http_headers: http_args.http_headers,
                 wordlist_paths: wordlist_args.wordlist_paths,
+                wordlist_keywords: wordlist_args.wordlist_keywords,
+                wordlist_encoders: wordlist_args.wordlist_encoders,
+                wordlist_options: wordlist_args.wordlist_options,
                 url: http_args.url.to_owned(),
-                ignore_status_codes: http_args.ignore_status_codes,
-                include_status_codes: http_args.include_status_codes,
+                targets: targets_args.targets,
+                host_threads: targets_args.host_threads,
                 no_progress_bar: common_args.no_progress_bar,
                 exit_on_connection_errors: common_args.exit_on_connection_errors,
                 output: common_args.output.to_owned(),
-                include_body: body_args.include_strings,
-                ignore_body: body_args.ignore_strings,
+                output_format: common_args.output_format,
+                matcher: matcher_args.matcher,
                 csrf_url: fuzz_args.csrf_url,
                 csrf_regex: fuzz_args.csrf_regex,
                 csrf_headers: fuzz_args.csrf_headers,
+                auto_calibrate: calibration_args.auto_calibrate,
+                mode: fuzz_args.mode,
+                default_value: fuzz_args.default_value,
+                proxy: http_args.proxy,
+                replay_proxy: replay_args.replay_proxy,
+                requeue: requeue_args.requeue,
+                rate: common_args.rate,
+                delay: common_args.delay.clone(),
+                timeout: http_args.timeout,
+                retries: http_args.retries,
+                resume,
             };
 
             debug!("FuzzBuster {:#?}", fuzzbuster);
@@ -232,30 +444,98 @@ fn main() {
             fuzzbuster.run();
         }
         "tilde" => {
-            let http_args = extract_http_args(submatches);
-            if !url_is_valid(&http_args.url) {
+            let http_args = match extract_http_args(submatches) {
+                Err(_) => return,
+                Ok(v) => v,
+            };
+            let request_args = extract_request_args(submatches);
+            let targets_args = match extract_targets_args(submatches, &http_args.url) {
+                Err(_) => return,
+                Ok(v) => v,
+            };
+            if !targets_args.targets.iter().all(|url| url_is_valid(url))
+                || !proxy_is_valid(&http_args.proxy)
+            {
                 return;
             }
 
+            let replay_args = extract_replay_args(submatches);
+            if !proxy_is_valid(&replay_args.replay_proxy) {
+                return;
+            }
+
+            let requeue_args = extract_requeue_args(submatches);
             let tilde_args = extract_tilde_args(submatches);
+            let matcher_args = match extract_matcher_args(submatches, "404", "") {
+                Err(_) => return,
+                Ok(v) => v,
+            };
             let tildebuster = TildeBuster {
                 n_threads: common_args.n_threads,
                 ignore_certificate: http_args.ignore_certificate,
-                http_method: http_args.http_method.to_owned(),
-                http_body: http_args.http_body.to_owned(),
+                http_method: request_args.http_method.to_owned(),
+                http_body: request_args.http_body.to_owned(),
                 user_agent: http_args.user_agent.to_owned(),
                 http_headers: http_args.http_headers,
                 url: http_args.url.to_owned(),
+                targets: targets_args.targets,
+                host_threads: targets_args.host_threads,
                 no_progress_bar: common_args.no_progress_bar,
                 exit_on_connection_errors: common_args.exit_on_connection_errors,
                 output: common_args.output.to_owned(),
+                output_format: common_args.output_format,
                 extension: tilde_args.extension,
+                matcher: matcher_args.matcher,
+                proxy: http_args.proxy,
+                replay_proxy: replay_args.replay_proxy,
+                requeue: requeue_args.requeue,
+                rate: common_args.rate,
+                delay: common_args.delay.clone(),
+                timeout: http_args.timeout,
+                retries: http_args.retries,
+                resume,
             };
 
             debug!("TildeBuster {:#?}", tildebuster);
 
             tildebuster.run();
         }
+        "crawl" => {
+            let http_args = match extract_http_args(submatches) {
+                Err(_) => return,
+                Ok(v) => v,
+            };
+            if !url_is_valid(&http_args.url) || !proxy_is_valid(&http_args.proxy) {
+                return;
+            }
+
+            let crawl_args = match extract_crawl_args(submatches) {
+                Err(_) => return,
+                Ok(v) => v,
+            };
+            let crawlbuster = CrawlBuster {
+                n_threads: common_args.n_threads,
+                ignore_certificate: http_args.ignore_certificate,
+                user_agent: http_args.user_agent.to_owned(),
+                http_headers: http_args.http_headers,
+                url: http_args.url.to_owned(),
+                depth: crawl_args.depth,
+                min_length: crawl_args.min_length,
+                min_frequency: crawl_args.min_frequency,
+                no_progress_bar: common_args.no_progress_bar,
+                exit_on_connection_errors: common_args.exit_on_connection_errors,
+                output: common_args.output.to_owned(),
+                proxy: http_args.proxy,
+                rate: common_args.rate,
+                delay: common_args.delay.clone(),
+                timeout: http_args.timeout,
+                retries: http_args.retries,
+            };
+
+            debug!("CrawlBuster {:#?}", crawlbuster);
+
+            crawlbuster.run();
+        }
         _ => (),
     }
 }
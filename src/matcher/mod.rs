@@ -0,0 +1,175 @@
+use regex::Regex;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+mod spec;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NumericRange {
+    pub min: u64,
+    pub max: u64,
+}
+
+impl NumericRange {
+    pub fn parse(value: &str) -> Option<NumericRange> {
+        let value = value.trim();
+        let (min, max) = match value.find('-') {
+            Some(index) => (&value[..index], &value[index + 1..]),
+            None => (value, value),
+        };
+
+        let min = if min.is_empty() {
+            0
+        } else {
+            min.parse::<u64>().ok()?
+        };
+        let max = if max.is_empty() {
+            u64::MAX
+        } else {
+            max.parse::<u64>().ok()?
+        };
+
+        if min > max {
+            return None;
+        }
+
+        Some(NumericRange { min, max })
+    }
+
+    pub fn parse_status(value: &str) -> Option<NumericRange> {
+        let value = value.trim();
+        if value.contains('-') || value.len() >= 3 {
+            return NumericRange::parse(value);
+        }
+
+        let prefix = value.parse::<u64>().ok()?;
+        let scale = 10u64.pow(3 - value.len() as u32);
+
+        Some(NumericRange {
+            min: prefix * scale,
+            max: (prefix + 1) * scale - 1,
+        })
+    }
+
+    pub fn contains(&self, value: u64) -> bool {
+        value >= self.min && value <= self.max
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Pattern(Regex);
+
+impl Pattern {
+    pub fn new(pattern: &str) -> Result<Pattern, regex::Error> {
+        Regex::new(pattern).map(Pattern)
+    }
+
+    pub fn is_match(&self, value: &str) -> bool {
+        self.0.is_match(value)
+    }
+}
+
+impl Serialize for Pattern {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Pattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Pattern, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        Pattern::new(&pattern).map_err(de::Error::custom)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum Rule {
+    Status(Vec<NumericRange>),
+    Size(Vec<NumericRange>),
+    Words(Vec<NumericRange>),
+    Lines(Vec<NumericRange>),
+    ResponseTime(Vec<NumericRange>),
+    Body(Vec<Pattern>),
+    Headers(Vec<Pattern>),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Combinator {
+    And,
+    Or,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MatchTarget<'a> {
+    pub status: &'a str,
+    pub content_length: usize,
+    pub words: usize,
+    pub lines: usize,
+    pub response_time: u64,
+    pub headers: &'a str,
+    pub body: &'a str,
+}
+
+impl Rule {
+    pub fn matches(&self, target: &MatchTarget) -> bool {
+        let in_ranges = |ranges: &Vec<NumericRange>, value: u64| {
+            ranges.iter().any(|range| range.contains(value))
+        };
+
+        match self {
+            Rule::Status(ranges) => match target.status.get(..3).map(|code| code.parse::<u64>()) {
+                Some(Ok(code)) => in_ranges(ranges, code),
+                _ => false,
+            },
+            Rule::Size(ranges) => in_ranges(ranges, target.content_length as u64),
+            Rule::Words(ranges) => in_ranges(ranges, target.words as u64),
+            Rule::Lines(ranges) => in_ranges(ranges, target.lines as u64),
+            Rule::ResponseTime(ranges) => in_ranges(ranges, target.response_time),
+            Rule::Body(patterns) => patterns.iter().any(|pattern| pattern.is_match(target.body)),
+            Rule::Headers(patterns) => patterns
+                .iter()
+                .any(|pattern| pattern.is_match(target.headers)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Matcher {
+    pub include: Vec<Rule>,
+    pub include_mode: Combinator,
+    pub ignore: Vec<Rule>,
+    pub ignore_mode: Combinator,
+}
+
+impl Default for Matcher {
+    fn default() -> Self {
+        Matcher {
+            include: Vec::new(),
+            include_mode: Combinator::And,
+            ignore: Vec::new(),
+            ignore_mode: Combinator::Or,
+        }
+    }
+}
+
+impl Matcher {
+    pub fn is_match(&self, target: &MatchTarget) -> bool {
+        let included = self.include.is_empty() || combine(&self.include, self.include_mode, target);
+        let ignored = !self.ignore.is_empty() && combine(&self.ignore, self.ignore_mode, target);
+
+        included && !ignored
+    }
+}
+
+fn combine(rules: &[Rule], mode: Combinator, target: &MatchTarget) -> bool {
+    match mode {
+        Combinator::And => rules.iter().all(|rule| rule.matches(target)),
+        Combinator::Or => rules.iter().any(|rule| rule.matches(target)),
+    }
+}
+
+pub fn format_headers(headers: &hyper::HeaderMap) -> String {
+    headers
+        .iter()
+        .map(|(name, value)| format!("{}: {}\n", name, value.to_str().unwrap_or("")))
+        .collect()
+}
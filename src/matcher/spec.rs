@@ -0,0 +1,81 @@
+use galvanic_test::test_suite;
+
+test_suite! {
+    name matcher;
+
+    use crate::matcher::{Combinator, MatchTarget, Matcher, NumericRange, Pattern, Rule};
+
+    fixture target() -> MatchTarget<'static> {
+        setup(&mut self) {
+            MatchTarget {
+                status: "200 OK",
+                content_length: 120,
+                words: 12,
+                lines: 3,
+                response_time: 50,
+                headers: "content-type: text/html\n",
+                body: "Welcome admin",
+            }
+        }
+    }
+
+    test numeric_range_parse() {
+        assert_eq!(Some(NumericRange { min: 120, max: 120 }), NumericRange::parse("120"));
+        assert_eq!(Some(NumericRange { min: 100, max: 200 }), NumericRange::parse("100-200"));
+        assert_eq!(Some(NumericRange { min: 100, max: u64::MAX }), NumericRange::parse("100-"));
+        assert_eq!(Some(NumericRange { min: 0, max: 200 }), NumericRange::parse("-200"));
+        assert_eq!(None, NumericRange::parse("200-100"));
+        assert_eq!(None, NumericRange::parse("abc"));
+    }
+
+    test numeric_range_parse_status() {
+        assert_eq!(Some(NumericRange { min: 200, max: 299 }), NumericRange::parse_status("2"));
+        assert_eq!(Some(NumericRange { min: 400, max: 409 }), NumericRange::parse_status("40"));
+        assert_eq!(Some(NumericRange { min: 404, max: 404 }), NumericRange::parse_status("404"));
+        assert_eq!(Some(NumericRange { min: 300, max: 399 }), NumericRange::parse_status("300-399"));
+        assert_eq!(None, NumericRange::parse_status(""));
+    }
+
+    test matcher_defaults_to_match(target) {
+        assert!(Matcher::default().is_match(&target.val));
+    }
+
+    test matcher_include_and(target) {
+        let mut matcher = Matcher {
+            include: vec![
+                Rule::Status(vec![NumericRange { min: 200, max: 299 }]),
+                Rule::Body(vec![Pattern::new("admin").unwrap()]),
+            ],
+            ..Matcher::default()
+        };
+        assert!(matcher.is_match(&target.val));
+
+        matcher.include.push(Rule::Words(vec![NumericRange { min: 1, max: 5 }]));
+        assert!(!matcher.is_match(&target.val));
+
+        matcher.include_mode = Combinator::Or;
+        assert!(matcher.is_match(&target.val));
+    }
+
+    test matcher_ignore_or(target) {
+        let mut matcher = Matcher {
+            ignore: vec![
+                Rule::Size(vec![NumericRange { min: 0, max: 10 }]),
+                Rule::Headers(vec![Pattern::new("(?im)^content-type:.*html").unwrap()]),
+            ],
+            ..Matcher::default()
+        };
+        assert!(!matcher.is_match(&target.val));
+
+        matcher.ignore_mode = Combinator::And;
+        assert!(matcher.is_match(&target.val));
+    }
+
+    test matcher_response_time(target) {
+        let matcher = Matcher {
+            ignore: vec![Rule::ResponseTime(vec![NumericRange { min: 40, max: 60 }])],
+            ..Matcher::default()
+        };
+        assert!(!matcher.is_match(&target.val));
+    }
+}
@@ -0,0 +1,51 @@
+use serde::Serialize;
+use std::{fs::File, io::Write, path::Path};
+
+mod spec;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Json,
+    Jsonl,
+}
+
+pub struct JsonlWriter {
+    path: String,
+    file: Option<File>,
+}
+
+impl JsonlWriter {
+    pub fn new(path: &str, format: OutputFormat) -> Self {
+        let file = if format != OutputFormat::Jsonl || path.is_empty() {
+            None
+        } else {
+            match File::create(Path::new(path)) {
+                Ok(f) => Some(f),
+                Err(e) => {
+                    error!("Error while creating file: {}\n{}", path, e);
+                    None
+                }
+            }
+        };
+
+        JsonlWriter {
+            path: path.to_owned(),
+            file,
+        }
+    }
+
+    pub fn write<T: Serialize>(&mut self, result: &T) {
+        let file = match &mut self.file {
+            Some(file) => file,
+            None => return,
+        };
+
+        let mut line = serde_json::to_string(result).unwrap();
+        line.push('\n');
+
+        match file.write_all(line.as_bytes()).and_then(|_| file.flush()) {
+            Ok(_) => trace!("Result appended to: {}", self.path),
+            Err(e) => error!("Error while writing results to file: {}\n{}", self.path, e),
+        };
+    }
+}
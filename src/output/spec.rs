@@ -0,0 +1,46 @@
+use galvanic_test::test_suite;
+
+test_suite! {
+    name output;
+
+    use crate::output::{JsonlWriter, OutputFormat};
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    struct Finding {
+        url: String,
+        status: u16,
+    }
+
+    fn output(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("rustbuster-{}-{}", std::process::id(), name));
+        path.to_str().unwrap().to_owned()
+    }
+
+    test write_json_lines() {
+        let path = output("results.jsonl");
+        let mut writer = JsonlWriter::new(&path, OutputFormat::Jsonl);
+        writer.write(&Finding { url: "http://localhost/a".to_owned(), status: 200 });
+        writer.write(&Finding { url: "http://localhost/b\nc".to_owned(), status: 404 });
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let lines = content.lines().collect::<Vec<_>>();
+        assert_eq!(2, lines.len());
+        assert!(content.ends_with('\n'));
+        let first: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        let second: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!("http://localhost/a", first["url"]);
+        assert_eq!(404, second["status"]);
+    }
+
+    test skip_json_documents() {
+        let path = output("results.json");
+        let mut writer = JsonlWriter::new(&path, OutputFormat::Json);
+        writer.write(&Finding { url: "http://localhost/a".to_owned(), status: 200 });
+        assert!(!std::path::Path::new(&path).exists());
+
+        let mut writer = JsonlWriter::new("", OutputFormat::Jsonl);
+        writer.write(&Finding { url: "http://localhost/a".to_owned(), status: 200 });
+    }
+}
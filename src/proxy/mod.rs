@@ -0,0 +1,320 @@
+use futures::{
+    future::{self, Either, Loop},
+    sync::mpsc,
+    Future, Stream,
+};
+use hyper::{
+    client::{
+        connect::{
+            dns::{Name, Resolve, TokioThreadpoolGaiResolver},
+            Connect, Connected, Destination,
+        },
+        HttpConnector,
+    },
+    rt, Body, Client, Request, Uri,
+};
+use hyper_tls::HttpsConnector;
+use std::io;
+use std::net::IpAddr;
+use std::thread::{self, JoinHandle};
+use tokio_io::io::{read_exact, write_all};
+use tokio_tcp::TcpStream;
+
+mod spec;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProxyKind {
+    Http,
+    Socks5,
+    Socks5h,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Proxy {
+    pub kind: ProxyKind,
+    pub host: String,
+    pub port: u16,
+}
+
+impl Proxy {
+    pub fn parse(proxy: &str) -> Result<Proxy, String> {
+        let uri = proxy.parse::<Uri>().map_err(|e| e.to_string())?;
+        let kind = match uri.scheme_part().map(|s| s.as_str()) {
+            Some("http") => ProxyKind::Http,
+            Some("socks5") => ProxyKind::Socks5,
+            Some("socks5h") => ProxyKind::Socks5h,
+            Some(_) => return Err("only http://, socks5:// and socks5h:// are supported".to_owned()),
+            None => return Err("missing protocol, consider adding http:// or socks5://".to_owned()),
+        };
+        let host = match uri.host() {
+            Some(host) => host.to_owned(),
+            None => return Err("missing host".to_owned()),
+        };
+        let port = uri.port_u16().unwrap_or(match kind {
+            ProxyKind::Http => 8080,
+            ProxyKind::Socks5 | ProxyKind::Socks5h => 1080,
+        });
+
+        Ok(Proxy { kind, host, port })
+    }
+}
+
+type Connecting = Box<dyn Future<Item = (TcpStream, Connected), Error = io::Error> + Send>;
+
+#[derive(Debug, Clone)]
+pub struct ProxyConnector {
+    http: HttpConnector,
+    proxy: Option<Proxy>,
+}
+
+impl ProxyConnector {
+    pub fn new(http: HttpConnector, proxy: Option<Proxy>) -> Self {
+        ProxyConnector { http, proxy }
+    }
+}
+
+impl Connect for ProxyConnector {
+    type Transport = TcpStream;
+    type Error = io::Error;
+    type Future = Connecting;
+
+    fn connect(&self, dst: Destination) -> Self::Future {
+        let proxy = match &self.proxy {
+            Some(proxy) => proxy.clone(),
+            None => return Box::new(self.http.connect(dst)),
+        };
+
+        let is_https = dst.scheme() == "https";
+        let host = dst.host().to_owned();
+        let port = dst.port().unwrap_or(if is_https { 443 } else { 80 });
+        let proxy_dst = format!("http://{}:{}", proxy.host, proxy.port)
+            .parse::<Uri>()
+            .map_err(io::Error::other)
+            .and_then(|uri| {
+                Destination::try_from_uri(uri).map_err(io::Error::other)
+            });
+        let proxy_dst = match proxy_dst {
+            Ok(v) => v,
+            Err(e) => return Box::new(future::err(e)),
+        };
+        let connecting = self.http.connect(proxy_dst).map(|(tcp, _)| tcp);
+
+        match proxy.kind {
+            ProxyKind::Http if is_https => Box::new(
+                connecting
+                    .and_then(move |tcp| http_tunnel(tcp, host, port))
+                    .map(|tcp| (tcp, Connected::new())),
+            ),
+            ProxyKind::Http => Box::new(connecting.map(|tcp| (tcp, Connected::new().proxy(true)))),
+            ProxyKind::Socks5 => Box::new(
+                connecting
+                    .join(resolve(host))
+                    .and_then(move |(tcp, ip)| socks5_handshake(tcp, ip.to_string(), port))
+                    .map(|tcp| (tcp, Connected::new())),
+            ),
+            ProxyKind::Socks5h => Box::new(
+                connecting
+                    .and_then(move |tcp| socks5_handshake(tcp, host, port))
+                    .map(|tcp| (tcp, Connected::new())),
+            ),
+        }
+    }
+}
+
+fn proxy_error(message: &str) -> io::Error {
+    io::Error::other(message.to_owned())
+}
+
+fn http_tunnel(
+    tcp: TcpStream,
+    host: String,
+    port: u16,
+) -> impl Future<Item = TcpStream, Error = io::Error> {
+    let request = format!(
+        "CONNECT {0}:{1} HTTP/1.1\r\nHost: {0}:{1}\r\n\r\n",
+        host, port
+    );
+
+    write_all(tcp, request.into_bytes())
+        .and_then(|(tcp, _)| {
+            future::loop_fn((tcp, Vec::new()), |(tcp, mut head)| {
+                read_exact(tcp, [0u8; 1]).and_then(move |(tcp, byte)| {
+                    head.push(byte[0]);
+                    if head.ends_with(b"\r\n\r\n") {
+                        Ok(Loop::Break((tcp, head)))
+                    } else if head.len() > 8192 {
+                        Err(proxy_error("proxy response headers too long"))
+                    } else {
+                        Ok(Loop::Continue((tcp, head)))
+                    }
+                })
+            })
+        })
+        .and_then(|(tcp, head)| {
+            let head = String::from_utf8_lossy(&head);
+            match head.split_whitespace().nth(1) {
+                Some("200") => Ok(tcp),
+                _ => Err(proxy_error(&format!(
+                    "proxy tunnel failed: {}",
+                    head.lines().next().unwrap_or("")
+                ))),
+            }
+        })
+}
+
+fn resolve(host: String) -> impl Future<Item = IpAddr, Error = io::Error> {
+    if let Ok(ip) = host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+        return Either::A(future::ok(ip));
+    }
+
+    let name = match host.parse::<Name>() {
+        Ok(v) => v,
+        Err(e) => return Either::A(future::err(io::Error::other(e))),
+    };
+
+    Either::B(
+        TokioThreadpoolGaiResolver::new()
+            .resolve(name)
+            .and_then(move |mut addrs| {
+                addrs
+                    .next()
+                    .ok_or_else(|| proxy_error(&format!("unable to resolve {}", host)))
+            }),
+    )
+}
+
+pub fn socks5_address(host: &str, port: u16) -> Result<Vec<u8>, io::Error> {
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let mut address = match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => [&[1u8][..], &ip.octets()].concat(),
+        Ok(IpAddr::V6(ip)) => [&[4u8][..], &ip.octets()].concat(),
+        Err(_) if host.len() > 255 => return Err(proxy_error("SOCKS5 hostname too long")),
+        Err(_) => [&[3u8, host.len() as u8][..], host.as_bytes()].concat(),
+    };
+    address.extend_from_slice(&port.to_be_bytes());
+
+    Ok(address)
+}
+
+fn socks5_handshake(
+    tcp: TcpStream,
+    host: String,
+    port: u16,
+) -> impl Future<Item = TcpStream, Error = io::Error> {
+    write_all(tcp, vec![5u8, 1, 0])
+        .and_then(|(tcp, _)| read_exact(tcp, [0u8; 2]))
+        .and_then(move |(tcp, reply)| {
+            if reply != [5, 0] {
+                return Either::A(future::err(proxy_error(
+                    "SOCKS5 proxy requires an unsupported authentication method",
+                )));
+            }
+
+            let address = match socks5_address(&host, port) {
+                Ok(v) => v,
+                Err(e) => return Either::A(future::err(e)),
+            };
+
+            let mut request = vec![5u8, 1, 0];
+            request.extend_from_slice(&address);
+
+            Either::B(write_all(tcp, request))
+        })
+        .and_then(|(tcp, _)| read_exact(tcp, [0u8; 4]))
+        .and_then(|(tcp, reply)| {
+            if reply[1] != 0 {
+                return Either::A(future::err(proxy_error(&format!(
+                    "SOCKS5 connect failed with code {}",
+                    reply[1]
+                ))));
+            }
+
+            let remaining = match reply[3] {
+                1 => Either::A(future::ok((tcp, 4 + 2))),
+                4 => Either::A(future::ok((tcp, 16 + 2))),
+                3 => Either::B(
+                    read_exact(tcp, [0u8; 1]).map(|(tcp, len)| (tcp, len[0] as usize + 2)),
+                ),
+                _ => return Either::A(future::err(proxy_error("SOCKS5 invalid address type"))),
+            };
+
+            Either::B(
+                remaining
+                    .and_then(|(tcp, len)| read_exact(tcp, vec![0u8; len]))
+                    .map(|(tcp, _)| tcp),
+            )
+        })
+}
+
+pub fn build_client(
+    n_threads: usize,
+    ignore_certificate: bool,
+    proxy: &str,
+) -> Client<HttpsConnector<ProxyConnector>> {
+    let mut tls_connector_builder = native_tls::TlsConnector::builder();
+    tls_connector_builder.danger_accept_invalid_certs(ignore_certificate);
+    let tls_connector = tls_connector_builder
+        .build()
+        .expect("TLS initialization failed");
+    let mut http_connector = HttpConnector::new(n_threads);
+    http_connector.enforce_http(false);
+    let proxy = if proxy.is_empty() {
+        None
+    } else {
+        Some(Proxy::parse(proxy).expect("Invalid proxy"))
+    };
+    let proxy_connector = ProxyConnector::new(http_connector, proxy);
+    let https_connector = HttpsConnector::from((proxy_connector, tls_connector));
+    Client::builder().build(https_connector)
+}
+
+pub struct Replayer {
+    tx: Option<mpsc::UnboundedSender<Request<Body>>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Replayer {
+    pub fn new(n_threads: usize, ignore_certificate: bool, replay_proxy: &str) -> Self {
+        if replay_proxy.is_empty() {
+            return Replayer {
+                tx: None,
+                handle: None,
+            };
+        }
+
+        let client = build_client(n_threads, ignore_certificate, replay_proxy);
+        let (tx, rx) = mpsc::unbounded::<Request<Body>>();
+        let stream = rx
+            .map(move |request| {
+                client
+                    .request(request)
+                    .and_then(|res| res.into_body().concat2())
+                    .map(|_| ())
+                    .or_else(|e| {
+                        warn!("Replay failed: {}", e);
+                        Ok(())
+                    })
+            })
+            .buffer_unordered(n_threads)
+            .for_each(Ok);
+        let handle = thread::spawn(move || rt::run(stream));
+
+        Replayer {
+            tx: Some(tx),
+            handle: Some(handle),
+        }
+    }
+
+    pub fn replay(&self, request: Request<Body>) {
+        if let Some(tx) = &self.tx {
+            let _ = tx.unbounded_send(request);
+        }
+    }
+
+    pub fn finish(mut self) {
+        self.tx.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
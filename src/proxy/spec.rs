@@ -0,0 +1,77 @@
+use galvanic_test::test_suite;
+
+test_suite! {
+    name proxy;
+
+    use crate::proxy::{build_client, socks5_address, Proxy, ProxyKind};
+    use futures::Future;
+    use hyper::{Body, Request};
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    fn requested_address_type(scheme: &str) -> u8 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let proxy = format!("{}://{}", scheme, listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut greeting = [0u8; 3];
+            stream.read_exact(&mut greeting).unwrap();
+            stream.write_all(&[5, 0]).unwrap();
+            let mut request = [0u8; 4];
+            stream.read_exact(&mut request).unwrap();
+            stream.write_all(&[5, 1, 0, 1, 0, 0, 0, 0, 0, 0]).unwrap();
+            request[3]
+        });
+
+        let client = build_client(1, false, &proxy);
+        let request = Request::get("http://localhost/").body(Body::empty()).unwrap();
+        hyper::rt::run(client.request(request).then(|_| Ok(())));
+        server.join().unwrap()
+    }
+
+    test proxy_parse_http() {
+        assert_eq!(
+            Proxy::parse("http://127.0.0.1:8080").unwrap(),
+            Proxy {
+                kind: ProxyKind::Http,
+                host: "127.0.0.1".to_owned(),
+                port: 8080,
+            }
+        );
+        assert_eq!(Proxy::parse("http://burp").unwrap().port, 8080);
+    }
+
+    test proxy_parse_socks5() {
+        assert_eq!(
+            Proxy::parse("socks5://10.0.0.1:9050").unwrap(),
+            Proxy {
+                kind: ProxyKind::Socks5,
+                host: "10.0.0.1".to_owned(),
+                port: 9050,
+            }
+        );
+        assert_eq!(Proxy::parse("socks5h://pivot").unwrap().port, 1080);
+        assert_eq!(Proxy::parse("socks5h://pivot").unwrap().kind, ProxyKind::Socks5h);
+    }
+
+    test socks5_addresses() {
+        assert_eq!(vec![1, 10, 0, 0, 1, 0, 80], socks5_address("10.0.0.1", 80).unwrap());
+        let mut ipv6 = vec![4];
+        ipv6.extend_from_slice(&[0; 15]);
+        ipv6.extend_from_slice(&[1, 1, 187]);
+        assert_eq!(ipv6, socks5_address("[::1]", 443).unwrap());
+        assert_eq!(b"\x03\x09localhost\x1f\x90".to_vec(), socks5_address("localhost", 8080).unwrap());
+        assert!(socks5_address(&"a".repeat(256), 80).is_err());
+    }
+
+    test socks5_resolve_locally() {
+        assert!(matches!(requested_address_type("socks5"), 1 | 4));
+        assert_eq!(3, requested_address_type("socks5h"));
+    }
+
+    test proxy_parse_invalid() {
+        assert!(Proxy::parse("127.0.0.1:8080").is_err());
+        assert!(Proxy::parse("ftp://127.0.0.1:21").is_err());
+        assert!(Proxy::parse("https://127.0.0.1:8443").is_err());
+    }
+}
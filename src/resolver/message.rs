@@ -0,0 +1,317 @@
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+pub const TYPE_A: u16 = 1;
+pub const TYPE_NS: u16 = 2;
+pub const TYPE_CNAME: u16 = 5;
+pub const TYPE_SOA: u16 = 6;
+pub const TYPE_MX: u16 = 15;
+pub const TYPE_TXT: u16 = 16;
+pub const TYPE_AAAA: u16 = 28;
+pub const TYPE_SRV: u16 = 33;
+pub const TYPE_AXFR: u16 = 252;
+
+const RECORD_TYPES: [(u16, &str); 8] = [
+    (TYPE_A, "A"),
+    (TYPE_NS, "NS"),
+    (TYPE_CNAME, "CNAME"),
+    (TYPE_SOA, "SOA"),
+    (TYPE_MX, "MX"),
+    (TYPE_TXT, "TXT"),
+    (TYPE_AAAA, "AAAA"),
+    (TYPE_SRV, "SRV"),
+];
+
+pub const RCODE_SERVFAIL: u8 = 2;
+pub const RCODE_NXDOMAIN: u8 = 3;
+pub const RCODE_REFUSED: u8 = 5;
+
+const CLASS_IN: u16 = 1;
+const HEADER_LENGTH: usize = 12;
+const MAX_POINTERS: usize = 64;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordData {
+    A(Ipv4Addr),
+    Aaaa(Ipv6Addr),
+    Cname(String),
+    Ns(String),
+    Soa(String, String, [u32; 5]),
+    Mx(u16, String),
+    Txt(Vec<String>),
+    Srv(u16, u16, u16, String),
+    Other(Vec<u8>),
+}
+
+impl RecordData {
+    pub fn ip(&self) -> Option<IpAddr> {
+        match self {
+            RecordData::A(ip) => Some(IpAddr::V4(*ip)),
+            RecordData::Aaaa(ip) => Some(IpAddr::V6(*ip)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RecordData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordData::A(ip) => write!(f, "{}", ip),
+            RecordData::Aaaa(ip) => write!(f, "{}", ip),
+            RecordData::Cname(name) | RecordData::Ns(name) => write!(f, "{}", name),
+            RecordData::Soa(mname, rname, values) => write!(
+                f,
+                "{} {} {}",
+                mname,
+                rname,
+                values
+                    .iter()
+                    .map(|value| value.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            ),
+            RecordData::Mx(preference, exchange) => write!(f, "{} {}", preference, exchange),
+            RecordData::Txt(strings) => write!(
+                f,
+                "{}",
+                strings
+                    .iter()
+                    .map(|string| format!("{:?}", string))
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            ),
+            RecordData::Srv(priority, weight, port, target) => {
+                write!(f, "{} {} {} {}", priority, weight, port, target)
+            }
+            RecordData::Other(data) => write!(
+                f,
+                "{}",
+                data.iter().map(|byte| format!("{:02x}", byte)).collect::<String>()
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub name: String,
+    pub rtype: u16,
+    pub ttl: u32,
+    pub data: RecordData,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    pub id: u16,
+    pub truncated: bool,
+    pub rcode: u8,
+    pub answers: Vec<Record>,
+}
+
+pub fn build_query(id: u16, name: &str, qtype: u16) -> Result<Vec<u8>, String> {
+    let mut packet = Vec::with_capacity(HEADER_LENGTH + name.len() + 6);
+    packet.extend_from_slice(&id.to_be_bytes());
+    packet.extend_from_slice(&[0x01, 0x00]);
+    packet.extend_from_slice(&1u16.to_be_bytes());
+    packet.extend_from_slice(&[0; 6]);
+    write_name(&mut packet, name)?;
+    packet.extend_from_slice(&qtype.to_be_bytes());
+    packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+
+    Ok(packet)
+}
+
+fn write_name(packet: &mut Vec<u8>, name: &str) -> Result<(), String> {
+    let name = name.trim_end_matches('.');
+    if name.len() > 253 {
+        return Err(format!("{} is too long", name));
+    }
+
+    for label in name.split('.').filter(|label| !label.is_empty()) {
+        if label.len() > 63 {
+            return Err(format!("label {} is too long", label));
+        }
+
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label.as_bytes());
+    }
+
+    packet.push(0);
+    Ok(())
+}
+
+pub fn answers_query(query: &[u8], response: &[u8]) -> bool {
+    response.len() >= query.len()
+        && query.len() > HEADER_LENGTH
+        && response[..2] == query[..2]
+        && response[2] & 0x80 != 0
+        && read_u16(response, 4) == Ok(1)
+        && response[HEADER_LENGTH..query.len()].eq_ignore_ascii_case(&query[HEADER_LENGTH..])
+}
+
+pub fn parse(packet: &[u8]) -> Result<Message, String> {
+    if packet.len() < HEADER_LENGTH {
+        return Err("truncated header".to_owned());
+    }
+
+    let id = read_u16(packet, 0)?;
+    let truncated = packet[2] & 0x02 != 0;
+    let rcode = packet[3] & 0x0f;
+    let questions = read_u16(packet, 4)?;
+    let answers = read_u16(packet, 6)?;
+
+    let mut offset = HEADER_LENGTH;
+    for _ in 0..questions {
+        let (_, next) = read_name(packet, offset)?;
+        offset = next + 4;
+    }
+
+    let mut records = Vec::with_capacity(answers as usize);
+    for _ in 0..answers {
+        if truncated && offset >= packet.len() {
+            break;
+        }
+
+        let (record, next) = read_record(packet, offset)?;
+        records.push(record);
+        offset = next;
+    }
+
+    Ok(Message {
+        id,
+        truncated,
+        rcode,
+        answers: records,
+    })
+}
+
+fn read_record(packet: &[u8], offset: usize) -> Result<(Record, usize), String> {
+    let (name, offset) = read_name(packet, offset)?;
+    let rtype = read_u16(packet, offset)?;
+    let ttl = read_u32(packet, offset + 4)?;
+    let length = read_u16(packet, offset + 8)? as usize;
+    let start = offset + 10;
+    let end = start + length;
+    let rdata = packet.get(start..end).ok_or("truncated record")?;
+
+    let data = match rtype {
+        TYPE_A if length == 4 => RecordData::A(Ipv4Addr::new(rdata[0], rdata[1], rdata[2], rdata[3])),
+        TYPE_AAAA if length == 16 => {
+            let mut octets = [0u8; 16];
+            octets.copy_from_slice(rdata);
+            RecordData::Aaaa(Ipv6Addr::from(octets))
+        }
+        TYPE_CNAME => RecordData::Cname(read_name(packet, start)?.0),
+        TYPE_NS => RecordData::Ns(read_name(packet, start)?.0),
+        TYPE_SOA => {
+            let (mname, next) = read_name(packet, start)?;
+            let (rname, next) = read_name(packet, next)?;
+            let mut values = [0u32; 5];
+            for (index, value) in values.iter_mut().enumerate() {
+                *value = read_u32(packet, next + index * 4)?;
+            }
+
+            RecordData::Soa(mname, rname, values)
+        }
+        TYPE_MX if length > 2 => RecordData::Mx(read_u16(packet, start)?, read_name(packet, start + 2)?.0),
+        TYPE_TXT => RecordData::Txt(read_strings(rdata)?),
+        TYPE_SRV if length > 6 => RecordData::Srv(
+            read_u16(packet, start)?,
+            read_u16(packet, start + 2)?,
+            read_u16(packet, start + 4)?,
+            read_name(packet, start + 6)?.0,
+        ),
+        _ => RecordData::Other(rdata.to_vec()),
+    };
+
+    Ok((
+        Record {
+            name,
+            rtype,
+            ttl,
+            data,
+        },
+        end,
+    ))
+}
+
+fn read_name(packet: &[u8], offset: usize) -> Result<(String, usize), String> {
+    let mut labels = Vec::new();
+    let mut position = offset;
+    let mut next = None;
+    let mut pointers = 0;
+
+    loop {
+        let length = *packet.get(position).ok_or("truncated name")? as usize;
+        match length & 0xc0 {
+            0x00 if length == 0 => {
+                position += 1;
+                break;
+            }
+            0x00 => {
+                let label = packet
+                    .get(position + 1..position + 1 + length)
+                    .ok_or("truncated label")?;
+                labels.push(String::from_utf8_lossy(label).into_owned());
+                position += 1 + length;
+            }
+            0xc0 => {
+                pointers += 1;
+                if pointers > MAX_POINTERS {
+                    return Err("compression loop".to_owned());
+                }
+
+                let pointer = (read_u16(packet, position)? & 0x3fff) as usize;
+                next.get_or_insert(position + 2);
+                position = pointer;
+            }
+            _ => return Err("unsupported label type".to_owned()),
+        }
+    }
+
+    Ok((labels.join("."), next.unwrap_or(position)))
+}
+
+fn read_strings(rdata: &[u8]) -> Result<Vec<String>, String> {
+    let mut strings = Vec::new();
+    let mut position = 0;
+
+    while position < rdata.len() {
+        let length = rdata[position] as usize;
+        let string = rdata
+            .get(position + 1..position + 1 + length)
+            .ok_or("truncated character string")?;
+        strings.push(String::from_utf8_lossy(string).into_owned());
+        position += 1 + length;
+    }
+
+    Ok(strings)
+}
+
+fn read_u16(packet: &[u8], offset: usize) -> Result<u16, String> {
+    match packet.get(offset..offset + 2) {
+        Some(bytes) => Ok(u16::from_be_bytes([bytes[0], bytes[1]])),
+        None => Err("unexpected end of message".to_owned()),
+    }
+}
+
+fn read_u32(packet: &[u8], offset: usize) -> Result<u32, String> {
+    match packet.get(offset..offset + 4) {
+        Some(bytes) => Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+        None => Err("unexpected end of message".to_owned()),
+    }
+}
+
+pub fn type_name(rtype: u16) -> String {
+    match RECORD_TYPES.iter().find(|(code, _)| *code == rtype) {
+        Some((_, name)) => (*name).to_owned(),
+        None => format!("TYPE{}", rtype),
+    }
+}
+
+pub fn type_code(name: &str) -> Option<u16> {
+    RECORD_TYPES
+        .iter()
+        .find(|(_, v)| v.eq_ignore_ascii_case(name.trim()))
+        .map(|(code, _)| *code)
+}
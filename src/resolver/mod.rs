@@ -0,0 +1,378 @@
+use futures::{
+    future::{self, Either, Loop},
+    Future,
+};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio_io::io::{read_exact, write_all};
+use tokio_tcp::TcpStream;
+use tokio_timer::Timeout;
+use tokio_udp::UdpSocket;
+
+use crate::retry::RetryPolicy;
+
+pub mod message;
+mod spec;
+
+use message::{Message, Record, RCODE_REFUSED, RCODE_SERVFAIL, TYPE_A, TYPE_AAAA, TYPE_AXFR, TYPE_SOA};
+
+pub const DNS_PORT: u16 = 53;
+const MAX_FAILURES: usize = 3;
+const FAILURE_COOLDOWN_MS: u64 = 5000;
+const MAX_UDP_SIZE: usize = 4096;
+const RESOLV_CONF: &str = "/etc/resolv.conf";
+
+type Exchange = Box<dyn Future<Item = Message, Error = ResolveError> + Send>;
+type Transfer = Box<dyn Future<Item = Vec<Record>, Error = ResolveError> + Send>;
+
+#[derive(Debug)]
+pub enum ResolveError {
+    Timeout,
+    InvalidName(String),
+    Io(io::Error),
+    Timer(tokio_timer::Error),
+    Protocol(String),
+    Server(u8),
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ResolveError::Timeout => write!(f, "query timed out"),
+            ResolveError::InvalidName(e) => write!(f, "invalid name: {}", e),
+            ResolveError::Io(e) => write!(f, "{}", e),
+            ResolveError::Timer(e) => write!(f, "timer error: {}", e),
+            ResolveError::Protocol(e) => write!(f, "invalid response: {}", e),
+            ResolveError::Server(rcode) => write!(f, "server failure (rcode {})", rcode),
+        }
+    }
+}
+
+impl From<tokio_timer::timeout::Error<ResolveError>> for ResolveError {
+    fn from(e: tokio_timer::timeout::Error<ResolveError>) -> Self {
+        if e.is_elapsed() {
+            ResolveError::Timeout
+        } else if e.is_timer() {
+            ResolveError::Timer(e.into_timer().unwrap())
+        } else {
+            e.into_inner().unwrap()
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Nameserver {
+    addr: SocketAddr,
+    failures: AtomicUsize,
+    benched_until: Mutex<Option<Instant>>,
+}
+
+impl Nameserver {
+    fn is_available(&self, now: Instant) -> bool {
+        match *self.benched_until.lock().unwrap() {
+            Some(benched_until) => benched_until <= now,
+            None => true,
+        }
+    }
+
+    fn succeeded(&self) {
+        self.failures.store(0, Ordering::SeqCst);
+        *self.benched_until.lock().unwrap() = None;
+    }
+
+    fn failed(&self) {
+        if self.failures.fetch_add(1, Ordering::SeqCst) + 1 >= MAX_FAILURES {
+            let benched_until = Instant::now() + Duration::from_millis(FAILURE_COOLDOWN_MS);
+            *self.benched_until.lock().unwrap() = Some(benched_until);
+            debug!("benching nameserver {} for {}ms", self.addr, FAILURE_COOLDOWN_MS);
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Resolver {
+    nameservers: Arc<Vec<Nameserver>>,
+    next: Arc<AtomicUsize>,
+    policy: RetryPolicy,
+}
+
+impl Resolver {
+    pub fn new(nameservers: &[SocketAddr], policy: RetryPolicy) -> Self {
+        Resolver {
+            nameservers: Arc::new(
+                nameservers
+                    .iter()
+                    .map(|addr| Nameserver {
+                        addr: *addr,
+                        failures: AtomicUsize::new(0),
+                        benched_until: Mutex::new(None),
+                    })
+                    .collect(),
+            ),
+            next: Arc::new(AtomicUsize::new(0)),
+            policy,
+        }
+    }
+
+    fn pick(&self) -> usize {
+        let start = self.next.fetch_add(1, Ordering::SeqCst);
+        let length = self.nameservers.len();
+        let now = Instant::now();
+
+        (0..length)
+            .map(|index| (start + index) % length)
+            .find(|index| self.nameservers[*index].is_available(now))
+            .unwrap_or(start % length)
+    }
+
+    pub fn query(&self, name: &str, qtype: u16) -> impl Future<Item = Message, Error = ResolveError> + Send {
+        let resolver = self.clone();
+        let name = name.to_owned();
+
+        future::loop_fn(0, move |attempt| {
+            let index = resolver.pick();
+            let resolver = resolver.clone();
+            let name = name.clone();
+            let addr = resolver.nameservers[index].addr;
+
+            resolver.exchange(addr, &name, qtype).then(move |result| {
+                let result = match result {
+                    Ok(ref message) if message.rcode == RCODE_SERVFAIL || message.rcode == RCODE_REFUSED => {
+                        Err(ResolveError::Server(message.rcode))
+                    }
+                    result => result,
+                };
+                let nameserver = &resolver.nameservers[index];
+
+                match result {
+                    Ok(message) => {
+                        nameserver.succeeded();
+                        Ok(Loop::Break(message))
+                    }
+                    Err(ResolveError::InvalidName(e)) => Err(ResolveError::InvalidName(e)),
+                    Err(e) => {
+                        nameserver.failed();
+                        if attempt >= resolver.policy.retries {
+                            return Err(e);
+                        }
+
+                        debug!("retrying {} after error from {}: {}", name, addr, e);
+                        Ok(Loop::Continue(attempt + 1))
+                    }
+                }
+            })
+        })
+    }
+
+    pub fn lookup(&self, name: &str, types: &[u16]) -> impl Future<Item = Vec<Record>, Error = ResolveError> + Send {
+        let queries = types
+            .iter()
+            .map(|qtype| self.query(name, *qtype).then(Ok::<_, ()>))
+            .collect::<Vec<_>>();
+
+        future::join_all(queries)
+            .map_err(|_| unreachable!())
+            .and_then(|responses| {
+                let mut records: Vec<Record> = Vec::new();
+                let mut error = None;
+                let mut answered = false;
+
+                for response in responses {
+                    match response {
+                        Ok(message) => {
+                            answered = true;
+                            for record in message.answers {
+                                if !records.contains(&record) {
+                                    records.push(record);
+                                }
+                            }
+                        }
+                        Err(e) => error = Some(e),
+                    }
+                }
+
+                match error {
+                    Some(e) if !answered => Err(e),
+                    _ => Ok(records),
+                }
+            })
+    }
+
+    pub fn lookup_ip(&self, name: &str) -> impl Future<Item = Vec<IpAddr>, Error = ResolveError> + Send {
+        self.lookup(name, &[TYPE_A, TYPE_AAAA])
+            .map(|records| records.iter().filter_map(|record| record.data.ip()).collect())
+    }
+
+    pub fn zone_transfer(&self, addr: SocketAddr, zone: &str) -> Transfer {
+        let id = rand::random::<u16>();
+        let packet = match message::build_query(id, zone, TYPE_AXFR) {
+            Ok(v) => v,
+            Err(e) => return Box::new(future::err(ResolveError::InvalidName(e))),
+        };
+
+        let transfer = TcpStream::connect(&addr)
+            .and_then(|tcp| write_all(tcp, frame(packet)))
+            .map_err(ResolveError::Io)
+            .and_then(move |(tcp, _)| {
+                future::loop_fn((tcp, Vec::new(), 0), move |(tcp, mut records, soas)| {
+                    read_message(tcp).and_then(move |(tcp, message)| {
+                        if message.id != id {
+                            return Err(ResolveError::Protocol("mismatched message id".to_owned()));
+                        }
+                        if message.rcode != 0 {
+                            return Err(ResolveError::Server(message.rcode));
+                        }
+                        if message.answers.is_empty() {
+                            return Err(ResolveError::Protocol("empty zone transfer message".to_owned()));
+                        }
+                        if records.is_empty() && message.answers[0].rtype != TYPE_SOA {
+                            return Err(ResolveError::Protocol("zone transfer does not start with SOA".to_owned()));
+                        }
+
+                        let soas = soas + message.answers.iter().filter(|record| record.rtype == TYPE_SOA).count();
+                        records.extend(message.answers);
+                        if soas < 2 {
+                            return Ok(Loop::Continue((tcp, records, soas)));
+                        }
+
+                        records.pop();
+                        Ok(Loop::Break(records))
+                    })
+                })
+            });
+
+        match self.policy.timeout {
+            Some(timeout) => Box::new(Timeout::new(transfer, timeout).map_err(ResolveError::from)),
+            None => Box::new(transfer),
+        }
+    }
+
+    fn exchange(&self, addr: SocketAddr, name: &str, qtype: u16) -> Exchange {
+        let id = rand::random::<u16>();
+        let packet = match message::build_query(id, name, qtype) {
+            Ok(v) => v,
+            Err(e) => return Box::new(future::err(ResolveError::InvalidName(e))),
+        };
+
+        let response = udp_exchange(addr, packet.clone())
+            .and_then(move |message| {
+                if message.truncated {
+                    Either::A(tcp_exchange(addr, packet))
+                } else {
+                    Either::B(future::ok(message))
+                }
+            })
+            .and_then(move |message| {
+                if message.id == id {
+                    Ok(message)
+                } else {
+                    Err(ResolveError::Protocol("mismatched message id".to_owned()))
+                }
+            });
+
+        match self.policy.timeout {
+            Some(timeout) => Box::new(Timeout::new(response, timeout).map_err(ResolveError::from)),
+            None => Box::new(response),
+        }
+    }
+}
+
+fn udp_exchange(addr: SocketAddr, packet: Vec<u8>) -> impl Future<Item = Message, Error = ResolveError> {
+    let local: SocketAddr = if addr.is_ipv4() {
+        ([0, 0, 0, 0], 0).into()
+    } else {
+        ([0u16; 8], 0).into()
+    };
+    let socket = match UdpSocket::bind(&local) {
+        Ok(v) => v,
+        Err(e) => return Either::A(future::err(ResolveError::Io(e))),
+    };
+
+    Either::B(
+        socket
+            .send_dgram(packet, &addr)
+            .and_then(move |(socket, packet)| {
+                future::loop_fn((socket, packet), move |(socket, packet)| {
+                    socket
+                        .recv_dgram(vec![0u8; MAX_UDP_SIZE])
+                        .map(move |(socket, mut buffer, length, source)| {
+                            if source == addr && message::answers_query(&packet, &buffer[..length]) {
+                                buffer.truncate(length);
+                                Loop::Break(buffer)
+                            } else {
+                                debug!("ignoring unexpected datagram from {}", source);
+                                Loop::Continue((socket, packet))
+                            }
+                        })
+                })
+            })
+            .map_err(ResolveError::Io)
+            .and_then(|buffer| message::parse(&buffer).map_err(ResolveError::Protocol)),
+    )
+}
+
+pub fn tcp_exchange(addr: SocketAddr, packet: Vec<u8>) -> impl Future<Item = Message, Error = ResolveError> {
+    TcpStream::connect(&addr)
+        .and_then(|tcp| write_all(tcp, frame(packet)))
+        .map_err(ResolveError::Io)
+        .and_then(|(tcp, _)| read_message(tcp))
+        .map(|(_, message)| message)
+}
+
+fn frame(packet: Vec<u8>) -> Vec<u8> {
+    let mut framed = (packet.len() as u16).to_be_bytes().to_vec();
+    framed.extend(packet);
+    framed
+}
+
+fn read_message(tcp: TcpStream) -> impl Future<Item = (TcpStream, Message), Error = ResolveError> {
+    read_exact(tcp, [0u8; 2])
+        .and_then(|(tcp, length)| read_exact(tcp, vec![0u8; u16::from_be_bytes(length) as usize]))
+        .map_err(ResolveError::Io)
+        .and_then(|(tcp, buffer)| match message::parse(&buffer) {
+            Ok(message) => Ok((tcp, message)),
+            Err(e) => Err(ResolveError::Protocol(e)),
+        })
+}
+
+pub fn parse_nameserver(nameserver: &str) -> Result<SocketAddr, String> {
+    let nameserver = nameserver.trim();
+    if let Ok(addr) = nameserver.parse::<SocketAddr>() {
+        return Ok(addr);
+    }
+
+    match nameserver.parse::<IpAddr>() {
+        Ok(ip) => Ok(SocketAddr::new(ip, DNS_PORT)),
+        Err(_) => Err(format!("{} is not a valid resolver address", nameserver)),
+    }
+}
+
+pub fn parse_resolv_conf(content: &str) -> Vec<SocketAddr> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            match (fields.next(), fields.next()) {
+                (Some("nameserver"), Some(nameserver)) => {
+                    parse_nameserver(nameserver.split('%').next().unwrap()).ok()
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+pub fn system_nameservers() -> Vec<SocketAddr> {
+    match fs::read_to_string(RESOLV_CONF) {
+        Ok(content) => parse_resolv_conf(&content),
+        Err(e) => {
+            debug!("unable to read {}: {}", RESOLV_CONF, e);
+            Vec::new()
+        }
+    }
+}
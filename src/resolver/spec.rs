@@ -0,0 +1,257 @@
+use galvanic_test::test_suite;
+
+test_suite! {
+    name resolver;
+
+    use crate::resolver::message::{self, RecordData, TYPE_A, TYPE_MX, TYPE_SOA};
+    use crate::resolver::{parse_nameserver, parse_resolv_conf, Resolver};
+    use crate::retry::RetryPolicy;
+    use futures::Future;
+    use std::io::{Read, Write};
+    use std::net::{SocketAddr, TcpListener, UdpSocket};
+
+    fn reply(query: &[u8], truncated: bool) -> Vec<u8> {
+        let name = String::from_utf8_lossy(&query[13..]).into_owned();
+        let mut packet = query.to_vec();
+        packet[2] = 0x81 | if truncated { 0x02 } else { 0x00 };
+        packet[3] = if name.starts_with("missing") { 0x83 } else { 0x80 };
+        if name.starts_with("missing") || truncated {
+            return packet;
+        }
+
+        packet[7] = 1;
+        packet.extend_from_slice(&[0xc0, 0x0c, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x0e, 0x10, 0x00, 0x04, 10, 0, 0, 1]);
+        packet
+    }
+
+    fn stub_server() -> SocketAddr {
+        let udp = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = udp.local_addr().unwrap();
+        let tcp = TcpListener::bind(addr).unwrap();
+
+        std::thread::spawn(move || {
+            let mut buffer = [0u8; 512];
+            while let Ok((length, peer)) = udp.recv_from(&mut buffer) {
+                let query = &buffer[..length];
+                let truncated = String::from_utf8_lossy(&query[13..]).starts_with("big");
+                udp.send_to(&reply(query, truncated), peer).unwrap();
+            }
+        });
+
+        std::thread::spawn(move || {
+            for stream in tcp.incoming() {
+                let mut stream = stream.unwrap();
+                let mut length = [0u8; 2];
+                stream.read_exact(&mut length).unwrap();
+                let mut query = vec![0u8; u16::from_be_bytes(length) as usize];
+                stream.read_exact(&mut query).unwrap();
+                let response = reply(&query, false);
+                stream.write_all(&(response.len() as u16).to_be_bytes()).unwrap();
+                stream.write_all(&response).unwrap();
+            }
+        });
+
+        addr
+    }
+
+    fn zone_server() -> SocketAddr {
+        let tcp = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = tcp.local_addr().unwrap();
+        let soa = [0xc0, 0x0c, 0x00, 0x06, 0x00, 0x01, 0x00, 0x00, 0x0e, 0x10, 0x00, 0x18, 0xc0, 0x0c,
+            0xc0, 0x0c, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00,
+            0x00, 0x04, 0x00, 0x00, 0x00, 0x05];
+
+        std::thread::spawn(move || {
+            for stream in tcp.incoming() {
+                let mut stream = stream.unwrap();
+                let mut length = [0u8; 2];
+                stream.read_exact(&mut length).unwrap();
+                let mut query = vec![0u8; u16::from_be_bytes(length) as usize];
+                stream.read_exact(&mut query).unwrap();
+
+                let mut first = query.clone();
+                first[2] = 0x84;
+                first[3] = 0x80;
+                let mut second = first.clone();
+                if String::from_utf8_lossy(&query[13..]).starts_with("refused") {
+                    first[3] = 0x85;
+                    second.clear();
+                } else {
+                    first[7] = 2;
+                    first.extend_from_slice(&soa);
+                    first.extend_from_slice(&[0x03, b'w', b'w', b'w', 0xc0, 0x0c, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x0e, 0x10, 0x00, 0x04, 10, 0, 0, 1]);
+                    second[7] = 1;
+                    second.extend_from_slice(&soa);
+                }
+
+                for response in [first, second].iter().filter(|response| !response.is_empty()) {
+                    stream.write_all(&(response.len() as u16).to_be_bytes()).unwrap();
+                    stream.write_all(response).unwrap();
+                }
+            }
+        });
+
+        addr
+    }
+
+    fn transfer(resolver: &Resolver, addr: SocketAddr, zone: &str) -> Result<Vec<message::Record>, String> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let transfer = resolver.zone_transfer(addr, zone).then(move |result| {
+            tx.send(result.map_err(|e| e.to_string())).unwrap();
+            Ok(())
+        });
+        hyper::rt::run(transfer);
+        rx.recv().unwrap()
+    }
+
+    fn resolve(resolver: &Resolver, name: &str) -> Result<message::Message, String> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let query = resolver.query(name, TYPE_A).then(move |result| {
+            tx.send(result.map_err(|e| e.to_string())).unwrap();
+            Ok(())
+        });
+        hyper::rt::run(query);
+        rx.recv().unwrap()
+    }
+
+    test build_and_parse_query() {
+        let query = message::build_query(0x1234, "www.example.com.", TYPE_A).unwrap();
+        let parsed = message::parse(&query).unwrap();
+        assert_eq!(0x1234, parsed.id);
+        assert!(parsed.answers.is_empty());
+        assert!(message::build_query(1, &"a".repeat(64), TYPE_A).is_err());
+    }
+
+    test parse_record_types() {
+        let mut packet = message::build_query(1, "example.com", TYPE_MX).unwrap();
+        packet[2] = 0x81;
+        packet[3] = 0x80;
+        packet[7] = 3;
+        packet.extend_from_slice(&[0xc0, 0x0c, 0x00, 0x0f, 0x00, 0x01, 0x00, 0x00, 0x00, 0x3c, 0x00, 0x09, 0x00, 0x0a]);
+        packet.extend_from_slice(&[0x04, b'm', b'a', b'i', b'l', 0xc0, 0x0c]);
+        packet.extend_from_slice(&[0xc0, 0x0c, 0x00, 0x10, 0x00, 0x01, 0x00, 0x00, 0x00, 0x3c, 0x00, 0x06]);
+        packet.extend_from_slice(&[0x02, b'h', b'i', 0x01, b'!', 0x00]);
+        packet.extend_from_slice(&[0xc0, 0x0c, 0x00, 0x21, 0x00, 0x01, 0x00, 0x00, 0x00, 0x3c, 0x00, 0x08]);
+        packet.extend_from_slice(&[0x00, 0x01, 0x00, 0x02, 0x01, 0xbb, 0xc0, 0x0c]);
+
+        let parsed = message::parse(&packet).unwrap();
+        assert_eq!(RecordData::Mx(10, "mail.example.com".to_owned()), parsed.answers[0].data);
+        assert_eq!("10 mail.example.com", parsed.answers[0].data.to_string());
+        assert_eq!(60, parsed.answers[0].ttl);
+        assert_eq!("\"hi\" \"!\" \"\"", parsed.answers[1].data.to_string());
+        assert_eq!(RecordData::Srv(1, 2, 443, "example.com".to_owned()), parsed.answers[2].data);
+        assert_eq!("SRV", message::type_name(parsed.answers[2].rtype));
+        assert_eq!("TYPE99", message::type_name(99));
+        assert_eq!(Some(TYPE_MX), message::type_code("mx"));
+        assert_eq!(None, message::type_code("foo"));
+    }
+
+    test resolve_against_stub_server() {
+        let addr = stub_server();
+        let silent = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let resolver = Resolver::new(&[silent.local_addr().unwrap(), addr], RetryPolicy::new(200, 2));
+
+        let response = resolve(&resolver, "www.example.com").unwrap();
+        assert_eq!(1, response.answers.len());
+        assert_eq!("www.example.com", response.answers[0].name);
+        assert_eq!(3600, response.answers[0].ttl);
+        assert_eq!(RecordData::A("10.0.0.1".parse().unwrap()), response.answers[0].data);
+
+        let response = resolve(&resolver, "missing.example.com").unwrap();
+        assert_eq!(3, response.rcode);
+        assert!(response.answers.is_empty());
+
+        let response = resolve(&resolver, "big.example.com").unwrap();
+        assert!(!response.truncated);
+        assert_eq!(1, response.answers.len());
+
+        let unreachable = Resolver::new(&[silent.local_addr().unwrap()], RetryPolicy::new(100, 1));
+        assert_eq!(Err("query timed out".to_owned()), resolve(&unreachable, "www.example.com").map(|_| ()));
+    }
+
+    test ignore_spoofed_replies() {
+        let udp = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = udp.local_addr().unwrap();
+        let spoofer = UdpSocket::bind("127.0.0.1:0").unwrap();
+
+        std::thread::spawn(move || {
+            let mut buffer = [0u8; 512];
+            let (length, peer) = udp.recv_from(&mut buffer).unwrap();
+            let query = &buffer[..length];
+            let mut forged = reply(query, false);
+            let last = forged.len() - 1;
+            forged[last] = 6;
+            spoofer.send_to(&forged, peer).unwrap();
+
+            let mut other_question = reply(query, false);
+            other_question[13] = b'x';
+            udp.send_to(&other_question, peer).unwrap();
+            udp.send_to(&reply(query, false), peer).unwrap();
+        });
+
+        let resolver = Resolver::new(&[addr], RetryPolicy::new(1000, 0));
+        let response = resolve(&resolver, "www.example.com").unwrap();
+        assert_eq!("www.example.com", response.answers[0].name);
+        assert_eq!(RecordData::A("10.0.0.1".parse().unwrap()), response.answers[0].data);
+
+        let query = message::build_query(7, "www.example.com", TYPE_A).unwrap();
+        assert!(!message::answers_query(&query, &query));
+        assert!(message::answers_query(&query, &reply(&query, false)));
+        assert!(!message::answers_query(&query, &reply(&message::build_query(8, "www.example.com", TYPE_A).unwrap(), false)));
+    }
+
+    test zone_transfer_against_stub_server() {
+        let addr = zone_server();
+        let resolver = Resolver::new(&[addr], RetryPolicy::new(1000, 0));
+
+        let records = transfer(&resolver, addr, "example.com").unwrap();
+        assert_eq!(2, records.len());
+        assert_eq!(TYPE_SOA, records[0].rtype);
+        assert_eq!("example.com example.com 1 2 3 4 5", records[0].data.to_string());
+        assert_eq!("www.example.com", records[1].name);
+        assert_eq!(RecordData::A("10.0.0.1".parse().unwrap()), records[1].data);
+
+        assert_eq!(Err("server failure (rcode 5)".to_owned()), transfer(&resolver, addr, "refused.example.com").map(|_| ()));
+    }
+
+    fn picks(resolver: &Resolver) -> Vec<usize> {
+        (0..4).map(|_| resolver.pick()).collect()
+    }
+
+    test bench_failing_nameservers() {
+        let nameservers: Vec<SocketAddr> = vec!["127.0.0.1:53".parse().unwrap(), "127.0.0.2:53".parse().unwrap()];
+        let resolver = Resolver::new(&nameservers, RetryPolicy::new(100, 0));
+        resolver.nameservers[0].failed();
+        resolver.nameservers[0].failed();
+        assert_eq!(vec![0, 1, 0, 1], picks(&resolver));
+
+        resolver.nameservers[0].failed();
+        assert_eq!(vec![1, 1, 1, 1], picks(&resolver));
+
+        let cooled_down = std::time::Instant::now().checked_sub(std::time::Duration::from_millis(1));
+        *resolver.nameservers[0].benched_until.lock().unwrap() = cooled_down;
+        assert_eq!(vec![0, 1, 0, 1], picks(&resolver));
+        resolver.nameservers[0].failed();
+        assert_eq!(vec![1, 1, 1, 1], picks(&resolver));
+
+        resolver.nameservers[0].succeeded();
+        assert_eq!(vec![0, 1, 0, 1], picks(&resolver));
+
+        for _ in 0..3 {
+            resolver.nameservers[0].failed();
+            resolver.nameservers[1].failed();
+        }
+        assert_eq!(vec![0, 1, 0, 1], picks(&resolver));
+    }
+
+    test parse_nameservers() {
+        assert_eq!("1.1.1.1:53".parse::<SocketAddr>().unwrap(), parse_nameserver("1.1.1.1").unwrap());
+        assert_eq!("127.0.0.1:5353".parse::<SocketAddr>().unwrap(), parse_nameserver("127.0.0.1:5353").unwrap());
+        assert_eq!("[::1]:53".parse::<SocketAddr>().unwrap(), parse_nameserver("::1").unwrap());
+        assert!(parse_nameserver("localhost").is_err());
+        assert_eq!(
+            vec!["8.8.8.8:53".parse::<SocketAddr>().unwrap(), "[fe80::1]:53".parse().unwrap()],
+            parse_resolv_conf("# comment\nnameserver 8.8.8.8\nsearch local\nnameserver fe80::1%eth0\n")
+        );
+    }
+}
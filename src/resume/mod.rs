@@ -0,0 +1,190 @@
+use clap::{App, ArgMatches};
+use futures::Future;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::Duration;
+use std::{fs::File, io::Write, path::Path};
+
+mod spec;
+
+const DRAIN_POLL_MS: u64 = 100;
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+pub fn handle_interrupts() {
+    let result = ctrlc::set_handler(|| {
+        if INTERRUPTED.swap(true, Ordering::SeqCst) {
+            crate::wordlist::remove_stdin_spool();
+            std::process::exit(130);
+        }
+
+        eprintln!("\n[!] Interrupted, waiting for the in-flight requests (press Ctrl-C again to abort)");
+    });
+
+    if let Err(e) = result {
+        warn!("Unable to handle interrupts: {}", e);
+    }
+}
+
+pub fn is_interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ResumeState {
+    #[serde(skip)]
+    pub path: String,
+    pub args: Vec<String>,
+    pub offset: usize,
+    #[serde(default)]
+    pub recursion_offset: usize,
+    #[serde(default)]
+    pub directories: Vec<(String, usize)>,
+    #[serde(default)]
+    pub pending: serde_json::Value,
+    #[serde(default)]
+    pub results: serde_json::Value,
+}
+
+impl ResumeState {
+    pub fn new(output: &str, args: Vec<String>) -> Self {
+        let path = if output.is_empty() {
+            "rustbuster.resume".to_owned()
+        } else {
+            format!("{}.resume", output)
+        };
+
+        ResumeState {
+            path,
+            args,
+            ..ResumeState::default()
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let file = File::open(Path::new(path)).map_err(|e| e.to_string())?;
+        let mut state: ResumeState = serde_json::from_reader(file).map_err(|e| e.to_string())?;
+        state.path = path.to_owned();
+
+        Ok(state)
+    }
+
+    pub fn matches<'a, 'b>(&self, app: App<'a, 'b>, mode: Option<&str>) -> Result<ArgMatches<'a>, String> {
+        let matches = app.get_matches_from_safe(&self.args).map_err(|e| e.message)?;
+
+        match (matches.subcommand_name(), mode) {
+            (None, _) => Err("the saved arguments do not contain a scan mode".to_owned()),
+            (Some(saved), Some(mode)) if saved != mode => {
+                Err(format!("a {} scan cannot be resumed in {} mode", saved, mode))
+            }
+            _ => Ok(matches),
+        }
+    }
+
+    pub fn save(&self) {
+        let json_string = serde_json::to_string(&self).unwrap();
+
+        let mut file = match File::create(Path::new(&self.path)) {
+            Ok(f) => f,
+            Err(e) => {
+                error!("Error while creating file: {}\n{}", self.path, e);
+                return;
+            }
+        };
+
+        match file.write_all(json_string.as_bytes()) {
+            Ok(_) => debug!("Resume state saved to: {}", self.path),
+            Err(e) => error!("Error while writing resume state to file: {}\n{}", self.path, e),
+        };
+    }
+
+    pub fn results<T: for<'de> Deserialize<'de>>(&self) -> Vec<T> {
+        if self.results.is_null() {
+            return Vec::new();
+        }
+
+        serde_json::from_value(self.results.clone()).expect("Invalid results in resume file")
+    }
+
+    pub fn pending<T: for<'de> Deserialize<'de>>(&self) -> Vec<T> {
+        if self.pending.is_null() {
+            return Vec::new();
+        }
+
+        serde_json::from_value(self.pending.clone()).expect("Invalid pending requests in resume file")
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Cursor {
+    position: Arc<AtomicUsize>,
+}
+
+impl Cursor {
+    pub fn new(position: usize) -> Self {
+        Cursor {
+            position: Arc::new(AtomicUsize::new(position)),
+        }
+    }
+
+    pub fn position(&self) -> usize {
+        self.position.load(Ordering::SeqCst)
+    }
+
+    pub fn track<I: Iterator>(&self, mut iterator: I) -> impl Iterator<Item = I::Item> {
+        let position = self.position.clone();
+
+        std::iter::from_fn(move || {
+            if is_interrupted() {
+                return None;
+            }
+
+            let item = iterator.next()?;
+            position.fetch_add(1, Ordering::SeqCst);
+            Some(item)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct InFlight {
+    count: Arc<AtomicUsize>,
+}
+
+impl InFlight {
+    pub fn track<F>(&self, request_future: F) -> impl Future<Item = (), Error = ()>
+    where
+        F: Future<Item = (), Error = ()>,
+    {
+        let count = self.count.clone();
+        count.fetch_add(1, Ordering::SeqCst);
+
+        request_future.then(move |res| {
+            count.fetch_sub(1, Ordering::SeqCst);
+            res
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count.load(Ordering::SeqCst) == 0
+    }
+}
+
+pub fn receive<T>(rx: &Receiver<T>, in_flight: &InFlight) -> Option<T> {
+    loop {
+        match rx.recv_timeout(Duration::from_millis(DRAIN_POLL_MS)) {
+            Ok(msg) => return Some(msg),
+            Err(RecvTimeoutError::Timeout) => {
+                if is_interrupted() && in_flight.is_empty() {
+                    return rx.try_recv().ok();
+                }
+            }
+            Err(e) => {
+                error!("{:?}", e);
+                return None;
+            }
+        }
+    }
+}
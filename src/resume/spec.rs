@@ -0,0 +1,82 @@
+use galvanic_test::test_suite;
+
+test_suite! {
+    name resume;
+
+    use crate::resume::{Cursor, ResumeState};
+    use clap::{App, Arg, SubCommand};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Finding {
+        url: String,
+    }
+
+    fn app() -> App<'static, 'static> {
+        App::new("rustbuster")
+            .arg(Arg::with_name("resume").long("resume").takes_value(true))
+            .subcommand(SubCommand::with_name("dir").arg(Arg::with_name("url").short("u").takes_value(true).required(true)))
+            .subcommand(SubCommand::with_name("dns"))
+    }
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| (*arg).to_owned()).collect()
+    }
+
+    test resume_paths() {
+        assert_eq!("rustbuster.resume", ResumeState::new("", Vec::new()).path);
+        assert_eq!("results.json.resume", ResumeState::new("results.json", Vec::new()).path);
+    }
+
+    test save_and_load_state() {
+        let path = std::env::temp_dir().join(format!("rustbuster-{}-state.resume", std::process::id()));
+        let findings = vec![Finding { url: "http://localhost/a".to_owned() }];
+        let state = ResumeState {
+            path: path.to_str().unwrap().to_owned(),
+            args: args(&["rustbuster", "dir", "-u", "http://localhost/"]),
+            offset: 42,
+            recursion_offset: 7,
+            directories: vec![("http://localhost/a/".to_owned(), 1)],
+            pending: serde_json::to_value(&findings).unwrap(),
+            results: serde_json::to_value(&findings).unwrap(),
+        };
+        state.save();
+        let loaded = ResumeState::load(&state.path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(state, loaded);
+        assert_eq!(findings, loaded.results::<Finding>());
+        assert_eq!(findings, loaded.pending::<Finding>());
+        assert!(ResumeState::load("/nonexistent/rustbuster.resume").is_err());
+    }
+
+    test empty_state_results() {
+        let state = ResumeState::new("", Vec::new());
+        assert!(state.results::<Finding>().is_empty());
+        assert!(state.pending::<Finding>().is_empty());
+    }
+
+    test match_saved_args() {
+        let state = ResumeState::new("", args(&["rustbuster", "dir", "-u", "http://localhost/"]));
+        let matches = state.matches(app(), None).unwrap();
+        assert_eq!(Some("http://localhost/"), matches.subcommand_matches("dir").unwrap().value_of("url"));
+        assert!(state.matches(app(), Some("dir")).is_ok());
+        assert!(state.matches(app(), Some("dns")).is_err());
+
+        assert!(ResumeState::new("", args(&["rustbuster", "dir"])).matches(app(), None).is_err());
+        assert!(ResumeState::new("", args(&["rustbuster", "--bogus"])).matches(app(), None).is_err());
+        assert!(ResumeState::new("", args(&["rustbuster"])).matches(app(), None).is_err());
+    }
+
+    test skip_to_cursor() {
+        let cursor = Cursor::default();
+        let words = cursor.track(vec!["a", "b", "c", "d"].into_iter()).skip(2).collect::<Vec<_>>();
+        assert_eq!(vec!["c", "d"], words);
+        assert_eq!(4, cursor.position());
+
+        let cursor = Cursor::new(2);
+        let words = cursor.track(vec!["a", "b", "c", "d"].into_iter().skip(2)).collect::<Vec<_>>();
+        assert_eq!(vec!["c", "d"], words);
+        assert_eq!(4, cursor.position());
+    }
+}
@@ -0,0 +1,121 @@
+use futures::{
+    future::{self, Either, Loop},
+    Future, Stream,
+};
+use hyper::{http::response::Parts, Body, Chunk, Client, Request};
+use hyper_tls::HttpsConnector;
+use std::fmt;
+use std::time::{Duration, Instant};
+use tokio_timer::{Delay, Timeout};
+
+use crate::proxy::ProxyConnector;
+
+mod spec;
+
+const RETRY_BACKOFF_MS: u64 = 250;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    pub timeout: Option<Duration>,
+    pub retries: usize,
+}
+
+impl RetryPolicy {
+    pub fn new(timeout: u64, retries: usize) -> Self {
+        RetryPolicy {
+            timeout: if timeout == 0 {
+                None
+            } else {
+                Some(Duration::from_millis(timeout))
+            },
+            retries,
+        }
+    }
+
+    pub fn backoff(&self, attempt: usize) -> Duration {
+        Duration::from_millis(RETRY_BACKOFF_MS << attempt.min(16))
+    }
+}
+
+#[derive(Debug)]
+pub enum RequestError {
+    Timeout,
+    Throttled(Option<Duration>),
+    Http(hyper::Error),
+    Timer(tokio_timer::Error),
+}
+
+impl RequestError {
+    pub fn is_transient(&self) -> bool {
+        match self {
+            RequestError::Timeout => true,
+            RequestError::Throttled(_) => false,
+            RequestError::Http(e) => !e.is_parse() && !e.is_user(),
+            RequestError::Timer(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for RequestError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RequestError::Timeout => write!(f, "request timed out"),
+            RequestError::Throttled(_) => write!(f, "request throttled by the target"),
+            RequestError::Http(e) => write!(f, "{}", e),
+            RequestError::Timer(e) => write!(f, "timer error: {}", e),
+        }
+    }
+}
+
+impl From<tokio_timer::timeout::Error<hyper::Error>> for RequestError {
+    fn from(e: tokio_timer::timeout::Error<hyper::Error>) -> Self {
+        if e.is_elapsed() {
+            RequestError::Timeout
+        } else if e.is_timer() {
+            RequestError::Timer(e.into_timer().unwrap())
+        } else {
+            RequestError::Http(e.into_inner().unwrap())
+        }
+    }
+}
+
+/// Sends the request, retrying transient failures, and yields the response
+/// along with the time the successful attempt took.
+pub fn send_request<F>(
+    client: Client<HttpsConnector<ProxyConnector>>,
+    policy: RetryPolicy,
+    build_request: F,
+) -> impl Future<Item = (Parts, Chunk, Duration), Error = RequestError>
+where
+    F: Fn() -> Request<Body> + Send + 'static,
+{
+    future::loop_fn(0, move |attempt| {
+        let start_time = Instant::now();
+        let response = client.request(build_request()).and_then(move |res| {
+            let (parts, body) = res.into_parts();
+            body.concat2()
+                .map(move |body| (parts, body, start_time.elapsed()))
+        });
+        let response = match policy.timeout {
+            Some(timeout) => Either::A(Timeout::new(response, timeout).map_err(RequestError::from)),
+            None => Either::B(response.map_err(RequestError::Http)),
+        };
+
+        response.then(move |result| match result {
+            Ok(v) => Either::A(future::ok(Loop::Break(v))),
+            Err(e) => {
+                if attempt >= policy.retries || !e.is_transient() {
+                    return Either::A(future::err(e));
+                }
+
+                let backoff = policy.backoff(attempt);
+                debug!("retrying in {:?} after error: {}", backoff, e);
+                Either::B(
+                    Delay::new(Instant::now() + backoff)
+                        .map_err(RequestError::Timer)
+                        .map(move |_| Loop::Continue(attempt + 1)),
+                )
+            }
+        })
+    })
+}
@@ -0,0 +1,102 @@
+use galvanic_test::test_suite;
+
+test_suite! {
+    name retry;
+
+    use crate::proxy::build_client;
+    use crate::retry::{send_request, RequestError, RetryPolicy};
+    use futures::Future;
+    use hyper::{Body, Request};
+    use std::io::{Read, Write};
+    use std::net::{SocketAddr, TcpListener};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    const OK: &[u8] = b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok";
+
+    fn serve(responses: Vec<Option<&'static [u8]>>) -> (SocketAddr, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let connections = Arc::new(AtomicUsize::new(0));
+        let counter = connections.clone();
+
+        std::thread::spawn(move || {
+            let mut hanging = Vec::new();
+            for (stream, response) in listener.incoming().zip(responses) {
+                let mut stream = stream.unwrap();
+                counter.fetch_add(1, Ordering::SeqCst);
+                let mut request = [0u8; 1024];
+                let _ = stream.read(&mut request).unwrap();
+                match response {
+                    Some(response) => stream.write_all(response).unwrap(),
+                    None => hanging.push(stream),
+                }
+            }
+        });
+
+        (addr, connections)
+    }
+
+    fn fetch(addr: SocketAddr, policy: RetryPolicy) -> Result<(Vec<u8>, Duration), RequestError> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let client = build_client(1, false, "");
+        let request = send_request(client, policy, move || {
+            Request::get(format!("http://{}/", addr)).body(Body::empty()).unwrap()
+        })
+        .then(move |result| {
+            tx.send(result.map(|(_, body, elapsed)| (body.to_vec(), elapsed))).unwrap();
+            Ok(())
+        });
+        hyper::rt::run(request);
+        rx.recv().unwrap()
+    }
+
+    test backoff_doubles() {
+        let policy = RetryPolicy::new(0, 3);
+        assert_eq!(None, policy.timeout);
+        assert_eq!(Some(Duration::from_millis(500)), RetryPolicy::new(500, 3).timeout);
+        assert_eq!(Duration::from_millis(250), policy.backoff(0));
+        assert_eq!(Duration::from_millis(500), policy.backoff(1));
+        assert_eq!(Duration::from_millis(1000), policy.backoff(2));
+        assert_eq!(policy.backoff(16), policy.backoff(40));
+    }
+
+    test transient_errors() {
+        assert!(RequestError::Timeout.is_transient());
+        assert!(!RequestError::Throttled(None).is_transient());
+        assert!(!RequestError::Throttled(Some(Duration::from_secs(1))).is_transient());
+    }
+
+    test retry_dropped_connections() {
+        let (addr, connections) = serve(vec![Some(b""), Some(OK)]);
+        assert_eq!(b"ok".to_vec(), fetch(addr, RetryPolicy::new(1000, 2)).unwrap().0);
+        assert_eq!(2, connections.load(Ordering::SeqCst));
+    }
+
+    test time_successful_attempt() {
+        let (addr, connections) = serve(vec![None, Some(OK)]);
+        let (body, elapsed) = fetch(addr, RetryPolicy::new(300, 1)).unwrap();
+        assert_eq!(b"ok".to_vec(), body);
+        assert_eq!(2, connections.load(Ordering::SeqCst));
+        assert!(elapsed < Duration::from_millis(250));
+    }
+
+    test retry_timeouts() {
+        let (addr, connections) = serve(vec![None, None]);
+        match fetch(addr, RetryPolicy::new(100, 1)) {
+            Err(RequestError::Timeout) => (),
+            other => panic!("expected a timeout, got {:?}", other.map(|_| ())),
+        }
+        assert_eq!(2, connections.load(Ordering::SeqCst));
+    }
+
+    test skip_retrying_parse_errors() {
+        let (addr, connections) = serve(vec![Some(b"garbage\r\n\r\n"), Some(OK)]);
+        match fetch(addr, RetryPolicy::new(1000, 2)) {
+            Err(e @ RequestError::Http(_)) => assert!(!e.is_transient()),
+            other => panic!("expected a parse error, got {:?}", other.map(|_| ())),
+        }
+        assert_eq!(1, connections.load(Ordering::SeqCst));
+    }
+}
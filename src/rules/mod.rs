@@ -0,0 +1,246 @@
+use std::fs;
+
+mod spec;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Function {
+    Noop,
+    Lower,
+    Upper,
+    Capitalize,
+    InvertCapitalize,
+    ToggleCase,
+    ToggleAt(usize),
+    Reverse,
+    Duplicate,
+    DuplicateTimes(usize),
+    Reflect,
+    RotateLeft,
+    RotateRight,
+    Append(char),
+    Prepend(char),
+    DeleteFirst,
+    DeleteLast,
+    DeleteAt(usize),
+    Truncate(usize),
+    Extract(usize, usize),
+    Insert(usize, char),
+    Overwrite(usize, char),
+    Replace(char, char),
+    Purge(char),
+    DuplicateFirst(usize),
+    DuplicateLast(usize),
+    DuplicateAll,
+    RejectLonger(usize),
+    RejectShorter(usize),
+    RejectContains(char),
+    RejectMissing(char),
+}
+
+pub type Rule = Vec<Function>;
+
+pub fn load(path: &str) -> Result<Vec<Rule>, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+
+    content
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| parse_rule(line).map_err(|e| format!("{}: invalid rule {}: {}", path, line, e)))
+        .collect()
+}
+
+pub fn parse_rule(line: &str) -> Result<Rule, String> {
+    let mut chars = line.chars();
+    let mut rule = Vec::new();
+
+    while let Some(name) = chars.next() {
+        let mut arg = || chars.next().ok_or_else(|| format!("missing argument for {}", name));
+        let function = match name {
+            ' ' | '\t' => continue,
+            ':' => Function::Noop,
+            'l' => Function::Lower,
+            'u' => Function::Upper,
+            'c' => Function::Capitalize,
+            'C' => Function::InvertCapitalize,
+            't' => Function::ToggleCase,
+            'T' => Function::ToggleAt(position(arg()?)?),
+            'r' => Function::Reverse,
+            'd' => Function::Duplicate,
+            'p' => Function::DuplicateTimes(position(arg()?)?),
+            'f' => Function::Reflect,
+            '{' => Function::RotateLeft,
+            '}' => Function::RotateRight,
+            '$' => Function::Append(arg()?),
+            '^' => Function::Prepend(arg()?),
+            '[' => Function::DeleteFirst,
+            ']' => Function::DeleteLast,
+            'D' => Function::DeleteAt(position(arg()?)?),
+            '\'' => Function::Truncate(position(arg()?)?),
+            'x' => Function::Extract(position(arg()?)?, position(arg()?)?),
+            'i' => Function::Insert(position(arg()?)?, arg()?),
+            'o' => Function::Overwrite(position(arg()?)?, arg()?),
+            's' => Function::Replace(arg()?, arg()?),
+            '@' => Function::Purge(arg()?),
+            'z' => Function::DuplicateFirst(position(arg()?)?),
+            'Z' => Function::DuplicateLast(position(arg()?)?),
+            'q' => Function::DuplicateAll,
+            '<' => Function::RejectLonger(position(arg()?)?),
+            '>' => Function::RejectShorter(position(arg()?)?),
+            '!' => Function::RejectContains(arg()?),
+            '/' => Function::RejectMissing(arg()?),
+            _ => return Err(format!("unsupported function {}", name)),
+        };
+
+        rule.push(function);
+    }
+
+    Ok(rule)
+}
+
+fn position(c: char) -> Result<usize, String> {
+    match c {
+        '0'..='9' => Ok(c as usize - '0' as usize),
+        'A'..='Z' => Ok(c as usize - 'A' as usize + 10),
+        _ => Err(format!("invalid position {}", c)),
+    }
+}
+
+pub fn apply(rule: &[Function], word: &str) -> Option<String> {
+    let mut word = word.chars().collect::<Vec<char>>();
+
+    for function in rule {
+        let length = word.len();
+        match function {
+            Function::Noop => (),
+            Function::Lower => word = lower(&word),
+            Function::Upper => word = upper(&word),
+            Function::Capitalize => {
+                word = lower(&word);
+                if let Some(first) = word.first() {
+                    word.splice(0..1, first.to_uppercase());
+                }
+            }
+            Function::InvertCapitalize => {
+                word = upper(&word);
+                if let Some(first) = word.first() {
+                    word.splice(0..1, first.to_lowercase());
+                }
+            }
+            Function::ToggleCase => word = word.iter().flat_map(|c| toggle(*c)).collect(),
+            Function::ToggleAt(n) => {
+                if *n < length {
+                    word.splice(*n..*n + 1, toggle(word[*n]));
+                }
+            }
+            Function::Reverse => word.reverse(),
+            Function::Duplicate => word.extend(word.clone()),
+            Function::DuplicateTimes(n) => {
+                let original = word.clone();
+                for _ in 0..*n {
+                    word.extend(original.iter());
+                }
+            }
+            Function::Reflect => {
+                let reversed = word.iter().rev().cloned().collect::<Vec<_>>();
+                word.extend(reversed);
+            }
+            Function::RotateLeft => {
+                if length > 0 {
+                    word.rotate_left(1);
+                }
+            }
+            Function::RotateRight => {
+                if length > 0 {
+                    word.rotate_right(1);
+                }
+            }
+            Function::Append(c) => word.push(*c),
+            Function::Prepend(c) => word.insert(0, *c),
+            Function::DeleteFirst => {
+                if length > 0 {
+                    word.remove(0);
+                }
+            }
+            Function::DeleteLast => {
+                word.pop();
+            }
+            Function::DeleteAt(n) => {
+                if *n < length {
+                    word.remove(*n);
+                }
+            }
+            Function::Truncate(n) => word.truncate(*n),
+            Function::Extract(n, m) => {
+                if *n < length {
+                    word = word[*n..length.min(n + m)].to_vec();
+                }
+            }
+            Function::Insert(n, c) => {
+                if *n <= length {
+                    word.insert(*n, *c);
+                }
+            }
+            Function::Overwrite(n, c) => {
+                if *n < length {
+                    word[*n] = *c;
+                }
+            }
+            Function::Replace(x, y) => {
+                for c in word.iter_mut().filter(|c| **c == *x) {
+                    *c = *y;
+                }
+            }
+            Function::Purge(x) => word.retain(|c| c != x),
+            Function::DuplicateFirst(n) => {
+                if let Some(first) = word.first().cloned() {
+                    word.splice(0..0, std::iter::repeat_n(first, *n));
+                }
+            }
+            Function::DuplicateLast(n) => {
+                if let Some(last) = word.last().cloned() {
+                    word.extend(std::iter::repeat_n(last, *n));
+                }
+            }
+            Function::DuplicateAll => word = word.iter().flat_map(|c| vec![*c, *c]).collect(),
+            Function::RejectLonger(n) => {
+                if length > *n {
+                    return None;
+                }
+            }
+            Function::RejectShorter(n) => {
+                if length < *n {
+                    return None;
+                }
+            }
+            Function::RejectContains(x) => {
+                if word.contains(x) {
+                    return None;
+                }
+            }
+            Function::RejectMissing(x) => {
+                if !word.contains(x) {
+                    return None;
+                }
+            }
+        }
+    }
+
+    Some(word.into_iter().collect())
+}
+
+fn lower(word: &[char]) -> Vec<char> {
+    word.iter().flat_map(|c| c.to_lowercase()).collect()
+}
+
+fn upper(word: &[char]) -> Vec<char> {
+    word.iter().flat_map(|c| c.to_uppercase()).collect()
+}
+
+fn toggle(c: char) -> Vec<char> {
+    if c.is_lowercase() {
+        c.to_uppercase().collect()
+    } else {
+        c.to_lowercase().collect()
+    }
+}
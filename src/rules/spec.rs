@@ -0,0 +1,32 @@
+use galvanic_test::test_suite;
+
+test_suite! {
+    name rules;
+
+    use crate::rules::{apply, parse_rule};
+
+    fn mutate(rule: &str, word: &str) -> Option<String> {
+        apply(&parse_rule(rule).unwrap(), word)
+    }
+
+    test apply_rules() {
+        assert_eq!(Some("Password".to_owned()), mutate("c", "pASSWORD"));
+        assert_eq!(Some("drowssap".to_owned()), mutate("r", "password"));
+        assert_eq!(Some("p@ssw0rd".to_owned()), mutate("sa@ so0", "password"));
+        assert_eq!(Some("pass".to_owned()), mutate("'4", "password"));
+        assert_eq!(Some("ssw".to_owned()), mutate("x23", "password"));
+        assert_eq!(Some("pa-ssword".to_owned()), mutate("i2-", "password"));
+        assert_eq!(Some("ppassword".to_owned()), mutate("z1", "password"));
+        assert_eq!(Some("dpasswor".to_owned()), mutate("}", "password"));
+        assert_eq!(Some("password".to_owned()), mutate(">8", "password"));
+        assert_eq!(None, mutate(">9", "password"));
+        assert_eq!(Some("password".to_owned()), mutate("<8", "password"));
+        assert_eq!(None, mutate("<7", "password"));
+        assert_eq!(None, mutate("!s", "password"));
+    }
+
+    test parse_invalid_rules() {
+        assert!(parse_rule("$").is_err());
+        assert!(parse_rule("K").is_err());
+    }
+}
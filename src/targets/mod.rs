@@ -0,0 +1,56 @@
+use std::collections::VecDeque;
+use std::path::Path;
+
+use crate::wordlist::{self, WordlistOptions, STDIN};
+
+mod spec;
+
+pub fn load(targets_path: &str) -> Result<Vec<String>, String> {
+    if targets_path != STDIN && !Path::new(targets_path).is_file() {
+        return Err(format!("{} is not a file", targets_path));
+    }
+
+    let options = WordlistOptions {
+        dedup: true,
+        ..WordlistOptions::default()
+    };
+
+    Ok(wordlist::words(targets_path, &options).collect())
+}
+
+pub fn interleave<T: 'static>(
+    iterators: Vec<Box<dyn Iterator<Item = T> + Send>>,
+) -> impl Iterator<Item = T> + Send {
+    let mut iterators = VecDeque::from(iterators);
+
+    std::iter::from_fn(move || {
+        while let Some(mut iterator) = iterators.pop_front() {
+            if let Some(item) = iterator.next() {
+                iterators.push_back(iterator);
+                return Some(item);
+            }
+        }
+
+        None
+    })
+}
+
+pub fn host(target: &str) -> String {
+    match target.parse::<hyper::Uri>() {
+        Ok(uri) => match uri.authority_part() {
+            Some(authority) => authority.as_str().to_owned(),
+            None => target.to_owned(),
+        },
+        Err(_) => target.to_owned(),
+    }
+}
+
+pub fn target_of(targets: &[String], url: &str) -> String {
+    targets
+        .iter()
+        .filter(|target| url.starts_with(target.trim_end_matches('/')))
+        .max_by_key(|target| target.len())
+        .or_else(|| targets.first())
+        .cloned()
+        .unwrap_or_default()
+}
@@ -0,0 +1,32 @@
+use galvanic_test::test_suite;
+
+test_suite! {
+    name targets;
+
+    use crate::targets::{host, interleave, target_of};
+
+    test interleave_iterators() {
+        let iterators: Vec<Box<dyn Iterator<Item = u32> + Send>> = vec![
+            Box::new(vec![1, 2, 3].into_iter()),
+            Box::new(vec![10].into_iter()),
+            Box::new(vec![20, 21].into_iter()),
+        ];
+        assert_eq!(vec![1, 10, 20, 2, 21, 3], interleave(iterators).collect::<Vec<_>>());
+        assert_eq!(0, interleave::<u32>(vec![]).count());
+    }
+
+    test target_hosts() {
+        assert_eq!("a:8080", host("http://a:8080/FUZZ"));
+        assert_eq!("example.com", host("https://example.com/"));
+        assert_eq!("example.com", host("example.com"));
+    }
+
+    test targets_of_urls() {
+        let targets = vec!["http://a/".to_owned(), "http://a/app/".to_owned(), "http://b/".to_owned()];
+        assert_eq!("http://b/", target_of(&targets, "http://b/admin/"));
+        assert_eq!("http://a/app/", target_of(&targets, "http://a/app/admin/"));
+        assert_eq!("http://a/", target_of(&targets, "http://a/admin/"));
+        assert_eq!("http://a/", target_of(&targets, "http://c/admin/"));
+        assert_eq!("", target_of(&[], "http://c/admin/"));
+    }
+}
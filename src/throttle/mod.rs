@@ -0,0 +1,312 @@
+use chrono::{DateTime, Utc};
+use futures::{
+    future::{self, Either, Loop},
+    Future,
+};
+use rand::Rng;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio_timer::Delay;
+
+use crate::matcher::NumericRange;
+
+mod spec;
+
+pub const MAX_REQUEUES: usize = 3;
+const THROTTLED_BURST: usize = 3;
+const RAMP_UP_RESPONSES: usize = 20;
+const COOLDOWN_MS: u64 = 1000;
+const MAX_PAUSE_SECS: u64 = 300;
+const PERMIT_POLL_MS: u64 = 10;
+
+#[derive(Debug, Clone)]
+pub struct Throttle {
+    interval: Option<Duration>,
+    delay: Option<NumericRange>,
+    next_slot: Arc<Mutex<Option<Instant>>>,
+    adaptive: Arc<Mutex<Adaptive>>,
+}
+
+#[derive(Debug, Default)]
+struct Adaptive {
+    limit: Option<usize>,
+    in_flight: usize,
+    peak: usize,
+    paused_until: Option<Instant>,
+    throttled: usize,
+    healthy: usize,
+    last_adjustment: Option<Instant>,
+}
+
+struct Permit {
+    adaptive: Arc<Mutex<Adaptive>>,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        let mut state = self.adaptive.lock().unwrap();
+        state.in_flight = state.in_flight.saturating_sub(1);
+    }
+}
+
+impl Throttle {
+    pub fn new(rate: u64, delay: Option<NumericRange>) -> Self {
+        let interval = 1_000_000_000u64
+            .checked_div(rate)
+            .map(Duration::from_nanos);
+
+        Throttle {
+            interval,
+            delay,
+            next_slot: Arc::new(Mutex::new(None)),
+            adaptive: Arc::new(Mutex::new(Adaptive::default())),
+        }
+    }
+
+    pub fn run<F>(&self, request_future: F) -> impl Future<Item = (), Error = ()>
+    where
+        F: Future<Item = (), Error = ()>,
+    {
+        let adaptive = self.adaptive.clone();
+
+        self.wait()
+            .and_then(move |_| Throttle::acquire(adaptive))
+            .and_then(move |permit| {
+                request_future.then(move |res| {
+                    drop(permit);
+                    res
+                })
+            })
+    }
+
+    pub fn observe(&self, throttled: bool, retry_after: Option<Duration>) {
+        let mut state = self.adaptive.lock().unwrap();
+        let now = Instant::now();
+
+        if !throttled {
+            state.healthy += 1;
+            if let Some(limit) = state.limit {
+                if state.healthy >= RAMP_UP_RESPONSES {
+                    state.healthy = 0;
+                    state.last_adjustment = Some(now);
+                    if limit * 2 >= state.peak {
+                        state.limit = None;
+                        warn!("Responses look normal again, restoring full concurrency");
+                    } else {
+                        state.limit = Some(limit * 2);
+                        warn!("Responses look normal again, raising concurrency to {}", limit * 2);
+                    }
+                }
+            }
+
+            return;
+        }
+
+        state.healthy = 0;
+        state.throttled += 1;
+
+        if let Some(retry_after) = retry_after {
+            let retry_after = retry_after.min(Duration::from_secs(MAX_PAUSE_SECS));
+            let until = now + retry_after;
+            let paused = matches!(state.paused_until, Some(paused_until) if paused_until > now);
+            if !paused {
+                state.paused_until = Some(until);
+                warn!("Target asked to retry after {}s, pausing requests", retry_after.as_secs());
+            }
+        }
+
+        let cooled_down = match state.last_adjustment {
+            Some(last) => now.duration_since(last) >= Duration::from_millis(COOLDOWN_MS),
+            None => true,
+        };
+        if state.throttled < THROTTLED_BURST || !cooled_down {
+            return;
+        }
+
+        state.throttled = 0;
+        state.last_adjustment = Some(now);
+        match state.limit {
+            Some(1) => {
+                let until = now + Duration::from_millis(COOLDOWN_MS);
+                let paused = matches!(state.paused_until, Some(paused_until) if paused_until >= until);
+                if !paused {
+                    state.paused_until = Some(until);
+                }
+                warn!("Target is still throttling requests, pausing for {}ms", COOLDOWN_MS);
+            }
+            limit => {
+                let limit = (limit.unwrap_or(state.peak) / 2).max(1);
+                state.limit = Some(limit);
+                warn!("Target is throttling requests, lowering concurrency to {}", limit);
+            }
+        }
+    }
+
+    fn acquire(adaptive: Arc<Mutex<Adaptive>>) -> impl Future<Item = Permit, Error = ()> {
+        future::loop_fn(adaptive, |adaptive| {
+            let now = Instant::now();
+            let wake_up = {
+                let mut state = adaptive.lock().unwrap();
+                match state.paused_until {
+                    Some(paused_until) if paused_until > now => Some(paused_until),
+                    _ => {
+                        state.paused_until = None;
+                        let available = match state.limit {
+                            Some(limit) => state.in_flight < limit,
+                            None => true,
+                        };
+                        if available {
+                            state.in_flight += 1;
+                            state.peak = state.peak.max(state.in_flight);
+                            None
+                        } else {
+                            Some(now + Duration::from_millis(PERMIT_POLL_MS))
+                        }
+                    }
+                }
+            };
+
+            match wake_up {
+                None => Either::A(future::ok(Loop::Break(Permit { adaptive }))),
+                Some(deadline) => Either::B(
+                    Delay::new(deadline)
+                        .map_err(|e| error!("Timer error: {}", e))
+                        .map(move |_| Loop::Continue(adaptive)),
+                ),
+            }
+        })
+    }
+
+    pub fn wait(&self) -> impl Future<Item = (), Error = ()> {
+        let now = Instant::now();
+        let mut deadline = now;
+
+        if let Some(delay) = &self.delay {
+            let millis = if delay.min == delay.max {
+                delay.min
+            } else {
+                rand::thread_rng().gen_range(delay.min, delay.max.saturating_add(1))
+            };
+            deadline += Duration::from_millis(millis);
+        }
+
+        if let Some(interval) = self.interval {
+            let mut next_slot = self.next_slot.lock().unwrap();
+            let slot = match *next_slot {
+                Some(slot) if slot > deadline => slot,
+                _ => deadline,
+            };
+            *next_slot = Some(slot + interval);
+            deadline = slot;
+        }
+
+        if deadline <= now {
+            Either::A(future::ok(()))
+        } else {
+            Either::B(Delay::new(deadline).map_err(|e| error!("Timer error: {}", e)))
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HostLimiter {
+    limit: usize,
+    in_flight: Arc<Mutex<HashMap<String, usize>>>,
+}
+
+struct HostPermit {
+    host: String,
+    in_flight: Arc<Mutex<HashMap<String, usize>>>,
+}
+
+impl Drop for HostPermit {
+    fn drop(&mut self) {
+        let mut in_flight = self.in_flight.lock().unwrap();
+        if let Some(count) = in_flight.get_mut(&self.host) {
+            *count = count.saturating_sub(1);
+        }
+    }
+}
+
+impl HostLimiter {
+    pub fn new(limit: usize) -> Self {
+        HostLimiter {
+            limit,
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn run<F>(&self, host: &str, request_future: F) -> impl Future<Item = (), Error = ()>
+    where
+        F: Future<Item = (), Error = ()>,
+    {
+        if self.limit == 0 {
+            return Either::A(request_future);
+        }
+
+        Either::B(
+            HostLimiter::acquire(self.in_flight.clone(), self.limit, host.to_owned()).and_then(
+                move |permit| {
+                    request_future.then(move |res| {
+                        drop(permit);
+                        res
+                    })
+                },
+            ),
+        )
+    }
+
+    fn acquire(
+        in_flight: Arc<Mutex<HashMap<String, usize>>>,
+        limit: usize,
+        host: String,
+    ) -> impl Future<Item = HostPermit, Error = ()> {
+        future::loop_fn((in_flight, host), move |(in_flight, host)| {
+            let acquired = {
+                let mut state = in_flight.lock().unwrap();
+                let count = state.entry(host.clone()).or_insert(0);
+                if *count < limit {
+                    *count += 1;
+                    true
+                } else {
+                    false
+                }
+            };
+
+            if acquired {
+                Either::A(future::ok(Loop::Break(HostPermit { host, in_flight })))
+            } else {
+                Either::B(
+                    Delay::new(Instant::now() + Duration::from_millis(PERMIT_POLL_MS))
+                        .map_err(|e| error!("Timer error: {}", e))
+                        .map(move |_| Loop::Continue((in_flight, host))),
+                )
+            }
+        })
+    }
+}
+
+pub fn is_throttled(status: &str) -> bool {
+    status.starts_with("429") || status.starts_with("503")
+}
+
+pub fn parse_retry_after(headers: &str) -> Option<Duration> {
+    let value = headers.lines().find_map(|line| {
+        let mut parts = line.splitn(2, ':');
+        match (parts.next(), parts.next()) {
+            (Some(name), Some(value)) if name.trim().eq_ignore_ascii_case("retry-after") => {
+                Some(value.trim())
+            }
+            _ => None,
+        }
+    })?;
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    DateTime::parse_from_rfc2822(value)
+        .ok()
+        .and_then(|date| (date.with_timezone(&Utc) - Utc::now()).to_std().ok())
+}
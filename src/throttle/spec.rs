@@ -0,0 +1,152 @@
+use galvanic_test::test_suite;
+
+test_suite! {
+    name throttle;
+
+    use crate::matcher::NumericRange;
+    use crate::throttle::{parse_retry_after, HostLimiter, Throttle};
+    use chrono::Utc;
+    use futures::{future, Future};
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+    use tokio_timer::Delay;
+
+    fn limit(throttle: &Throttle) -> Option<usize> {
+        throttle.adaptive.lock().unwrap().limit
+    }
+
+    fn pause(throttle: &Throttle) -> Option<Duration> {
+        let paused_until = throttle.adaptive.lock().unwrap().paused_until?;
+        Some(paused_until.saturating_duration_since(Instant::now()))
+    }
+
+    fn throttle_burst(throttle: &Throttle) {
+        throttle.adaptive.lock().unwrap().last_adjustment = Instant::now().checked_sub(Duration::from_secs(2));
+        for _ in 0..3 {
+            throttle.observe(true, None);
+        }
+    }
+
+    fn peak_concurrency(limiter: &HostLimiter, hosts: &[&str]) -> HashMap<String, usize> {
+        let state = Arc::new(Mutex::new(HashMap::<String, (usize, usize)>::new()));
+        let requests = hosts
+            .iter()
+            .map(|host| {
+                let key = (*host).to_owned();
+                let state = state.clone();
+                limiter.run(host, future::lazy(move || {
+                    {
+                        let mut state = state.lock().unwrap();
+                        let (current, peak) = state.entry(key.clone()).or_insert((0, 0));
+                        *current += 1;
+                        *peak = (*peak).max(*current);
+                    }
+
+                    Delay::new(Instant::now() + Duration::from_millis(50))
+                        .map_err(|_| ())
+                        .map(move |_| state.lock().unwrap().get_mut(&key).unwrap().0 -= 1)
+                }))
+            })
+            .collect::<Vec<_>>();
+        let (tx, rx) = std::sync::mpsc::channel();
+        hyper::rt::run(future::join_all(requests).map(move |_| tx.send(()).unwrap()));
+        rx.recv().unwrap();
+
+        let state = state.lock().unwrap();
+        state.iter().map(|(host, (_, peak))| (host.clone(), *peak)).collect()
+    }
+
+    fn waits(throttle: &Throttle, count: usize) -> Vec<Duration> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let start = Instant::now();
+        let waits = (0..count)
+            .map(|_| throttle.wait().map(move |_| start.elapsed()))
+            .collect::<Vec<_>>();
+        let waits = future::join_all(waits).map(move |elapsed| tx.send(elapsed).unwrap());
+        hyper::rt::run(waits);
+        rx.recv().unwrap()
+    }
+
+    test space_rate_limited_slots() {
+        let elapsed = waits(&Throttle::new(20, None), 3);
+        assert!(elapsed[0] < Duration::from_millis(50));
+        assert!(elapsed[1] >= Duration::from_millis(50));
+        assert!(elapsed[2] >= Duration::from_millis(100));
+    }
+
+    test delay_requests() {
+        let elapsed = waits(&Throttle::new(0, NumericRange::parse("50")), 2);
+        assert!(elapsed.iter().all(|elapsed| *elapsed >= Duration::from_millis(50)));
+
+        let elapsed = waits(&Throttle::new(0, None), 2);
+        assert!(elapsed.iter().all(|elapsed| *elapsed < Duration::from_millis(50)));
+    }
+
+    test lower_and_restore_concurrency() {
+        let throttle = Throttle::new(0, None);
+        throttle.adaptive.lock().unwrap().peak = 8;
+
+        throttle_burst(&throttle);
+        assert_eq!(Some(4), limit(&throttle));
+        for _ in 0..3 {
+            throttle.observe(true, None);
+        }
+        assert_eq!(Some(4), limit(&throttle));
+
+        throttle_burst(&throttle);
+        assert_eq!(Some(2), limit(&throttle));
+        throttle_burst(&throttle);
+        assert_eq!(Some(1), limit(&throttle));
+        assert_eq!(None, pause(&throttle));
+
+        throttle_burst(&throttle);
+        assert_eq!(Some(1), limit(&throttle));
+        assert!(pause(&throttle).unwrap() > Duration::from_millis(500));
+
+        for expected in &[Some(2), Some(4), None] {
+            for _ in 0..19 {
+                throttle.observe(false, None);
+            }
+            assert_ne!(*expected, limit(&throttle));
+            throttle.observe(false, None);
+            assert_eq!(*expected, limit(&throttle));
+        }
+    }
+
+    test pause_on_retry_after() {
+        let throttle = Throttle::new(0, None);
+        throttle.observe(true, Some(Duration::from_secs(5)));
+        let paused = pause(&throttle).unwrap();
+        assert!(paused > Duration::from_secs(4) && paused <= Duration::from_secs(5));
+        assert_eq!(None, limit(&throttle));
+
+        let throttle = Throttle::new(0, None);
+        throttle.observe(true, Some(Duration::from_secs(3600)));
+        assert!(pause(&throttle).unwrap() <= Duration::from_secs(300));
+    }
+
+    test parse_retry_after_headers() {
+        assert_eq!(Some(Duration::from_secs(120)), parse_retry_after("Content-Type: text/html\nRetry-After: 120"));
+        assert_eq!(Some(Duration::from_secs(5)), parse_retry_after("retry-after:5"));
+        assert_eq!(None, parse_retry_after("Content-Type: text/html"));
+        assert_eq!(None, parse_retry_after("Retry-After: soon"));
+
+        let date = (Utc::now() + chrono::Duration::hours(1)).format("%a, %d %b %Y %H:%M:%S GMT");
+        let retry_after = parse_retry_after(&format!("Retry-After: {}", date)).unwrap();
+        assert!(retry_after > Duration::from_secs(3590) && retry_after <= Duration::from_secs(3600));
+        assert_eq!(None, parse_retry_after("Retry-After: Sun, 06 Nov 1994 08:49:37 GMT"));
+    }
+
+    test limit_requests_per_host() {
+        let peaks = peak_concurrency(&HostLimiter::new(1), &["a", "a", "b", "a", "b"]);
+        assert_eq!(Some(&1), peaks.get("a"));
+        assert_eq!(Some(&1), peaks.get("b"));
+
+        let peaks = peak_concurrency(&HostLimiter::new(2), &["a", "a", "a"]);
+        assert_eq!(Some(&2), peaks.get("a"));
+
+        let peaks = peak_concurrency(&HostLimiter::new(0), &["a", "a", "a"]);
+        assert_eq!(Some(&3), peaks.get("a"));
+    }
+}
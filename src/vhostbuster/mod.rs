@@ -1,34 +1,53 @@
-use futures::Stream;
+use futures::{sync::mpsc, Stream};
 use hyper::{
-    client::HttpConnector,
     rt::{self, Future},
     Body, Client, Request, StatusCode, Uri,
 };
 use hyper_tls::{self, HttpsConnector};
-use native_tls;
-use std::sync::{mpsc::Sender, Arc, Mutex};
+use std::collections::HashMap;
+use std::sync::mpsc::Sender;
 use std::sync::mpsc::channel;
 use indicatif::{ProgressBar, ProgressStyle};
-use std::{time::SystemTime};
+use std::time::SystemTime;
 
 pub mod result_processor;
 use result_processor::{SingleVhostScanResult, VhostScanResult};
 pub mod utils;
-use utils::{build_vhosts, save_vhost_results};
+use utils::{build_vhosts, count_vhosts, save_vhost_results};
+use crate::matcher::{format_headers, Matcher, NumericRange};
+use crate::output::{JsonlWriter, OutputFormat};
+use crate::resume::{is_interrupted, receive, Cursor, InFlight, ResumeState};
+use crate::proxy::{build_client, ProxyConnector, Replayer};
+use crate::retry::{send_request, RetryPolicy};
+use crate::targets::{self, interleave};
+use crate::throttle::{is_throttled, parse_retry_after, HostLimiter, Throttle, MAX_REQUEUES};
+use crate::wordlist::WordlistOptions;
 
 #[derive(Debug, Clone)]
 pub struct VhostBuster {
     pub n_threads: usize,
     pub ignore_certificate: bool,
-    pub ignore_strings: Vec<String>,
     pub user_agent: String,
     pub http_method: String,
     pub original_url: String,
-    pub wordlist_path: String,
+    pub targets: Vec<String>,
+    pub host_threads: usize,
+    pub wordlist_paths: Vec<String>,
+    pub wordlist_options: WordlistOptions,
     pub domain: String,
     pub no_progress_bar: bool,
     pub exit_on_connection_errors: bool,
     pub output: String,
+    pub output_format: OutputFormat,
+    pub matcher: Matcher,
+    pub proxy: String,
+    pub replay_proxy: String,
+    pub requeue: bool,
+    pub rate: u64,
+    pub delay: Option<NumericRange>,
+    pub timeout: u64,
+    pub retries: usize,
+    pub resume: ResumeState,
 }
 
 impl VhostBuster {
@@ -36,47 +55,40 @@ impl VhostBuster {
     fn make_request_future(
         &self,
         tx: Sender<SingleVhostScanResult>,
-        client: &Client<HttpsConnector<HttpConnector>>,
+        client: &Client<HttpsConnector<ProxyConnector>>,
+        target_url: String,
         url: Uri,
     ) -> impl Future<Item = (), Error = ()> {
         let tx_err = tx.clone();
-        let target = Arc::new(Mutex::new(SingleVhostScanResult {
+        let mut target = SingleVhostScanResult {
             vhost: url.to_string(),
+            target: target_url.clone(),
             status: StatusCode::default().to_string(),
             error: None,
             method: self.http_method.clone(),
-            ignored: false,
-        }));
-        let target_res = target.clone();
-        let mut target_err = (*target.lock().unwrap()).clone();
-        let mut request_builder = Request::builder();
-        let ignore_strings = self.ignore_strings.clone();
-        let request = request_builder
-            .header("User-Agent", &self.user_agent[..])
-            .method(&self.http_method[..])
-            .uri(&self.original_url)
-            .header("Host", url.host().unwrap())
-            .body(Body::from(""))
-            .expect("Request builder");
-    
-        client
-            .request(request)
-            .and_then(move |res| {
-                target.lock().unwrap().status = res.status().to_string();
-                res.into_body().concat2()
-            })
-            .and_then(move |body| {
-                let vec = body.iter().cloned().collect();
-                let body = String::from_utf8(vec).unwrap();
-                target_res.lock().unwrap().ignored = false;
-                for s in ignore_strings {
-                    if body.contains(&s) {
-                        target_res.lock().unwrap().ignored = true;
-                        break;
-                    }
-                }
-    
-                let target = Arc::try_unwrap(target_res).unwrap().into_inner().unwrap();
+            content_length: 0,
+            words: 0,
+            lines: 0,
+            response_time: 0,
+            headers: String::new(),
+            body: String::new(),
+        };
+        let mut target_err = target.clone();
+        let buster = self.clone();
+        let policy = RetryPolicy::new(self.timeout, self.retries);
+
+        send_request(client.clone(), policy, move || buster.build_request(&target_url, &url))
+            .and_then(move |(parts, body, elapsed)| {
+                target.status = parts.status.to_string();
+                target.headers = format_headers(&parts.headers);
+
+                target.content_length = body.len();
+                let body = String::from_utf8_lossy(&body);
+                target.words = body.split_whitespace().count();
+                target.lines = body.lines().count();
+                target.response_time = elapsed.as_millis() as u64;
+                target.body = body.into_owned();
+
                 tx.send(target).unwrap();
                 Ok(())
             })
@@ -86,45 +98,96 @@ impl VhostBuster {
                 Ok(())
             })
     }
+
+    fn targets(&self) -> Vec<String> {
+        if self.targets.is_empty() {
+            vec![self.original_url.clone()]
+        } else {
+            self.targets.clone()
+        }
+    }
+
+    fn build_request(&self, target: &str, url: &Uri) -> Request<Body> {
+        let mut request_builder = Request::builder();
+        request_builder
+            .header("User-Agent", &self.user_agent[..])
+            .method(&self.http_method[..])
+            .uri(target)
+            .header("Host", url.host().unwrap())
+            .body(Body::from(""))
+            .expect("Request builder")
+    }
     
     pub fn run(self) {
-        let mut current_numbers_of_request = 0;
+        let resume = self.resume.clone();
+        let mut current_numbers_of_request = resume.offset;
         let start_time = SystemTime::now();
         let output = self.output.clone();
+        let output_format = self.output_format;
+        let mut jsonl_writer = JsonlWriter::new(&self.output, self.output_format);
         let no_progress_bar = self.no_progress_bar;
         let exit_on_connection_errors = self.exit_on_connection_errors;
         let n_threads = self.n_threads;
         let (tx, rx) = channel::<SingleVhostScanResult>();
-        let mut tls_connector_builder = native_tls::TlsConnector::builder();
-        tls_connector_builder.danger_accept_invalid_certs(self.ignore_certificate);
-        let tls_connector = tls_connector_builder
-            .build()
-            .expect("TLS initialization failed");
-        let mut http_connector = HttpConnector::new(self.n_threads);
-        http_connector.enforce_http(false);
-        let https_connector = HttpsConnector::from((http_connector, tls_connector));
-        let client = Client::builder().build(https_connector);
+        let client = build_client(self.n_threads, self.ignore_certificate, &self.proxy);
+        let replayer = Replayer::new(self.n_threads, self.ignore_certificate, &self.replay_proxy);
+        let replay_buster = self.clone();
+        let throttle = Throttle::new(self.rate, self.delay.clone());
+        let controller = throttle.clone();
+        let requeue = self.requeue;
+        let limiter = HostLimiter::new(self.host_threads);
+        let (tx_requeue, rx_requeue) = mpsc::unbounded::<(String, Uri)>();
     
-        let vhosts = build_vhosts(&self.wordlist_path, &self.domain);
-        let total_numbers_of_request = vhosts.len();
+        let targets = self.targets();
+        let multiple_targets = targets.len() > 1;
+        let cursor = Cursor::default();
+        let vhosts = cursor
+            .track(interleave(
+                targets
+                    .iter()
+                    .map(|target| {
+                        let target = target.clone();
+                        let vhosts = build_vhosts(&self.wordlist_paths, &self.wordlist_options, &self.domain);
+                        Box::new(vhosts.map(move |vhost| (target.clone(), vhost)))
+                            as Box<dyn Iterator<Item = (String, Uri)> + Send>
+                    })
+                    .collect(),
+            ))
+            .skip(resume.offset);
+        let in_flight = InFlight::default();
+        let stream_in_flight = in_flight.clone();
+        let mut total_numbers_of_request =
+            count_vhosts(&self.wordlist_paths, &self.wordlist_options, &self.domain) * targets.len();
 
-        let mut result_processor = VhostScanResult::new();
+        let mut result_processor = VhostScanResult::new(self.matcher.clone());
+        for result in resume.results::<SingleVhostScanResult>() {
+            jsonl_writer.write(&result);
+            result_processor.results.push(result);
+        }
+        let mut failed_requests = Vec::new();
+        let mut requeued = HashMap::new();
         let bar = if self.no_progress_bar {
             ProgressBar::hidden()
         } else {
             ProgressBar::new(total_numbers_of_request as u64)
         };
+        bar.set_position(current_numbers_of_request as u64);
         bar.set_draw_delta(100);
         bar.set_style(ProgressStyle::default_bar()
             .template("{spinner} [{elapsed_precise}] {bar:40.red/white} {pos:>7}/{len:7} ETA: {eta_precise} req/s: {msg}")
             .progress_chars("#>-"));
         let stream = futures::stream::iter_ok(vhosts)
-            .map(move |url| self.make_request_future(tx.clone(), &client, url))
+            .select(rx_requeue)
+            .map(move |(target, url)| {
+                let host = targets::host(&target);
+                let request_future = self.make_request_future(tx.clone(), &client, target, url);
+                stream_in_flight.track(throttle.run(limiter.run(&host, request_future)))
+            })
             .buffer_unordered(n_threads)
             .for_each(Ok)
             .map_err(|err| eprintln!("Err {:?}", err));
     
-        rt::run(stream);
+        let _ = std::thread::spawn(move || rt::run(stream));
 
         while current_numbers_of_request != total_numbers_of_request {
             current_numbers_of_request = current_numbers_of_request + 1;
@@ -139,17 +202,15 @@ impl VhostBuster {
                 bar.set_message("warming up...")
             }
 
-            let msg = match rx.recv() {
-                Ok(msg) => msg,
-                Err(_err) => {
-                    error!("{:?}", _err);
-                    break;
-                }
+            let msg = match receive(&rx, &in_flight) {
+                Some(msg) => msg,
+                None => break,
             };
 
             match &msg.error {
                 Some(e) => {
                     error!("{} - {:?}", msg.vhost, e);
+                    failed_requests.push(msg.vhost.clone());
                     if current_numbers_of_request == 1 || exit_on_connection_errors
                     {
                         warn!("Check connectivity to the target");
@@ -161,6 +222,27 @@ impl VhostBuster {
                 None => (),
             }
 
+            let throttled = is_throttled(&msg.status);
+            controller.observe(throttled, parse_retry_after(&msg.headers));
+            if throttled && requeue {
+                let requeues = requeued
+                    .entry((msg.target.clone(), msg.vhost.clone()))
+                    .or_insert(0);
+                if *requeues < MAX_REQUEUES && !is_interrupted() {
+                    if let Ok(uri) = msg.vhost.parse::<Uri>() {
+                        *requeues += 1;
+                        debug!("requeueing {}", msg.vhost);
+                        total_numbers_of_request += 1;
+                        bar.set_length(total_numbers_of_request as u64);
+                        tx_requeue.unbounded_send((msg.target.clone(), uri)).unwrap();
+                        continue;
+                    }
+                }
+
+                failed_requests.push(msg.vhost.clone());
+                continue;
+            }
+
             let n_tabs = match msg.status.len() / 8 {
                 3 => 1,
                 2 => 2,
@@ -169,15 +251,25 @@ impl VhostBuster {
                 _ => 0,
             };
 
-            if !msg.ignored {
-                result_processor.maybe_add_result(msg.clone());
+            if result_processor.maybe_add_result(msg.clone()) {
+                jsonl_writer.write(&msg);
+                if let Ok(uri) = msg.vhost.parse::<Uri>() {
+                    replayer.replay(replay_buster.build_request(&msg.target, &uri));
+                }
+
+                let vhost = if multiple_targets {
+                    format!("{} ({})", msg.vhost, msg.target)
+                } else {
+                    msg.vhost.clone()
+                };
+
                 if no_progress_bar {
                     println!(
                         "{}\t{}{}{}",
                         msg.method,
                         msg.status,
                         "\t".repeat(n_tabs),
-                        msg.vhost
+                        vhost
                     );
                 } else {
                     bar.println(format!(
@@ -185,17 +277,31 @@ impl VhostBuster {
                         msg.method,
                         msg.status,
                         "\t".repeat(n_tabs),
-                        msg.vhost
+                        vhost
                     ));
                 }
             }
         }
 
         bar.finish();
+        replayer.finish();
+        if !failed_requests.is_empty() {
+            println!("{}", crate::banner::failed_requests(&failed_requests));
+        }
         println!("{}", crate::banner::ending_time());
 
-        if !output.is_empty() {
+        if !output.is_empty() && output_format == OutputFormat::Json {
             save_vhost_results(&output, &result_processor.results);
         }
+
+        if is_interrupted() {
+            let state = ResumeState {
+                offset: cursor.position(),
+                results: serde_json::to_value(&result_processor.results).unwrap(),
+                ..resume
+            };
+            state.save();
+            println!("{}", crate::banner::interrupted(&state.path));
+        }
     }
 }
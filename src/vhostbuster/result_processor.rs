@@ -1,29 +1,59 @@
 use serde::{Deserialize, Serialize};
 
+use crate::matcher::{MatchTarget, Matcher};
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SingleVhostScanResult {
     pub vhost: String,
+    pub target: String,
     pub status: String,
     pub method: String,
     pub error: Option<String>,
-    pub ignored: bool,
+    pub content_length: usize,
+    pub words: usize,
+    pub lines: usize,
+    pub response_time: u64,
+    #[serde(skip)]
+    pub headers: String,
+    #[serde(skip)]
+    pub body: String,
+}
+
+impl SingleVhostScanResult {
+    pub fn match_target(&self) -> MatchTarget<'_> {
+        MatchTarget {
+            status: &self.status,
+            content_length: self.content_length,
+            words: self.words,
+            lines: self.lines,
+            response_time: self.response_time,
+            headers: &self.headers,
+            body: &self.body,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct VhostScanResult {
     pub results: Vec<SingleVhostScanResult>,
+    matcher: Matcher,
 }
 
 impl VhostScanResult {
-    pub fn new() -> Self {
+    pub fn new(matcher: Matcher) -> Self {
         VhostScanResult {
             results: Vec::<SingleVhostScanResult>::new(),
+            matcher,
         }
     }
 
     pub fn maybe_add_result(&mut self, res: SingleVhostScanResult) -> bool {
         trace!("{:?}", res);
-        self.results.push(res);
-        true
+        if self.matcher.is_match(&res.match_target()) {
+            self.results.push(res);
+            return true;
+        }
+
+        false
     }
 }
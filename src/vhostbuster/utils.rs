@@ -1,29 +1,33 @@
-use std::{fs, fs::File, io::Write, path::Path, str};
+use std::{fs::File, io::Write, path::Path, str};
 
 use super::result_processor::SingleVhostScanResult;
+use crate::wordlist::{self, WordlistOptions};
 
-pub fn build_vhosts(wordlist_path: &str, url: &str) -> Vec<hyper::Uri> {
+pub fn build_vhosts(
+    wordlist_paths: &[String],
+    wordlist_options: &WordlistOptions,
+    url: &str,
+) -> impl Iterator<Item = hyper::Uri> {
     debug!("building urls");
-    let mut urls: Vec<hyper::Uri> = Vec::new();
-    let wordlist =
-        fs::read_to_string(wordlist_path).expect("Something went wrong reading the wordlist file");
-    let urls_iter = wordlist
-        .lines()
-        .filter(|word| !word.starts_with('#') && !word.starts_with(' '))
-        .map(|word| format!("{}.{}", word, url));
+    let url = url.to_owned();
 
-    for url in urls_iter {
-        match url.parse::<hyper::Uri>() {
-            Ok(v) => {
-                urls.push(v);
-            }
+    wordlist::merge(wordlist_paths, wordlist_options)
+        .map(move |word| format!("{}.{}", word, url))
+        .filter_map(|url| match url.parse::<hyper::Uri>() {
+            Ok(v) => Some(v),
             Err(e) => {
                 trace!("URI: {}", e);
+                None
             }
-        }
-    }
+        })
+}
 
-    urls
+pub fn count_vhosts(
+    wordlist_paths: &[String],
+    wordlist_options: &WordlistOptions,
+    url: &str,
+) -> usize {
+    build_vhosts(wordlist_paths, wordlist_options, url).count()
 }
 
 pub fn save_vhost_results(path: &str, results: &Vec<SingleVhostScanResult>) {
@@ -0,0 +1,380 @@
+use flate2::read::GzDecoder;
+use std::{
+    collections::HashSet,
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+
+use crate::generator::Generator;
+use crate::matcher::Pattern;
+use crate::rules::{self, Rule};
+
+mod spec;
+
+pub const STDIN: &str = "-";
+pub const MAX_GENERATED_WORDS: usize = 10_000_000;
+
+static STDIN_SPOOL: OnceLock<PathBuf> = OnceLock::new();
+
+type Words = Box<dyn Iterator<Item = String> + Send>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WordCase {
+    Lower,
+    Upper,
+    Capitalize,
+    All,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WordlistOptions {
+    pub dedup: bool,
+    pub case: Option<WordCase>,
+    pub prefix: String,
+    pub suffix: String,
+    pub min_length: usize,
+    pub max_length: Option<usize>,
+    pub include: Option<Pattern>,
+    pub exclude: Option<Pattern>,
+    pub rules: Vec<Rule>,
+}
+
+impl WordlistOptions {
+    pub fn accepts(&self, word: &str) -> bool {
+        let length = word.chars().count();
+
+        if length < self.min_length {
+            return false;
+        }
+        if let Some(max_length) = self.max_length {
+            if length > max_length {
+                return false;
+            }
+        }
+        if let Some(include) = &self.include {
+            if !include.is_match(word) {
+                return false;
+            }
+        }
+        if let Some(exclude) = &self.exclude {
+            if exclude.is_match(word) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    fn generated_count(&self, generator: &Generator) -> Option<usize> {
+        let one_to_one = self.rules.is_empty()
+            && self.include.is_none()
+            && self.exclude.is_none()
+            && !matches!(self.case, Some(WordCase::All));
+        if !one_to_one || (self.dedup && (self.case.is_some() || !generator.is_distinct())) {
+            return None;
+        }
+
+        generator.count_within(self.min_length, self.max_length)
+    }
+
+    pub fn transform(&self, word: String) -> Vec<String> {
+        let mutations = if self.rules.is_empty() {
+            vec![word]
+        } else {
+            self.rules
+                .iter()
+                .filter_map(|rule| rules::apply(rule, &word))
+                .filter(|mutation| !mutation.is_empty())
+                .collect()
+        };
+
+        let mut variants = mutations
+            .into_iter()
+            .flat_map(|mutation| self.cases(mutation))
+            .collect::<Vec<_>>();
+
+        let mut seen = HashSet::new();
+        variants.retain(|variant| seen.insert(variant.clone()));
+        variants
+            .into_iter()
+            .map(|variant| format!("{}{}{}", self.prefix, variant, self.suffix))
+            .collect()
+    }
+
+    fn cases(&self, word: String) -> Vec<String> {
+        match self.case {
+            None => vec![word],
+            Some(WordCase::Lower) => vec![word.to_lowercase()],
+            Some(WordCase::Upper) => vec![word.to_uppercase()],
+            Some(WordCase::Capitalize) => vec![capitalize(&word)],
+            Some(WordCase::All) => vec![
+                word.to_lowercase(),
+                word.to_uppercase(),
+                capitalize(&word),
+                word,
+            ],
+        }
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+        None => String::new(),
+    }
+}
+
+pub struct StdinSpool;
+
+impl Drop for StdinSpool {
+    fn drop(&mut self) {
+        remove_stdin_spool();
+    }
+}
+
+pub fn remove_stdin_spool() {
+    if let Some(path) = STDIN_SPOOL.get() {
+        let _ = fs::remove_file(path);
+    }
+}
+
+pub fn spool<R: Read>(reader: &mut R, path: &Path) -> io::Result<u64> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+
+    let mut file = options.open(path)?;
+    io::copy(reader, &mut file)
+}
+
+fn stdin_spool() -> &'static Path {
+    STDIN_SPOOL.get_or_init(|| {
+        let path = std::env::temp_dir().join(format!(
+            "rustbuster-stdin-{}-{:016x}",
+            std::process::id(),
+            rand::random::<u64>()
+        ));
+        let stdin = io::stdin();
+        spool(&mut stdin.lock(), &path).expect("Something went wrong reading the wordlist from stdin");
+        path
+    })
+}
+
+fn lines(wordlist_path: &str) -> Words {
+    if let Some(generator) = generator(wordlist_path) {
+        return generator.words();
+    }
+
+    let path = if wordlist_path == STDIN {
+        stdin_spool()
+    } else {
+        Path::new(wordlist_path)
+    };
+    let file = File::open(path).expect("Something went wrong reading the wordlist file");
+    let reader: Box<dyn BufRead + Send> = if wordlist_path.ends_with(".gz") {
+        Box::new(BufReader::new(GzDecoder::new(file)))
+    } else {
+        Box::new(BufReader::new(file))
+    };
+
+    Box::new(
+        reader
+            .split(b'\n')
+            .map_while(Result::ok)
+            .filter_map(|line| parse_word(&String::from_utf8_lossy(&line))),
+    )
+}
+
+fn generator(wordlist_path: &str) -> Option<Generator> {
+    if wordlist_path == STDIN || Path::new(wordlist_path).is_file() {
+        return None;
+    }
+
+    Generator::parse(wordlist_path).ok().flatten()
+}
+
+pub fn parse_word(line: &str) -> Option<String> {
+    let word = line.trim();
+    if word.is_empty() || word.starts_with('#') {
+        return None;
+    }
+
+    Some(word.to_owned())
+}
+
+pub fn words(wordlist_path: &str, options: &WordlistOptions) -> Words {
+    let filter_options = options.clone();
+    let transform_options = options.clone();
+    let dedup = options.dedup || !options.rules.is_empty();
+    let mut seen = HashSet::new();
+
+    Box::new(
+        lines(wordlist_path)
+            .filter(move |word| filter_options.accepts(word))
+            .flat_map(move |word| transform_options.transform(word))
+            .filter(move |word| !dedup || seen.insert(word.clone())),
+    )
+}
+
+pub fn merge(wordlist_paths: &[String], options: &WordlistOptions) -> impl Iterator<Item = String> {
+    let wordlist_paths = wordlist_paths.to_vec();
+    let options = options.clone();
+    let dedup = options.dedup;
+    let mut seen = HashSet::new();
+
+    wordlist_paths
+        .into_iter()
+        .flat_map(move |wordlist_path| words(&wordlist_path, &options))
+        .filter(move |word| !dedup || seen.insert(word.clone()))
+}
+
+pub fn count(wordlist_path: &str, options: &WordlistOptions) -> usize {
+    if let Some(generator) = generator(wordlist_path) {
+        if let Some(count) = options.generated_count(&generator) {
+            return count;
+        }
+    }
+
+    words(wordlist_path, options).count()
+}
+
+pub fn check_generator(wordlist_path: &str, options: &WordlistOptions) -> Result<(), String> {
+    let generator = match generator(wordlist_path) {
+        Some(v) => v,
+        None => return Ok(()),
+    };
+
+    if options.generated_count(&generator).is_some() || generator.count() <= MAX_GENERATED_WORDS {
+        return Ok(());
+    }
+
+    Err(format!(
+        "{} generates {} words, at most {} can be filtered, deduplicated or mutated",
+        wordlist_path,
+        generator.count(),
+        MAX_GENERATED_WORDS
+    ))
+}
+
+pub fn pitchfork(
+    wordlist_paths: &[String],
+    options: &WordlistOptions,
+) -> impl Iterator<Item = Vec<String>> {
+    let mut iterators = wordlist_paths
+        .iter()
+        .map(|wordlist_path| words(wordlist_path, options))
+        .collect::<Vec<_>>();
+
+    std::iter::from_fn(move || {
+        if iterators.is_empty() {
+            return None;
+        }
+
+        iterators.iter_mut().map(|iterator| iterator.next()).collect()
+    })
+}
+
+pub struct CartesianProduct {
+    wordlist_paths: Vec<String>,
+    options: WordlistOptions,
+    iterators: Vec<Words>,
+    current: Vec<String>,
+    offsets: Vec<usize>,
+    done: bool,
+}
+
+impl CartesianProduct {
+    pub fn new(wordlist_paths: &[String], options: &WordlistOptions) -> Self {
+        CartesianProduct {
+            wordlist_paths: wordlist_paths.to_vec(),
+            options: options.clone(),
+            iterators: Vec::new(),
+            current: Vec::new(),
+            offsets: vec![0; wordlist_paths.len()],
+            done: wordlist_paths.is_empty(),
+        }
+    }
+
+    pub fn starting_at(wordlist_paths: &[String], options: &WordlistOptions, index: usize) -> Self {
+        let mut product = CartesianProduct::new(wordlist_paths, options);
+        let mut remaining = index;
+
+        for (position, wordlist_path) in wordlist_paths.iter().enumerate().rev() {
+            let count = count(wordlist_path, options);
+            if count == 0 {
+                product.done = true;
+                return product;
+            }
+
+            product.offsets[position] = remaining % count;
+            remaining /= count;
+        }
+
+        if remaining > 0 {
+            product.done = true;
+        }
+
+        product
+    }
+
+    fn start(&mut self) -> bool {
+        for (wordlist_path, offset) in self.wordlist_paths.iter().zip(self.offsets.iter()) {
+            let mut iterator = words(wordlist_path, &self.options);
+            match iterator.nth(*offset) {
+                Some(word) => self.current.push(word),
+                None => return false,
+            }
+            self.iterators.push(iterator);
+        }
+
+        true
+    }
+
+    fn advance(&mut self) -> bool {
+        for index in (0..self.iterators.len()).rev() {
+            if let Some(word) = self.iterators[index].next() {
+                self.current[index] = word;
+                return true;
+            }
+
+            let mut iterator = words(&self.wordlist_paths[index], &self.options);
+            match iterator.next() {
+                Some(word) => self.current[index] = word,
+                None => return false,
+            }
+            self.iterators[index] = iterator;
+        }
+
+        false
+    }
+}
+
+impl Iterator for CartesianProduct {
+    type Item = Vec<String>;
+
+    fn next(&mut self) -> Option<Vec<String>> {
+        if self.done {
+            return None;
+        }
+
+        let has_next = if self.iterators.is_empty() {
+            self.start()
+        } else {
+            self.advance()
+        };
+
+        if !has_next {
+            self.done = true;
+            return None;
+        }
+
+        Some(self.current.clone())
+    }
+}
@@ -0,0 +1,177 @@
+use galvanic_test::test_suite;
+
+test_suite! {
+    name wordlist;
+
+    use crate::matcher::Pattern;
+    use crate::wordlist::{check_generator, count, merge, parse_word, spool, words, WordCase, WordlistOptions};
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    fn wordlist(name: &str, content: &[u8]) -> String {
+        let path = std::env::temp_dir().join(format!("rustbuster-{}-{}", std::process::id(), name));
+        std::fs::write(&path, content).unwrap();
+        path.to_str().unwrap().to_owned()
+    }
+
+    fn collect(path: &str, options: &WordlistOptions) -> Vec<String> {
+        words(path, options).collect()
+    }
+
+    test parse_words() {
+        assert_eq!(Some("admin".to_owned()), parse_word("admin\r"));
+        assert_eq!(Some("admin".to_owned()), parse_word("  admin \t"));
+        assert_eq!(Some("admin panel".to_owned()), parse_word("admin panel"));
+        assert_eq!(None, parse_word(""));
+        assert_eq!(None, parse_word(" \r"));
+        assert_eq!(None, parse_word("# comment"));
+        assert_eq!(None, parse_word("  #comment"));
+    }
+
+    test read_normalized_words() {
+        let path = wordlist("normalized", b"admin\r\n\r\n# comment\n  login \nadmin\n");
+        assert_eq!(vec!["admin", "login", "admin"], collect(&path, &WordlistOptions::default()));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    test read_invalid_utf8_words() {
+        let path = wordlist("invalid-utf8", b"admin\np\xe4ss\r\nlogin\n");
+        let words = collect(&path, &WordlistOptions::default());
+        assert_eq!(vec!["admin", "p\u{fffd}ss", "login"], words);
+        assert_eq!(3, count(&path, &WordlistOptions::default()));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    test read_spooled_stdin() {
+        let path = std::env::temp_dir().join(format!("rustbuster-{}-stdin", std::process::id()));
+        assert_eq!(23, spool(&mut &b"admin\r\n# comment\nlogin\n"[..], &path).unwrap());
+        let path = path.to_str().unwrap().to_owned();
+        assert_eq!(vec!["admin", "login"], collect(&path, &WordlistOptions::default()));
+        assert_eq!(vec!["admin", "login"], collect(&path, &WordlistOptions::default()));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    test spool_to_new_private_file() {
+        let path = std::env::temp_dir().join(format!("rustbuster-{}-spool", std::process::id()));
+        spool(&mut &b"admin\n"[..], &path).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(0o600, mode & 0o777);
+        }
+        assert!(spool(&mut &b"login\n"[..], &path).is_err());
+        assert_eq!(b"admin\n".to_vec(), std::fs::read(&path).unwrap());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    test read_gzipped_words() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"admin\nlogin\n").unwrap();
+        let path = wordlist("words.gz", &encoder.finish().unwrap());
+        assert_eq!(vec!["admin", "login"], collect(&path, &WordlistOptions::default()));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    test merge_wordlists() {
+        let first = wordlist("merge-first", b"admin\nlogin\n");
+        let second = wordlist("merge-second", b"login\nbackup\n");
+        let wordlist_paths = vec![first.clone(), second.clone()];
+        assert_eq!(
+            vec!["admin", "login", "login", "backup"],
+            merge(&wordlist_paths, &WordlistOptions::default()).collect::<Vec<_>>()
+        );
+        let options = WordlistOptions {
+            dedup: true,
+            ..WordlistOptions::default()
+        };
+        assert_eq!(vec!["admin", "login", "backup"], merge(&wordlist_paths, &options).collect::<Vec<_>>());
+        std::fs::remove_file(&first).unwrap();
+        std::fs::remove_file(&second).unwrap();
+    }
+
+    test generator_words() {
+        let options = WordlistOptions::default();
+        assert_eq!(vec![" ", "#"], collect("charset: #/1", &options));
+        assert_eq!(vec![" admin", "#root"], collect("list: admin;#root", &options));
+        assert_eq!(217_180_147_158, count("charset:a-z/1-8", &options));
+
+        let options = WordlistOptions {
+            max_length: Some(1),
+            ..WordlistOptions::default()
+        };
+        assert_eq!(3, count("charset:a-c/1-2", &options));
+    }
+
+    test count_generated_words() {
+        let options = WordlistOptions {
+            dedup: true,
+            prefix: "x".to_owned(),
+            min_length: 8,
+            ..WordlistOptions::default()
+        };
+        assert_eq!(208_827_064_576, count("charset:a-z/1-8", &options));
+        assert!(check_generator("charset:a-z/1-8", &options).is_ok());
+
+        let options = WordlistOptions {
+            dedup: true,
+            case: Some(WordCase::Lower),
+            ..WordlistOptions::default()
+        };
+        assert_eq!(2, count("list:admin;ADMIN", &WordlistOptions::default()));
+        assert_eq!(1, count("list:admin;ADMIN", &options));
+        assert!(check_generator("list:admin;ADMIN", &options).is_ok());
+        assert!(check_generator("charset:a-z/1-8", &options).is_err());
+        assert!(check_generator("charset:a-z/1-4", &options).is_ok());
+    }
+
+    test filter_lengths() {
+        let path = wordlist("lengths", b"a\nab\nabc\nabcd\n");
+        let options = WordlistOptions {
+            min_length: 2,
+            max_length: Some(3),
+            ..WordlistOptions::default()
+        };
+        assert_eq!(vec!["ab", "abc"], collect(&path, &options));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    test filter_patterns() {
+        let path = wordlist("patterns", b"admin\nadministrator\nlogin\nbackup\n");
+        let options = WordlistOptions {
+            include: Some(Pattern::new("^(admin|login)").unwrap()),
+            exclude: Some(Pattern::new("istrator$").unwrap()),
+            ..WordlistOptions::default()
+        };
+        assert_eq!(vec!["admin", "login"], collect(&path, &options));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    test dedup_words() {
+        let path = wordlist("dedup", b"admin\nlogin\nadmin\nAdmin\n");
+        let options = WordlistOptions {
+            dedup: true,
+            ..WordlistOptions::default()
+        };
+        assert_eq!(vec!["admin", "login", "Admin"], collect(&path, &options));
+        let options = WordlistOptions {
+            dedup: true,
+            case: Some(WordCase::Lower),
+            ..WordlistOptions::default()
+        };
+        assert_eq!(vec!["admin", "login"], collect(&path, &options));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    test transform_words() {
+        let path = wordlist("transform", b"admin\n");
+        let options = WordlistOptions {
+            case: Some(WordCase::All),
+            prefix: "/".to_owned(),
+            suffix: ".php".to_owned(),
+            ..WordlistOptions::default()
+        };
+        assert_eq!(vec!["/admin.php", "/ADMIN.php", "/Admin.php"], collect(&path, &options));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
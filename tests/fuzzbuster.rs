@@ -13,17 +13,31 @@ test_suite! {
                 user_agent: "ua".to_owned(),
                 http_headers: vec![("Header".to_owned(), "Value".to_owned())],
                 wordlist_paths: vec!["./examples/wordlist_short".to_owned()],
+                wordlist_keywords: vec![None],
+                wordlist_encoders: vec![],
+                wordlist_options: librustbuster::wordlist::WordlistOptions::default(),
                 url: "http://localhost/FUZZ".to_owned(),
-                include_status_codes: vec![],
-                ignore_status_codes: vec!["404".to_owned()],
-                include_body: vec![],
-                ignore_body: vec![],
+                targets: vec![],
+                host_threads: 0,
+                matcher: librustbuster::matcher::Matcher::default(),
                 no_progress_bar: true,
                 exit_on_connection_errors: false,
                 output: "".to_owned(),
+                output_format: librustbuster::output::OutputFormat::Json,
                 csrf_url: None,
                 csrf_regex: None,
                 csrf_headers: None,
+                auto_calibrate: false,
+                mode: librustbuster::fuzzbuster::FuzzMode::ClusterBomb,
+                default_value: "".to_owned(),
+                proxy: "".to_owned(),
+                replay_proxy: "".to_owned(),
+                requeue: false,
+                rate: 0,
+                delay: None,
+                timeout: 10000,
+                retries: 2,
+                resume: librustbuster::resume::ResumeState::default(),
             }
         }
     }
@@ -38,17 +52,31 @@ test_suite! {
                 user_agent: "ua".to_owned(),
                 http_headers: vec![("Header".to_owned(), "Value".to_owned())],
                 wordlist_paths: vec!["./examples/wordlist_short".to_owned(), "./examples/wordlist_short".to_owned()],
+                wordlist_keywords: vec![None, None],
+                wordlist_encoders: vec![],
+                wordlist_options: librustbuster::wordlist::WordlistOptions::default(),
                 url: "http://localhost/FUZZ/FUZZ".to_owned(),
-                include_status_codes: vec![],
-                ignore_status_codes: vec!["404".to_owned()],
-                include_body: vec![],
-                ignore_body: vec![],
+                targets: vec![],
+                host_threads: 0,
+                matcher: librustbuster::matcher::Matcher::default(),
                 no_progress_bar: true,
                 exit_on_connection_errors: false,
                 output: "".to_owned(),
+                output_format: librustbuster::output::OutputFormat::Json,
                 csrf_url: None,
                 csrf_regex: None,
                 csrf_headers: None,
+                auto_calibrate: false,
+                mode: librustbuster::fuzzbuster::FuzzMode::ClusterBomb,
+                default_value: "".to_owned(),
+                proxy: "".to_owned(),
+                replay_proxy: "".to_owned(),
+                requeue: false,
+                rate: 0,
+                delay: None,
+                timeout: 10000,
+                retries: 2,
+                resume: librustbuster::resume::ResumeState::default(),
             }
         }
     }
@@ -63,17 +91,31 @@ test_suite! {
                 user_agent: "ua".to_owned(),
                 http_headers: vec![("Header".to_owned(), "FUZZ".to_owned())],
                 wordlist_paths: vec!["./examples/wordlist_short".to_owned()],
+                wordlist_keywords: vec![None],
+                wordlist_encoders: vec![],
+                wordlist_options: librustbuster::wordlist::WordlistOptions::default(),
                 url: "http://localhost/".to_owned(),
-                include_status_codes: vec![],
-                ignore_status_codes: vec!["404".to_owned()],
-                include_body: vec![],
-                ignore_body: vec![],
+                targets: vec![],
+                host_threads: 0,
+                matcher: librustbuster::matcher::Matcher::default(),
                 no_progress_bar: true,
                 exit_on_connection_errors: false,
                 output: "".to_owned(),
+                output_format: librustbuster::output::OutputFormat::Json,
                 csrf_url: None,
                 csrf_regex: None,
                 csrf_headers: None,
+                auto_calibrate: false,
+                mode: librustbuster::fuzzbuster::FuzzMode::ClusterBomb,
+                default_value: "".to_owned(),
+                proxy: "".to_owned(),
+                replay_proxy: "".to_owned(),
+                requeue: false,
+                rate: 0,
+                delay: None,
+                timeout: 10000,
+                retries: 2,
+                resume: librustbuster::resume::ResumeState::default(),
             }
         }
     }
@@ -88,17 +130,31 @@ test_suite! {
                 user_agent: "ua".to_owned(),
                 http_headers: vec![("FUZZ".to_owned(), "FUZZ".to_owned())],
                 wordlist_paths: vec!["./examples/wordlist_short".to_owned(), "./examples/wordlist_short".to_owned()],
+                wordlist_keywords: vec![None, None],
+                wordlist_encoders: vec![],
+                wordlist_options: librustbuster::wordlist::WordlistOptions::default(),
                 url: "http://localhost/".to_owned(),
-                include_status_codes: vec![],
-                ignore_status_codes: vec!["404".to_owned()],
-                include_body: vec![],
-                ignore_body: vec![],
+                targets: vec![],
+                host_threads: 0,
+                matcher: librustbuster::matcher::Matcher::default(),
                 no_progress_bar: true,
                 exit_on_connection_errors: false,
                 output: "".to_owned(),
+                output_format: librustbuster::output::OutputFormat::Json,
                 csrf_url: None,
                 csrf_regex: None,
                 csrf_headers: None,
+                auto_calibrate: false,
+                mode: librustbuster::fuzzbuster::FuzzMode::ClusterBomb,
+                default_value: "".to_owned(),
+                proxy: "".to_owned(),
+                replay_proxy: "".to_owned(),
+                requeue: false,
+                rate: 0,
+                delay: None,
+                timeout: 10000,
+                retries: 2,
+                resume: librustbuster::resume::ResumeState::default(),
             }
         }
     }
@@ -113,17 +169,31 @@ test_suite! {
                 user_agent: "ua".to_owned(),
                 http_headers: vec![("Header".to_owned(), "Value".to_owned())],
                 wordlist_paths: vec!["./examples/wordlist_short".to_owned()],
+                wordlist_keywords: vec![None],
+                wordlist_encoders: vec![],
+                wordlist_options: librustbuster::wordlist::WordlistOptions::default(),
                 url: "http://localhost/".to_owned(),
-                include_status_codes: vec![],
-                ignore_status_codes: vec!["404".to_owned()],
-                include_body: vec![],
-                ignore_body: vec![],
+                targets: vec![],
+                host_threads: 0,
+                matcher: librustbuster::matcher::Matcher::default(),
                 no_progress_bar: true,
                 exit_on_connection_errors: false,
                 output: "".to_owned(),
+                output_format: librustbuster::output::OutputFormat::Json,
                 csrf_url: None,
                 csrf_regex: None,
                 csrf_headers: None,
+                auto_calibrate: false,
+                mode: librustbuster::fuzzbuster::FuzzMode::ClusterBomb,
+                default_value: "".to_owned(),
+                proxy: "".to_owned(),
+                replay_proxy: "".to_owned(),
+                requeue: false,
+                rate: 0,
+                delay: None,
+                timeout: 10000,
+                retries: 2,
+                resume: librustbuster::resume::ResumeState::default(),
             }
         }
     }
@@ -138,17 +208,31 @@ test_suite! {
                 user_agent: "ua".to_owned(),
                 http_headers: vec![("Header".to_owned(), "Value".to_owned())],
                 wordlist_paths: vec!["./examples/wordlist_short".to_owned(), "./examples/wordlist_short".to_owned()],
+                wordlist_keywords: vec![None, None],
+                wordlist_encoders: vec![],
+                wordlist_options: librustbuster::wordlist::WordlistOptions::default(),
                 url: "http://localhost/".to_owned(),
-                include_status_codes: vec![],
-                ignore_status_codes: vec!["404".to_owned()],
-                include_body: vec![],
-                ignore_body: vec![],
+                targets: vec![],
+                host_threads: 0,
+                matcher: librustbuster::matcher::Matcher::default(),
                 no_progress_bar: true,
                 exit_on_connection_errors: false,
                 output: "".to_owned(),
+                output_format: librustbuster::output::OutputFormat::Json,
                 csrf_url: None,
                 csrf_regex: None,
                 csrf_headers: None,
+                auto_calibrate: false,
+                mode: librustbuster::fuzzbuster::FuzzMode::ClusterBomb,
+                default_value: "".to_owned(),
+                proxy: "".to_owned(),
+                replay_proxy: "".to_owned(),
+                requeue: false,
+                rate: 0,
+                delay: None,
+                timeout: 10000,
+                retries: 2,
+                resume: librustbuster::resume::ResumeState::default(),
             }
         }
     }
@@ -161,7 +245,7 @@ test_suite! {
                 http_headers: vec![],
                 http_body: "CSRFCSRF".to_owned(),
                 user_agent: "ua".to_owned(),
-                payload: vec!["1".to_owned()],
+                payload: vec![("FUZZ".to_owned(), "1".to_owned())].into_iter().collect(),
                 csrf_uri: Some("http://localhost/".parse::<hyper::Uri>().unwrap()),
                 csrf_regex: Some("(\\w+)".to_owned()),
                 csrf_headers: None,
@@ -177,7 +261,7 @@ test_suite! {
                 http_headers: vec![("X-CSRF-Token".to_owned(), "CSRFCSRF".to_owned())],
                 http_body: "body".to_owned(),
                 user_agent: "ua".to_owned(),
-                payload: vec!["1".to_owned()],
+                payload: vec![("FUZZ".to_owned(), "1".to_owned())].into_iter().collect(),
                 csrf_uri: Some("http://localhost/".parse::<hyper::Uri>().unwrap()),
                 csrf_regex: Some("(\\w+)".to_owned()),
                 csrf_headers: None,
@@ -193,7 +277,7 @@ test_suite! {
                 http_headers: vec![],
                 http_body: "body".to_owned(),
                 user_agent: "ua".to_owned(),
-                payload: vec!["1".to_owned()],
+                payload: vec![("FUZZ".to_owned(), "1".to_owned())].into_iter().collect(),
                 csrf_uri: Some("http://localhost/".parse::<hyper::Uri>().unwrap()),
                 csrf_regex: Some("(\\w+)".to_owned()),
                 csrf_headers: None,
@@ -211,61 +295,61 @@ test_suite! {
     }
 
     test build_requests_fuzz_url_single(fuzzbuster_url_single()) {
-        let requests = fuzzbuster_url_single.val.build_requests();
+        let requests = fuzzbuster_url_single.val.build_requests().collect::<Vec<_>>();
         let expected: Vec<librustbuster::fuzzbuster::FuzzRequest> = vec![
-            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/1".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec!["1".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
-            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/2".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec!["2".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/1".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "1".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/2".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "2".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
         ];
         assert_eq!(expected, requests);
     }
 
     test build_requests_fuzz_url_multiple(fuzzbuster_url_multiple()) {
-        let requests = fuzzbuster_url_multiple.val.build_requests();
+        let requests = fuzzbuster_url_multiple.val.build_requests().collect::<Vec<_>>();
         let expected: Vec<librustbuster::fuzzbuster::FuzzRequest> = vec![
-            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/1/1".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec!["1".to_owned(), "1".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
-            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/1/2".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec!["1".to_owned(), "2".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
-            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/2/1".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec!["2".to_owned(), "1".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
-            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/2/2".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec!["2".to_owned(), "2".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None }
+            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/1/1".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "1".to_owned()), ("FUZZ2".to_owned(), "1".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/1/2".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "1".to_owned()), ("FUZZ2".to_owned(), "2".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/2/1".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "2".to_owned()), ("FUZZ2".to_owned(), "1".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/2/2".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "2".to_owned()), ("FUZZ2".to_owned(), "2".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None }
         ];
         assert_eq!(expected, requests);
     }
 
     test build_requests_fuzz_header_single(fuzzbuster_header_single()) {
-        let requests = fuzzbuster_header_single.val.build_requests();
+        let requests = fuzzbuster_header_single.val.build_requests().collect::<Vec<_>>();
         let expected: Vec<librustbuster::fuzzbuster::FuzzRequest> = vec![
-            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "1".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec!["1".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
-            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "2".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec!["2".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "1".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "1".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "2".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "2".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
         ];
         assert_eq!(expected, requests);
     }
 
     test build_requests_fuzz_header_multiple(fuzzbuster_header_multiple()) {
-        let requests = fuzzbuster_header_multiple.val.build_requests();
+        let requests = fuzzbuster_header_multiple.val.build_requests().collect::<Vec<_>>();
         let expected: Vec<librustbuster::fuzzbuster::FuzzRequest> = vec![
-            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("1".to_owned(), "1".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec!["1".to_owned(), "1".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
-            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("1".to_owned(), "2".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec!["1".to_owned(), "2".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
-            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("2".to_owned(), "1".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec!["2".to_owned(), "1".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
-            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("2".to_owned(), "2".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec!["2".to_owned(), "2".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("1".to_owned(), "1".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "1".to_owned()), ("FUZZ2".to_owned(), "1".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("1".to_owned(), "2".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "1".to_owned()), ("FUZZ2".to_owned(), "2".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("2".to_owned(), "1".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "2".to_owned()), ("FUZZ2".to_owned(), "1".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("2".to_owned(), "2".to_owned())], http_body: "body".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "2".to_owned()), ("FUZZ2".to_owned(), "2".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
         ];
         assert_eq!(expected, requests);
     }
 
     test build_requests_fuzz_body_single(fuzzbuster_body_single()) {
-        let requests = fuzzbuster_body_single.val.build_requests();
+        let requests = fuzzbuster_body_single.val.build_requests().collect::<Vec<_>>();
         let expected: Vec<librustbuster::fuzzbuster::FuzzRequest> = vec![
-            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "1".to_owned(), user_agent: "ua".to_owned(), payload: vec!["1".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
-            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "2".to_owned(), user_agent: "ua".to_owned(), payload: vec!["2".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "1".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "1".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "2".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "2".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
         ];
         assert_eq!(expected, requests);
     }
 
     test build_requests_fuzz_body_multiple(fuzzbuster_body_multiple()) {
-        let requests = fuzzbuster_body_multiple.val.build_requests();
+        let requests = fuzzbuster_body_multiple.val.build_requests().collect::<Vec<_>>();
         let expected: Vec<librustbuster::fuzzbuster::FuzzRequest> = vec![
-            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "1:1".to_owned(), user_agent: "ua".to_owned(), payload: vec!["1".to_owned(), "1".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
-            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "1:2".to_owned(), user_agent: "ua".to_owned(), payload: vec!["1".to_owned(), "2".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
-            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "2:1".to_owned(), user_agent: "ua".to_owned(), payload: vec!["2".to_owned(), "1".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
-            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "2:2".to_owned(), user_agent: "ua".to_owned(), payload: vec!["2".to_owned(), "2".to_owned()], csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "1:1".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "1".to_owned()), ("FUZZ2".to_owned(), "1".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "1:2".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "1".to_owned()), ("FUZZ2".to_owned(), "2".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "2:1".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "2".to_owned()), ("FUZZ2".to_owned(), "1".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
+            librustbuster::fuzzbuster::FuzzRequest { uri: "http://localhost/".parse::<hyper::Uri>().unwrap(), http_method: "GET".to_owned(), http_headers: vec![("Header".to_owned(), "Value".to_owned())], http_body: "2:2".to_owned(), user_agent: "ua".to_owned(), payload: vec![("FUZZ".to_owned(), "2".to_owned()), ("FUZZ2".to_owned(), "2".to_owned())].into_iter().collect(), csrf_uri: None, csrf_regex: None, csrf_headers: None },
         ];
         assert_eq!(expected, requests);
     }